@@ -0,0 +1,5 @@
+CREATE TABLE IF NOT EXISTS quadlet_meta (
+    file TEXT PRIMARY KEY,
+    tags TEXT NOT NULL DEFAULT '',
+    description TEXT
+);
@@ -0,0 +1,44 @@
+use super::middleware::AdminClaims;
+use crate::models::{AppError, AppState, AuditEntry, CustomResponse};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing, Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Entradas por página si no se indica `limit`
+const DEFAULT_AUDIT_LIMIT: i64 = 100;
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new().route("/", routing::get(read_audit_log))
+}
+
+#[derive(Deserialize)]
+pub struct AuditQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Registro de auditoría, de lo más reciente a lo más antiguo. Solo para administradores
+async fn read_audit_log(
+    State(app_state): State<Arc<AppState>>,
+    _admin: AdminClaims,
+    Query(query): Query<AuditQuery>,
+) -> Result<CustomResponse<Vec<AuditEntry>>, AppError> {
+    let limit = query.limit.unwrap_or(DEFAULT_AUDIT_LIMIT);
+    let offset = query.offset.unwrap_or(0);
+    if limit < 0 || offset < 0 {
+        return Err(AppError::bad_request(
+            "limit y offset no pueden ser negativos",
+        ));
+    }
+    let (entries, total) = AuditEntry::read_page(&app_state.pool, limit, offset).await?;
+    Ok(CustomResponse::paginated(
+        StatusCode::OK,
+        "audit",
+        entries,
+        total as usize,
+    ))
+}
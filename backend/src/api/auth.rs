@@ -2,50 +2,77 @@ use std::sync::Arc;
 
 use axum::{
     body,
-    extract::State,
+    extract::{Query, State},
     http::{header, StatusCode},
     response::{IntoResponse, Response},
-    routing, Json, Router,
+    routing, Extension, Json, Router,
 };
 use bcrypt::verify;
+use serde::Deserialize;
 use tracing::{debug, error};
 
 use axum_extra::extract::cookie::{Cookie, SameSite};
 use jsonwebtoken::{encode, EncodingKey, Header};
 
-use crate::models::{AppState, CustomResponse, NewUser, TokenClaims, User, UserPass};
+use super::middleware::AdminClaims;
+use crate::models::{
+    ApiResponse, AppError, AppState, CurrentUser, CustomResponse, ErrorResponse, NewUser, Role,
+    TokenClaims, User, UserPass,
+};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/login", routing::post(login))
         .route("/logout", routing::get(logout))
         .route("/register", routing::post(register))
+        .route("/users", routing::post(create_user))
+        .route("/me", routing::get(me))
 }
 
 pub fn api_user_router() -> Router<Arc<AppState>> {
     Router::new().route("/", routing::get(read))
 }
 
+/// Comprueba una contraseña en claro contra el hash bcrypt guardado. Un hash
+/// corrupto es un error del servidor, no una contraseña incorrecta
+fn check_password(password: &str, hash: &str) -> Result<bool, AppError> {
+    verify(password, hash).map_err(|e| {
+        error!("Invalid stored password hash: {}", e);
+        AppError::InternalServerError("Invalid stored password hash".to_string())
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/login",
+    tag = "auth",
+    request_body = UserPass,
+    responses(
+        (status = 200, description = "Token JWT en `data.token`", body = ApiResponse<Object>),
+        (status = 403, description = "Usuario o contraseña incorrectos"),
+    )
+)]
 pub async fn login(
     State(app_state): State<Arc<AppState>>,
     Json(user_pass): Json<UserPass>,
 ) -> impl IntoResponse {
     //) -> Result<Json<serde_json::Value>,(StatusCode, Json<serde_json::Value>)>{
-    tracing::info!("init login");
-    tracing::info!("User pass: {:?}", user_pass);
+    debug!("Login de {}", user_pass.username);
     let user = User::read_by_username(&app_state.pool, &user_pass.username)
         .await
         .map_err(|e| {
             let message = &format!("Error: {}", e);
-            CustomResponse::<()>::empty(StatusCode::FORBIDDEN, message)
+            CustomResponse::<()>::empty(StatusCode::FORBIDDEN, message).into_response()
         })?
         .ok_or_else(|| {
             let message = "Invalid name or password";
-            CustomResponse::empty(StatusCode::FORBIDDEN, message)
+            CustomResponse::<()>::empty(StatusCode::FORBIDDEN, message).into_response()
         })?;
-    if !verify(&user_pass.hashed_password, &user.hashed_password).unwrap() {
+    if !check_password(&user_pass.password, &user.hashed_password)
+        .map_err(IntoResponse::into_response)?
+    {
         let message = "Invalid name or password";
-        return Err(CustomResponse::empty(StatusCode::FORBIDDEN, message));
+        return Err(CustomResponse::<()>::empty(StatusCode::FORBIDDEN, message).into_response());
     }
 
     let now = chrono::Utc::now();
@@ -65,7 +92,7 @@ pub async fn login(
     )
     .map_err(|e| {
         let message = format!("Encoding JWT error: {}", e);
-        CustomResponse::empty(StatusCode::INTERNAL_SERVER_ERROR, &message)
+        CustomResponse::<()>::empty(StatusCode::INTERNAL_SERVER_ERROR, &message).into_response()
     })
     .map(|token| {
         let value = serde_json::json!({"token": token});
@@ -73,10 +100,83 @@ pub async fn login(
     })
 }
 
+/// Usuario y rol del token de la petición
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/me",
+    tag = "auth",
+    responses(
+        (status = 200, body = ApiResponse<CurrentUser>),
+        (status = 401, body = ErrorResponse),
+    )
+)]
+pub async fn me(
+    claims: Option<Extension<TokenClaims>>,
+) -> Result<CustomResponse<CurrentUser>, AppError> {
+    let Extension(claims) = claims.ok_or(AppError::Unauthorized)?;
+    Ok(CustomResponse::api(
+        StatusCode::OK,
+        "me",
+        CurrentUser {
+            username: claims.sub,
+            role: claims.role,
+        },
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/register",
+    tag = "auth",
+    request_body = NewUser,
+    responses(
+        (status = 201, description = "El usuario siempre se crea como viewer", body = ApiResponse<Object>),
+        (status = 400, description = "No se pudo crear el usuario"),
+    )
+)]
 pub async fn register(
     State(app_state): State<Arc<AppState>>,
     Json(user): Json<NewUser>,
 ) -> impl IntoResponse {
+    // El registro es anónimo: los administradores solo se crean con POST /auth/users
+    let user = NewUser {
+        role: Role::Viewer,
+        ..user
+    };
+    insert_user(&app_state, user).await
+}
+
+/// Crea un usuario con cualquier rol. Solo para administradores
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/users",
+    tag = "auth",
+    request_body = NewUser,
+    responses(
+        (status = 201, body = ApiResponse<Object>),
+        (status = 400, description = "No se pudo crear el usuario"),
+        (status = 401, body = ErrorResponse),
+        (status = 403, body = ErrorResponse),
+    )
+)]
+pub async fn create_user(
+    State(app_state): State<Arc<AppState>>,
+    AdminClaims(claims): AdminClaims,
+    Json(user): Json<NewUser>,
+) -> impl IntoResponse {
+    tracing::info!(
+        "{} crea el usuario {} ({})",
+        claims.sub,
+        user.username,
+        user.role
+    );
+    insert_user(&app_state, user).await
+}
+
+async fn insert_user(
+    app_state: &AppState,
+    user: NewUser,
+) -> CustomResponse<Option<serde_json::Value>> {
     debug!("User data: {:?}", user);
     match User::create(&app_state.pool, user).await {
         Ok(user) => {
@@ -97,13 +197,20 @@ pub async fn register(
     }
 }
 
-pub async fn logout() -> impl IntoResponse {
+#[utoipa::path(
+    get,
+    path = "/api/v1/auth/logout",
+    tag = "auth",
+    responses((status = 303, description = "Borra la cookie del token y redirige a `/`"))
+)]
+pub async fn logout(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
     debug!("Logout");
     let cookie = Cookie::build(("token", ""))
         .path("/")
         .max_age(cookie::time::Duration::ZERO)
         .same_site(SameSite::Lax)
         .http_only(true)
+        .secure(app_state.tls)
         .build();
 
     tracing::info!("The cookie: {}", cookie.to_string());
@@ -116,22 +223,314 @@ pub async fn logout() -> impl IntoResponse {
         .unwrap()
 }
 
-pub async fn read(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
-    match User::read_all(&app_state.pool).await {
-        Ok(values) => {
-            debug!("Users: {:?}", values);
-            CustomResponse::api(
-                StatusCode::OK,
-                "Users",
-                Some(serde_json::to_value(values).unwrap()),
+/// Usuarios por página si no se indica `limit`
+const DEFAULT_USERS_LIMIT: i64 = 100;
+
+#[derive(Deserialize)]
+pub struct UsersQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Listado de usuarios, sin sus contraseñas. Solo para administradores
+pub async fn read(
+    State(app_state): State<Arc<AppState>>,
+    _admin: AdminClaims,
+    Query(query): Query<UsersQuery>,
+) -> Result<CustomResponse<Vec<User>>, AppError> {
+    let limit = query.limit.unwrap_or(DEFAULT_USERS_LIMIT);
+    let offset = query.offset.unwrap_or(0);
+    if limit < 0 || offset < 0 {
+        return Err(AppError::bad_request(
+            "limit y offset no pueden ser negativos",
+        ));
+    }
+    let (users, total) = User::read_page(&app_state.pool, limit, offset)
+        .await
+        .map_err(|e| {
+            error!("Error reading users: {:?}", e);
+            e
+        })?;
+    debug!("Users: {} de {}", users.len(), total);
+    Ok(CustomResponse::paginated(
+        StatusCode::OK,
+        "Users",
+        users,
+        total as usize,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::authenticate;
+    use crate::models::Role;
+    use axum::middleware;
+    use tower::ServiceExt;
+
+    async fn get_me(token: Option<&str>) -> Response {
+        let state = Arc::new(AppState::for_tests().await);
+        let app = router()
+            .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+            .with_state(state);
+        let mut request = axum::http::Request::get("/me");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        app.oneshot(request.body(body::Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_me_with_valid_token() {
+        let secret = AppState::for_tests().await.secret;
+        let now = chrono::Utc::now().timestamp() as usize;
+        let claims = TokenClaims {
+            sub: "alice".to_string(),
+            role: Role::Viewer,
+            iat: now,
+            exp: now + 60,
+        };
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap();
+
+        let response = get_me(Some(&token)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(
+            body["data"],
+            serde_json::json!({"username": "alice", "role": "viewer"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_me_without_token() {
+        let response = get_me(None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_read_users_pages_without_hashes() {
+        let state = Arc::new(AppState::for_tests().await);
+        for username in ["alice", "bob", "carol"] {
+            User::create(
+                &state.pool,
+                NewUser {
+                    username: username.to_string(),
+                    password: format!("pass-of-{}", username),
+                    role: Role::Viewer,
+                },
             )
+            .await
+            .unwrap();
         }
-        Err(e) => {
-            error!("Error reading values: {:?}", e);
-            CustomResponse::empty(
-                StatusCode::BAD_REQUEST,
-                &format!("Error reading values: {}", e),
+        let query = UsersQuery {
+            limit: Some(2),
+            offset: Some(1),
+        };
+        let admin = TokenClaims {
+            sub: "alice".to_string(),
+            role: Role::Admin,
+            iat: 0,
+            exp: 0,
+        };
+        let response = read(State(state), AdminClaims(admin), Query(query))
+            .await
+            .unwrap()
+            .into_response();
+        let bytes = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(!text.contains("hash"), "{}", text);
+        let body: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(body["total"], 3);
+        let names: Vec<_> = body["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|user| user["username"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["bob", "carol"]);
+    }
+
+    async fn post_json(uri: &str, token: Option<&str>, body: serde_json::Value) -> Response {
+        let state = Arc::new(AppState::for_tests().await);
+        let app = router()
+            .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+            .with_state(state);
+        let mut request =
+            axum::http::Request::post(uri).header(header::CONTENT_TYPE, "application/json");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        app.oneshot(request.body(body::Body::from(body.to_string())).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn post_register(body: serde_json::Value) -> Response {
+        post_json("/register", None, body).await
+    }
+
+    fn token_for(role: Role, secret: &str) -> String {
+        let now = chrono::Utc::now().timestamp() as usize;
+        let claims = TokenClaims {
+            sub: "alice".to_string(),
+            role,
+            iat: now,
+            exp: now + 60,
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    async fn data(response: Response) -> serde_json::Value {
+        let bytes = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()["data"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_register_cannot_create_admin() {
+        let response = post_register(serde_json::json!({
+            "username": "eve",
+            "password": "x",
+            "role": "admin"
+        }))
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(data(response).await["role"], "viewer");
+
+        let admin = serde_json::json!({
+            "username": "eve",
+            "password": "x",
+            "role": "admin"
+        });
+        let response = post_json("/users", None, admin.clone()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let secret = AppState::for_tests().await.secret;
+        let viewer = token_for(Role::Viewer, &secret);
+        let response = post_json("/users", Some(&viewer), admin).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_admin_can_create_admin() {
+        let secret = AppState::for_tests().await.secret;
+        let token = token_for(Role::Admin, &secret);
+        let response = post_json(
+            "/users",
+            Some(&token),
+            serde_json::json!({
+                "username": "root2",
+                "password": "x",
+                "role": "admin"
+            }),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(data(response).await["role"], "admin");
+    }
+
+    #[tokio::test]
+    async fn test_created_user_can_log_in() {
+        let state = Arc::new(AppState::for_tests().await);
+        let token = token_for(Role::Admin, &state.secret);
+        let app = router()
+            .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+            .with_state(state);
+        let post = |uri: &str, body: serde_json::Value| {
+            axum::http::Request::post(uri)
+                .header(header::CONTENT_TYPE, "application/json")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(body::Body::from(body.to_string()))
+                .unwrap()
+        };
+        let user = serde_json::json!({
+            "username": "frank",
+            "password": "s3cr3t",
+            "role": "viewer"
+        });
+        let response = app.clone().oneshot(post("/users", user)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let login = |password: &str| {
+            post(
+                "/login",
+                serde_json::json!({"username": "frank", "password": password}),
             )
+        };
+        let response = app.clone().oneshot(login("s3cr3t")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(data(response).await["token"].is_string());
+        let response = app.oneshot(login("otra")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_register_rejects_unknown_role() {
+        let response = post_register(serde_json::json!({
+            "username": "mallory",
+            "password": "x",
+            "role": "superuser"
+        }))
+        .await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
+    #[tokio::test]
+    async fn test_register_with_valid_role() {
+        let response = post_register(serde_json::json!({
+            "username": "victor",
+            "password": "x",
+            "role": "viewer"
+        }))
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let bytes = body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["data"]["role"], "viewer");
+    }
+
+    #[test]
+    fn test_check_password() {
+        let hash = bcrypt::hash("s3cr3t", 4).unwrap();
+        assert!(check_password("s3cr3t", &hash).unwrap());
+        assert!(!check_password("otra", &hash).unwrap());
+    }
+
+    #[test]
+    fn test_check_password_with_corrupt_hash() {
+        let result = check_password("s3cr3t", "no-es-un-hash");
+        assert!(matches!(result, Err(AppError::InternalServerError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_logout_cookie_is_secure_with_tls() {
+        for tls in [false, true] {
+            let state = Arc::new(AppState {
+                tls,
+                ..AppState::for_tests().await
+            });
+            let response = logout(State(state)).await.into_response();
+            let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+            assert_eq!(cookie.contains("Secure"), tls, "{}", cookie);
         }
     }
 }
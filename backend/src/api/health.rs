@@ -1,11 +1,164 @@
-use crate::models::{AppState, CustomResponse};
-use axum::{http::StatusCode, response::IntoResponse, routing, Router};
-use std::sync::Arc;
+use crate::models::{AppState, CustomResponse, ServerConfig, Versions};
+use crate::system;
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing, Router};
+use sqlx::SqlitePool;
+use std::{future::Future, sync::Arc, time::Duration};
 
 pub fn router() -> Router<Arc<AppState>> {
-    Router::new().route("/", routing::get(check_health))
+    Router::new()
+        .route("/", routing::get(check_health))
+        .route("/ready", routing::get(check_ready))
+        .route("/config", routing::get(read_config))
+        .route("/versions", routing::get(read_versions))
 }
 
+/// Liveness: solo indica que el proceso responde, sin consultar dependencias
 async fn check_health() -> impl IntoResponse {
     CustomResponse::<()>::empty(StatusCode::OK, "🚀 Up and running")
 }
+
+/// Tiempo máximo para cada comprobación de dependencias
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Ejecuta una comprobación con timeout, devolviendo "ok" o el motivo del fallo
+async fn run_check<F, E>(check: F) -> Result<&'static str, String>
+where
+    F: Future<Output = Result<(), E>>,
+    E: std::fmt::Display,
+{
+    match tokio::time::timeout(CHECK_TIMEOUT, check).await {
+        Ok(Ok(())) => Ok("ok"),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("timeout".to_string()),
+    }
+}
+
+async fn check_database(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT 1").execute(pool).await.map(|_| ())
+}
+
+async fn check_dbus(app_state: &AppState) -> zbus::Result<()> {
+    let conn = app_state.bus.connection().await?;
+    conn.call_method(
+        Some("org.freedesktop.DBus"),
+        "/org/freedesktop/DBus",
+        Some("org.freedesktop.DBus.Peer"),
+        "Ping",
+        &(),
+    )
+    .await
+    .map(|_| ())
+}
+
+/// Readiness: comprueba la base de datos, el bus de D-Bus y el generador de quadlets
+async fn check_ready(State(app_state): State<Arc<AppState>>) -> impl IntoResponse {
+    let generator = app_state
+        .quadlet_generator
+        .as_ref()
+        .map(|path| path.display().to_string());
+    let checks = [
+        ("database", run_check(check_database(&app_state.pool)).await),
+        ("dbus", run_check(check_dbus(&app_state)).await),
+        (
+            "quadlet_generator",
+            generator
+                .as_ref()
+                .map(|_| "ok")
+                .ok_or_else(|| "not found".to_string()),
+        ),
+    ];
+
+    let failed: Vec<&str> = checks
+        .iter()
+        .filter(|(_, result)| result.is_err())
+        .map(|(name, _)| *name)
+        .collect();
+    let (status, message) = if failed.is_empty() {
+        (StatusCode::OK, "Ready".to_string())
+    } else {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("Not ready: {}", failed.join(", ")),
+        )
+    };
+    let checks: serde_json::Map<String, serde_json::Value> = checks
+        .into_iter()
+        .map(|(name, result)| {
+            let value = match result {
+                Ok(ok) => ok.to_string(),
+                Err(e) => e,
+            };
+            (name.to_string(), serde_json::Value::String(value))
+        })
+        .collect();
+    CustomResponse::api(
+        status,
+        &message,
+        serde_json::json!({ "quadlet_generator": generator, "checks": checks }),
+    )
+}
+
+/// Capacidades del servidor para que el frontend adapte lo que muestra
+async fn read_config(State(app_state): State<Arc<AppState>>) -> CustomResponse<ServerConfig> {
+    let config = ServerConfig::from_state(&app_state, system::journalctl_available());
+    CustomResponse::api(StatusCode::OK, "config", config)
+}
+
+/// Versiones de Quadly, podman y systemd, para diagnosticar problemas
+async fn read_versions() -> CustomResponse<Versions> {
+    CustomResponse::api(StatusCode::OK, "versions", system::get_versions().await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn ready_body(state: Arc<AppState>) -> (StatusCode, serde_json::Value) {
+        let response = check_ready(State(state)).await.into_response();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_ready_reports_each_dependency() {
+        let state = Arc::new(AppState::for_tests().await);
+        let (_, body) = ready_body(state).await;
+        let checks = &body["data"]["checks"];
+        assert_eq!(checks["database"], "ok");
+        assert!(checks["dbus"].is_string());
+        // Sin generador no estamos listos
+        assert_eq!(checks["quadlet_generator"], "not found");
+    }
+
+    #[tokio::test]
+    async fn test_ready_degraded_when_database_is_closed() {
+        let mut state = AppState::for_tests().await;
+        state.quadlet_generator = Some("/usr/libexec/podman/quadlet".into());
+        state.pool.close().await;
+
+        let (status, body) = ready_body(Arc::new(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_ne!(body["data"]["checks"]["database"], "ok");
+        assert!(body["message"].as_str().unwrap().contains("database"));
+    }
+
+    #[tokio::test]
+    async fn test_read_config() {
+        let state = Arc::new(AppState::for_tests().await);
+        let response = read_config(State(state)).await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let config: ServerConfig = serde_json::from_value(body["data"].clone()).unwrap();
+        assert_eq!(config.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(config.scope, crate::models::SystemdScope::User);
+        assert!(config.features.sse);
+        assert!(!config.features.system_bus);
+    }
+}
@@ -0,0 +1,295 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{FromRequestParts, OriginalUri, Request, State},
+    http::{header, request::Parts, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::cookie::CookieJar;
+use tracing::{debug, error, warn};
+
+use crate::models::{AccessLogEntry, AppError, AppState, TokenClaims};
+use crate::system::InFlight;
+
+/// Obtiene el token de la cabecera `Authorization: Bearer` o de la cookie `token`
+fn extract_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string)
+        .or_else(|| {
+            CookieJar::from_headers(headers)
+                .get("token")
+                .map(|cookie| cookie.value().to_string())
+        })
+}
+
+/// Valida el token de la petición (si lo hay) y deja los `TokenClaims` en las extensiones.
+/// No rechaza peticiones anónimas: eso es responsabilidad de cada handler
+pub async fn authenticate(
+    State(app_state): State<Arc<AppState>>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    if let Some(token) = extract_token(req.headers()) {
+        match TokenClaims::decode(&token, &app_state.secret) {
+            Ok(claims) => {
+                tracing::Span::current().record("sub", claims.sub.as_str());
+                req.extensions_mut().insert(claims);
+            }
+            Err(e) => debug!("Invalid token: {}", e),
+        }
+    }
+    next.run(req).await
+}
+
+/// Registra las peticiones autenticadas en la tabla `access_log` si `ACCESS_LOG` está activo.
+/// Los health checks se excluyen para no llenar la tabla
+pub async fn access_log(
+    State(app_state): State<Arc<AppState>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !app_state.access_log || req.uri().path().starts_with("/health") {
+        return next.run(req).await;
+    }
+    let username = req
+        .extensions()
+        .get::<TokenClaims>()
+        .map(|claims| claims.sub.clone());
+    let method = req.method().to_string();
+    let path = req
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| req.uri().path().to_string());
+
+    let response = next.run(req).await;
+
+    if let Some(username) = username {
+        let status = response.status().as_u16();
+        if let Err(e) =
+            AccessLogEntry::create(&app_state.pool, &username, &method, &path, status).await
+        {
+            error!("Error writing access log: {}", e);
+        }
+    }
+    response
+}
+
+/// Extractor para los handlers que solo puede usar un administrador. Usa los
+/// `TokenClaims` que deja `authenticate`: sin token responde 401 y con otro rol 403
+pub struct AdminClaims(pub TokenClaims);
+
+impl<S: Sync> FromRequestParts<S> for AdminClaims {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let claims = parts
+            .extensions
+            .get::<TokenClaims>()
+            .cloned()
+            .ok_or(AppError::Unauthorized)?;
+        if !claims.is_admin() {
+            return Err(AppError::Forbidden);
+        }
+        Ok(AdminClaims(claims))
+    }
+}
+
+/// Tamaño máximo del cuerpo de las peticiones si no se indica `MAX_BODY_BYTES`
+pub const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+/// Tamaño máximo del cuerpo de las peticiones, de `MAX_BODY_BYTES`
+pub fn max_body_bytes() -> usize {
+    parse_max_body_bytes(std::env::var("MAX_BODY_BYTES").ok().as_deref())
+}
+
+fn parse_max_body_bytes(value: Option<&str>) -> usize {
+    let Some(value) = value else {
+        return DEFAULT_MAX_BODY_BYTES;
+    };
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|bytes| *bytes > 0)
+        .unwrap_or_else(|| {
+            warn!(
+                "MAX_BODY_BYTES '{}' no es un tamaño válido, usando {}",
+                value, DEFAULT_MAX_BODY_BYTES
+            );
+            DEFAULT_MAX_BODY_BYTES
+        })
+}
+
+/// Cambia el 413 en texto plano con el que `RequestBodyLimitLayer` o los extractores
+/// rechazan un cuerpo demasiado grande por un error de la API
+pub async fn payload_too_large(req: Request, next: Next) -> Response {
+    let response = next.run(req).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|value| value.as_bytes().starts_with(b"application/json"));
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE || is_json {
+        return response;
+    }
+    AppError::PayloadTooLarge(format!(
+        "El cuerpo supera el máximo de {} bytes",
+        max_body_bytes()
+    ))
+    .into_response()
+}
+
+/// Cuenta las peticiones en curso para el apagado ordenado
+pub async fn track_in_flight(
+    State(in_flight): State<InFlight>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let _guard = in_flight.enter();
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Role;
+    use axum::{body::Body, middleware, routing, Router};
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use tower::ServiceExt;
+
+    fn app(state: Arc<AppState>) -> Router {
+        Router::new()
+            .route("/quadlets/ping", routing::get(|| async { "pong" }))
+            .route("/health/", routing::get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(state.clone(), access_log))
+            .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+            .with_state(state)
+    }
+
+    fn token_for(username: &str, secret: &str) -> String {
+        token_with_role(username, Role::Admin, secret)
+    }
+
+    fn token_with_role(username: &str, role: Role, secret: &str) -> String {
+        let now = chrono::Utc::now().timestamp() as usize;
+        let claims = TokenClaims {
+            sub: username.to_string(),
+            role,
+            iat: now,
+            exp: now + 60,
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(secret.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    async fn entries(state: &AppState) -> Vec<AccessLogEntry> {
+        sqlx::query_as::<_, AccessLogEntry>("SELECT * FROM access_log")
+            .fetch_all(&state.pool)
+            .await
+            .unwrap()
+    }
+
+    fn get(uri: &str, token: Option<&str>) -> Request {
+        let mut builder = Request::builder().uri(uri);
+        if let Some(token) = token {
+            builder = builder.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_authenticated_request_is_logged() {
+        let state = Arc::new(AppState {
+            access_log: true,
+            ..AppState::for_tests().await
+        });
+        let token = token_for("alice", &state.secret);
+
+        let response = app(state.clone())
+            .oneshot(get("/quadlets/ping", Some(&token)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+
+        let entries = entries(&state).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].username, "alice");
+        assert_eq!(entries[0].method, "GET");
+        assert_eq!(entries[0].path, "/quadlets/ping");
+        assert_eq!(entries[0].status, 200);
+    }
+
+    #[tokio::test]
+    async fn test_anonymous_and_health_requests_are_not_logged() {
+        let state = Arc::new(AppState {
+            access_log: true,
+            ..AppState::for_tests().await
+        });
+        let token = token_for("alice", &state.secret);
+
+        app(state.clone())
+            .oneshot(get("/quadlets/ping", None))
+            .await
+            .unwrap();
+        app(state.clone())
+            .oneshot(get("/health/", Some(&token)))
+            .await
+            .unwrap();
+
+        assert!(entries(&state).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_access_log_disabled() {
+        let state = Arc::new(AppState::for_tests().await);
+        let token = token_for("alice", &state.secret);
+
+        app(state.clone())
+            .oneshot(get("/quadlets/ping", Some(&token)))
+            .await
+            .unwrap();
+
+        assert!(entries(&state).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_admin_guard() {
+        let state = Arc::new(AppState::for_tests().await);
+        let admin = token_for("alice", &state.secret);
+        let viewer = token_with_role("bob", Role::Viewer, &state.secret);
+        let app = || {
+            Router::new()
+                .route(
+                    "/admin",
+                    routing::get(|AdminClaims(claims): AdminClaims| async move { claims.sub }),
+                )
+                .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+                .with_state(state.clone())
+        };
+
+        for (token, status) in [(Some(&admin), 200), (Some(&viewer), 403), (None, 401)] {
+            let response = app()
+                .oneshot(get("/admin", token.map(String::as_str)))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), status);
+        }
+    }
+
+    #[test]
+    fn test_parse_max_body_bytes() {
+        assert_eq!(parse_max_body_bytes(None), DEFAULT_MAX_BODY_BYTES);
+        assert_eq!(parse_max_body_bytes(Some("4096")), 4096);
+        for invalid in ["", "0", "-1", "1MiB"] {
+            assert_eq!(parse_max_body_bytes(Some(invalid)), DEFAULT_MAX_BODY_BYTES);
+        }
+    }
+}
@@ -1,6 +1,12 @@
+mod audit;
 mod auth;
 mod quadlet;
 mod health;
+mod middleware;
+mod openapi;
+mod pagination;
+mod static_files;
+mod trace;
 
 use crate::models::CustomResponse;
 use axum::{http::StatusCode, response::IntoResponse};
@@ -8,6 +14,12 @@ use axum::{http::StatusCode, response::IntoResponse};
 pub use quadlet::router as quadlet_router;
 pub use health::router as health_router;
 pub use auth::router as auth_router;
+pub use auth::api_user_router as user_router;
+pub use audit::router as audit_router;
+pub use static_files::static_files;
+pub use trace::{on_request, on_response, request_span};
+pub use middleware::{access_log, authenticate, track_in_flight};
+pub use openapi::openapi_json;
 
 pub async fn fallback_404() -> impl IntoResponse {
     CustomResponse::<()>::empty( StatusCode::NOT_FOUND, "Not found")
@@ -0,0 +1,74 @@
+use super::{auth, quadlet};
+use axum::Json;
+use utoipa::OpenApi;
+
+/// Documento OpenAPI 3 de los endpoints de quadlets y de autenticación
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Quadly API"),
+    paths(
+        auth::login,
+        auth::logout,
+        auth::register,
+        auth::create_user,
+        auth::me,
+        quadlet::read_quadlets,
+        quadlet::read_quadlet,
+        quadlet::save_quadlet,
+        quadlet::delete_quadlet,
+        quadlet::run_action,
+        quadlet::get_quadlet_logs,
+        quadlet::read_status_detail,
+        quadlet::discover_quadlets,
+    ),
+    components(schemas(
+        crate::models::ErrorResponse,
+        crate::models::QuadletInfo,
+        crate::models::Quadlet,
+        crate::models::UnitAction,
+    )),
+    tags(
+        (name = "quadlets", description = "Gestión de los ficheros quadlet y de sus unidades"),
+        (name = "auth", description = "Login y usuarios"),
+    )
+)]
+struct ApiDoc;
+
+/// Sirve el documento OpenAPI. No requiere autenticación
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openapi_lists_paths_and_schemas() {
+        let json = ApiDoc::openapi().to_json().unwrap();
+        let doc: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(doc["openapi"].as_str().unwrap().starts_with("3."));
+        for path in [
+            "/api/v1/auth/login",
+            "/api/v1/auth/me",
+            "/api/v1/quadlets/{extension}",
+            "/api/v1/quadlets/{extension}/{name}",
+            "/api/v1/quadlets/{extension}/{name}/action",
+            "/api/v1/quadlets/discover",
+        ] {
+            assert!(doc["paths"].get(path).is_some(), "falta {}", path);
+        }
+        let item = &doc["paths"]["/api/v1/quadlets/{extension}/{name}"];
+        assert!(
+            item.get("get").is_some() && item.get("post").is_some() && item.get("delete").is_some()
+        );
+        let schemas = doc["components"]["schemas"].as_object().unwrap();
+        for schema in [
+            "ErrorResponse",
+            "QuadletInfo",
+            "CustomResponse_Vec_QuadletInfo",
+        ] {
+            assert!(schemas.contains_key(schema), "falta {}", schema);
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::models::{AppError, Quadlet, QuadletInfo, QuadletStatus};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+/// Criterio de ordenación de los listados
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SortKey {
+    #[default]
+    Name,
+    Status,
+    /// Los modificados más recientemente primero
+    Modified,
+}
+
+impl SortKey {
+    fn parse(value: Option<&str>) -> Result<Self, AppError> {
+        match value {
+            None | Some("name") => Ok(SortKey::Name),
+            Some("status") => Ok(SortKey::Status),
+            Some("modified") => Ok(SortKey::Modified),
+            Some(other) => Err(AppError::BadRequest(format!(
+                "Criterio de ordenación no válido: '{}' (usa 'name', 'status' o 'modified')",
+                other
+            ))),
+        }
+    }
+}
+
+/// Elementos de un listado que se pueden ordenar por nombre, estado y fecha de modificación
+pub trait Sortable {
+    fn sort_name(&self) -> &str;
+    fn sort_status(&self) -> Option<QuadletStatus>;
+    fn sort_modified(&self) -> Option<DateTime<Utc>>;
+}
+
+impl Sortable for Quadlet {
+    fn sort_name(&self) -> &str {
+        &self.name
+    }
+    fn sort_status(&self) -> Option<QuadletStatus> {
+        self.status
+    }
+    fn sort_modified(&self) -> Option<DateTime<Utc>> {
+        self.modified
+    }
+}
+
+impl Sortable for QuadletInfo {
+    fn sort_name(&self) -> &str {
+        &self.name
+    }
+    fn sort_status(&self) -> Option<QuadletStatus> {
+        self.status
+    }
+    fn sort_modified(&self) -> Option<DateTime<Utc>> {
+        self.modified
+    }
+}
+
+/// Parámetros de paginación y ordenación comunes a los listados
+#[derive(Debug, Default, Clone, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageQuery {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// "name" (por defecto), "status" o "modified"
+    pub sort: Option<String>,
+}
+
+impl PageQuery {
+    /// Ordena y recorta `items`. Devuelve la página pedida y el total de elementos.
+    /// Se ordena siempre, por nombre por defecto, para que las páginas sean estables
+    pub fn apply<T: Sortable>(&self, mut items: Vec<T>) -> Result<(Vec<T>, usize), AppError> {
+        match SortKey::parse(self.sort.as_deref())? {
+            SortKey::Name => items.sort_by(|a, b| a.sort_name().cmp(b.sort_name())),
+            SortKey::Status => items.sort_by(|a, b| {
+                a.sort_status()
+                    .cmp(&b.sort_status())
+                    .then_with(|| a.sort_name().cmp(b.sort_name()))
+            }),
+            // Sin fecha (`None`) quedan al final
+            SortKey::Modified => items.sort_by(|a, b| {
+                b.sort_modified()
+                    .cmp(&a.sort_modified())
+                    .then_with(|| a.sort_name().cmp(b.sort_name()))
+            }),
+        }
+        let total = items.len();
+        let page = items
+            .into_iter()
+            .skip(self.offset.unwrap_or(0))
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+        Ok((page, total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::QuadletType;
+
+    fn info(name: &str, status: QuadletStatus) -> QuadletInfo {
+        QuadletInfo {
+            name: name.to_string(),
+            kind: QuadletType::Container,
+            status: Some(status),
+            modified: None,
+            health: None,
+            enablement: None,
+            meta: None,
+        }
+    }
+
+    fn sample() -> Vec<QuadletInfo> {
+        vec![
+            info("web", QuadletStatus::Failed),
+            info("db", QuadletStatus::Active),
+            info("cache", QuadletStatus::Inactive),
+            info("api", QuadletStatus::Active),
+        ]
+    }
+
+    fn names(items: &[QuadletInfo]) -> Vec<&str> {
+        items.iter().map(|q| q.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_defaults_to_name_ascending() {
+        let (page, total) = PageQuery::default().apply(sample()).unwrap();
+        assert_eq!(total, 4);
+        assert_eq!(names(&page), vec!["api", "cache", "db", "web"]);
+    }
+
+    #[test]
+    fn test_sort_by_status_then_name() {
+        let query = PageQuery {
+            sort: Some("status".to_string()),
+            ..Default::default()
+        };
+        let (page, _) = query.apply(sample()).unwrap();
+        assert_eq!(names(&page), vec!["api", "db", "cache", "web"]);
+    }
+
+    #[test]
+    fn test_limit_and_offset() {
+        let query = PageQuery {
+            limit: Some(2),
+            offset: Some(1),
+            sort: None,
+        };
+        let (page, total) = query.apply(sample()).unwrap();
+        assert_eq!(total, 4);
+        assert_eq!(names(&page), vec!["cache", "db"]);
+    }
+
+    #[test]
+    fn test_offset_past_the_end_is_empty() {
+        let query = PageQuery {
+            limit: Some(10),
+            offset: Some(50),
+            sort: None,
+        };
+        let (page, total) = query.apply(sample()).unwrap();
+        assert!(page.is_empty());
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn test_invalid_sort_key() {
+        let query = PageQuery {
+            sort: Some("size".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            query.apply(sample()),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+}
@@ -1,13 +1,41 @@
-use crate::models::{AppState, CustomResponse, Quadlet, QuadletInfo, QuadletStatus, QuadletType};
+use super::middleware::{max_body_bytes, payload_too_large, AdminClaims};
+use super::pagination::PageQuery;
+use crate::core;
+use crate::models::{
+    content_etag, dir_writable, file_modified, get_quadlet_dir, get_unit_dir,
+    validate_quadlet_name, ActionPreview, ApiResponse, AppError, AppState, ApplyResult, ApplyStep,
+    AuditEntry, BulkActionResult, CustomResponse, ErrorResponse, ImagePull, OrphanUnit, Quadlet,
+    QuadletDiff, QuadletGraph, QuadletInfo, QuadletLint, QuadletMeta, QuadletStatus,
+    QuadletSummary, QuadletTemplate, QuadletType, StatusDetail, SystemdScope, TokenClaims,
+    UnitAction, UnitErrors, UnitResources, ValidationError,
+};
 use crate::system;
 use axum::{
-    extract::{Path, Query},
-    http::StatusCode,
-    response::IntoResponse,
-    routing, Json, Router,
+    body::{Body, Bytes},
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        DefaultBodyLimit, Path, Query, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    middleware,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+    routing, Extension, Json, Router,
 };
+use futures_util::{Stream, StreamExt, TryStreamExt};
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use tokio::process::Child;
+use tokio::sync::{
+    broadcast::{self, error::RecvError},
+    mpsc,
+};
+use tower_http::limit::RequestBodyLimitLayer;
+use tracing::{error, info, warn};
+use utoipa::{IntoParams, ToSchema};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -15,155 +43,3275 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/{extension}/{name}", routing::get(read_quadlet))
         .route("/{extension}/{name}", routing::post(save_quadlet))
         .route("/{extension}/{name}", routing::delete(delete_quadlet))
+        .route("/{extension}/{name}", routing::patch(patch_quadlet))
+        .route("/{extension}/{name}/apply", routing::post(apply_quadlet))
+        .route("/{extension}/{name}/clone", routing::post(clone_quadlet))
+        .route("/{extension}/{name}/meta", routing::put(save_meta))
+        .route(
+            "/{extension}/{name}/relocate",
+            routing::post(relocate_quadlet),
+        )
+        .route("/{extension}/{name}/parsed", routing::get(read_parsed))
+        .route(
+            "/{extension}/{name}/effective",
+            routing::get(read_effective),
+        )
+        .route("/{extension}/{name}/diff", routing::post(diff_quadlet))
+        .route(
+            "/{extension}/{name}/from-template",
+            routing::post(create_from_template),
+        )
         .route("/{extension}/{name}/action", routing::post(run_action))
         .route("/{extension}/{name}/logs", routing::get(get_quadlet_logs))
+        .route(
+            "/{extension}/{name}/logs/stream",
+            routing::get(stream_quadlet_logs),
+        )
+        .route(
+            "/{extension}/{name}/unit",
+            routing::get(read_generated_unit),
+        )
+        .route(
+            "/{extension}/{name}/status",
+            routing::get(read_status_detail),
+        )
+        .route(
+            "/{extension}/{name}/resources",
+            routing::get(read_resources),
+        )
+        .route("/{extension}/{name}/dropins", routing::get(read_dropins))
+        .route(
+            "/{extension}/{name}/dropins/{file}",
+            routing::get(read_dropin).post(save_dropin),
+        )
+        .route("/{extension}/{name}/pull", routing::post(pull_image))
+        .route(
+            "/{extension}/{name}/podman-run",
+            routing::get(export_podman_run),
+        )
+        .route("/by-file/{filename}", routing::get(read_quadlet_by_file))
+        // Bajo `/{extension}/schema` ocultaría a un quadlet llamado "schema"
+        .route("/schema/{extension}", routing::get(read_schema))
         .route("/discover", routing::get(discover_quadlets))
+        .route("/summary", routing::get(read_summaries))
+        .route("/graph", routing::get(read_graph))
+        .route("/orphans", routing::get(read_orphans))
+        .route("/lint", routing::get(lint_quadlets))
+        .route("/errors", routing::get(read_errors))
+        .route("/status", routing::post(batch_status))
+        .route("/actions", routing::post(run_bulk_action))
+        .route("/stop-all", routing::post(stop_all_quadlets))
+        .route("/reload", routing::post(reload_units))
+        .route("/export", routing::get(export_quadlets))
+        .route("/import", routing::post(import_quadlets))
+        .route("/import/compose", routing::post(import_compose))
+        .route("/templates", routing::get(read_templates))
+        .route("/events", routing::get(quadlet_events))
+        // El límite de axum se sustituye por el de MAX_BODY_BYTES
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(max_body_bytes()))
+        .layer(middleware::from_fn(payload_too_large))
+}
+
+/// Apunta en `audit_log` quién hizo `action` sobre `target` y cómo acabó. Un fallo
+/// al escribirlo se registra en el log pero no cambia la respuesta
+async fn audit(
+    app_state: &AppState,
+    claims: Option<&TokenClaims>,
+    action: &str,
+    target: &str,
+    result: &str,
+) {
+    let username = claims.map_or("anonymous", |claims| claims.sub.as_str());
+    if let Err(e) = AuditEntry::create(&app_state.pool, username, action, target, result).await {
+        error!("Error writing audit log: {}", e);
+    }
 }
 
-async fn read_quadlets(Path(extension): Path<String>) -> impl IntoResponse {
-    match Quadlet::read_by_extension(&extension).await {
-        Ok(quadlets) => CustomResponse::api(StatusCode::OK, "quadlets", quadlets),
-        Err(e) => CustomResponse::empty(StatusCode::NOT_FOUND, &format!("Error: {}", e)),
+/// "ok" o el error con el que acabó una operación, para el registro de auditoría
+fn outcome<T>(result: &Result<T, AppError>) -> String {
+    match result {
+        Ok(_) => "ok".to_string(),
+        Err(e) => e.to_string(),
     }
 }
 
-async fn read_quadlet(Path((extension, name)): Path<(String, String)>) -> impl IntoResponse {
-    let mut quadlet = match Quadlet::new(&name, &extension, None) {
-        Ok(quadlet) => quadlet,
-        Err(e) => {
-            return CustomResponse::empty(
-                StatusCode::BAD_REQUEST,
-                &format!("Invalid quadlet type: {}. {}", extension, e),
+/// Publica el estado actual de un quadlet en el canal de eventos para que los clientes
+/// lo vean sin esperar al monitor de systemd
+async fn publish_status(app_state: &AppState, name: &str, kind: QuadletType) -> QuadletStatus {
+    let status = system::get_status(&app_state.bus, name, kind).await;
+    let path = get_quadlet_dir().join(format!("{}.{}", name, kind.as_str()));
+    let _ = app_state.events.send(QuadletInfo {
+        name: name.to_string(),
+        kind,
+        status: Some(status),
+        modified: file_modified(&path).await,
+        health: None,
+        enablement: None,
+        meta: None,
+    });
+    status
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/quadlets/{extension}",
+    tag = "quadlets",
+    params(
+        ("extension" = String, Path, description = "Tipo de quadlet: container, volume, network..."),
+        PageQuery,
+    ),
+    responses(
+        (status = 200, body = ApiResponse<Vec<Quadlet>>),
+        (status = 400, body = ErrorResponse),
+    )
+)]
+async fn read_quadlets(
+    Path(extension): Path<String>,
+    Query(page): Query<PageQuery>,
+) -> Result<CustomResponse<Vec<Quadlet>>, AppError> {
+    let quadlets = Quadlet::read_by_extension(&extension)
+        .await
+        .map_err(|e| AppError::from_io(e, &extension))?;
+    let (quadlets, total) = page.apply(quadlets)?;
+    Ok(CustomResponse::paginated(
+        StatusCode::OK,
+        "quadlets",
+        quadlets,
+        total,
+    ))
+}
+
+async fn read_schema(
+    Path(extension): Path<String>,
+) -> Result<CustomResponse<serde_json::Value>, AppError> {
+    let kind: QuadletType = extension.parse()?;
+    Ok(CustomResponse::api(
+        StatusCode::OK,
+        "schema",
+        core::json_schema(kind),
+    ))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReadQuery {
+    /// Ocultar los valores de las claves sensibles (`Environment`, `Secret`...)
+    #[serde(default)]
+    pub redact: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/quadlets/{extension}/{name}",
+    tag = "quadlets",
+    params(
+        ("extension" = String, Path, description = "Tipo de quadlet: container, volume, network..."),
+        ("name" = String, Path, description = "Nombre del quadlet, sin extensión"),
+        ReadQuery,
+    ),
+    responses(
+        (status = 200, body = ApiResponse<Quadlet>),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn read_quadlet(
+    State(app_state): State<Arc<AppState>>,
+    Path((extension, name)): Path<(String, String)>,
+    Query(query): Query<ReadQuery>,
+) -> Result<CustomResponse<Quadlet>, AppError> {
+    let mut quadlet = Quadlet::new(&name, &extension, None)?;
+    quadlet
+        .read()
+        .await
+        .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+    // El etag sigue siendo el del contenido real, para poder guardar después
+    if query.redact {
+        quadlet.content = quadlet
+            .content
+            .as_deref()
+            .map(|content| core::redact_content(content, &app_state.redact_patterns));
+    }
+    Ok(CustomResponse::api(StatusCode::OK, "quadlet", quadlet))
+}
+
+/// Igual que `read_quadlet` pero con el nombre de fichero completo (`web.container`)
+async fn read_quadlet_by_file(
+    state: State<Arc<AppState>>,
+    Path(filename): Path<String>,
+    query: Query<ReadQuery>,
+) -> Result<CustomResponse<Quadlet>, AppError> {
+    let (name, extension) = filename
+        .rsplit_once('.')
+        .filter(|(_, ext)| {
+            QuadletType::from_extension(ext).is_some_and(|kind| kind != QuadletType::Any)
+        })
+        .ok_or_else(|| {
+            AppError::bad_request(&format!("'{}' no es un fichero de quadlet", filename))
+        })?;
+    read_quadlet(
+        state,
+        Path((extension.to_string(), name.to_string())),
+        query,
+    )
+    .await
+}
+
+/// Estructura del quadlet ya parseada, para el editor de formularios
+async fn read_parsed(
+    Path((extension, name)): Path<(String, String)>,
+) -> Result<CustomResponse<core::Sections>, AppError> {
+    let mut quadlet = Quadlet::new(&name, &extension, None)?;
+    quadlet
+        .read()
+        .await
+        .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+    let sections = core::parsed_sections(
+        &quadlet.full_name(),
+        quadlet.content.as_deref().unwrap_or_default(),
+    )?;
+    Ok(CustomResponse::api(StatusCode::OK, "parsed", sections))
+}
+
+/// Configuración efectiva del quadlet: el fichero con los drop-ins de
+/// `{nombre}.{tipo}.d/*.conf` aplicados en orden, como hace el generador de quadlet
+async fn read_effective(
+    Path((extension, name)): Path<(String, String)>,
+) -> Result<CustomResponse<core::Sections>, AppError> {
+    let mut quadlet = Quadlet::new(&name, &extension, None)?;
+    quadlet
+        .read()
+        .await
+        .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+    let mut sections = core::parsed_sections(
+        &quadlet.full_name(),
+        quadlet.content.as_deref().unwrap_or_default(),
+    )?;
+    let dir = get_quadlet_dir().join(format!("{}.d", quadlet.full_name()));
+    for file in system::list_dropins(&dir).await? {
+        let content = system::read_dropin(&dir, &file).await?;
+        core::merge_dropin(&mut sections, quadlet.kind, &file, &content)?;
+    }
+    Ok(CustomResponse::api(StatusCode::OK, "effective", sections))
+}
+
+/// Cuerpo de `save_quadlet`: el contenido tal cual o junto al etag que el cliente
+/// leyó. Sin etag se sobrescribe lo que haya en disco
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum SaveRequest {
+    Content(String),
+    Versioned {
+        content: String,
+        expected_etag: Option<String>,
+    },
+}
+
+/// Falla con `Conflict` si el quadlet en disco ya no es el que leyó el cliente
+async fn check_etag(quadlet: &Quadlet, expected: &str) -> Result<(), AppError> {
+    let mut current = Quadlet::new(&quadlet.name, quadlet.kind.as_str(), None)?;
+    match current.read().await {
+        Ok(()) if current.etag.as_deref() == Some(expected) => Ok(()),
+        Ok(()) => Err(AppError::Conflict(format!(
+            "{} ha cambiado desde que se leyó",
+            quadlet.full_name()
+        ))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(AppError::Conflict(format!(
+            "{} ya no existe",
+            quadlet.full_name()
+        ))),
+        Err(e) => Err(AppError::from_io(e, &quadlet.full_name())),
+    }
+}
+
+impl SaveRequest {
+    /// Interpreta el cuerpo según su `Content-Type`: con `text/plain` es el contenido
+    /// tal cual (y el etag va en `If-Match`); en otro caso, JSON
+    fn from_body(headers: &HeaderMap, body: &[u8]) -> Result<Self, AppError> {
+        let content_type = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        if content_type.starts_with("text/plain") {
+            let content = String::from_utf8(body.to_vec())
+                .map_err(|_| AppError::bad_request("El contenido no es UTF-8 válido"))?;
+            let expected_etag = headers
+                .get(header::IF_MATCH)
+                .and_then(|value| value.to_str().ok())
+                .map(|etag| etag.trim_matches('"').to_string());
+            return Ok(SaveRequest::Versioned {
+                content,
+                expected_etag,
+            });
+        }
+        serde_json::from_slice(body).map_err(|_| {
+            AppError::bad_request(
+                "El cuerpo debe ser el quadlet en text/plain o en JSON: un string o \
+                 {\"content\": ..., \"expected_etag\": ...}",
             )
+        })
+    }
+}
+
+#[derive(Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SaveQuery {
+    /// Rechazar el guardado si hay avisos de validación
+    #[serde(default)]
+    pub strict: bool,
+    /// Sustituir `${VAR}` por el valor de las variables de `EXPAND_VARS`
+    #[serde(default)]
+    pub expand: bool,
+    /// Guardar también el contenido original, con las `${VAR}` sin expandir
+    #[serde(default)]
+    pub store_template: bool,
+}
+
+/// Avisos de un `.container` con claves no válidas, que referencia volúmenes o redes
+/// que no existen en disco o que publica puertos del host que ya usa otro `.container`.
+/// Solo fallarían al arrancar el contenedor, así que por defecto no impiden guardar.
+/// De un `.build` se avisa si le falta `ImageTag=` o `File=`. Las referencias se
+/// buscan en `dir`; si todavía no existe, no hay nada con lo que comparar
+async fn save_warnings(
+    quadlet: &Quadlet,
+    dir: &std::path::Path,
+) -> Result<Vec<ValidationError>, AppError> {
+    let Some(content) = quadlet.content.as_deref() else {
+        return Ok(Vec::new());
+    };
+    match quadlet.kind {
+        QuadletType::Container => {}
+        QuadletType::Build => return Ok(core::build_errors(content)),
+        _ => return Ok(Vec::new()),
+    }
+    let own_file = quadlet.full_name();
+    let mut existing = HashSet::new();
+    let mut containers = Vec::new();
+    let io_error = |e| AppError::from_io(e, &dir.display().to_string());
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => Some(entries),
+        // El primer quadlet del host: el directorio se crea al guardarlo
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(io_error(e)),
+    };
+    while let Some(entries) = entries.as_mut() {
+        let Some(entry) = entries.next_entry().await.map_err(io_error)? else {
+            break;
+        };
+        let Some(file_name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if file_name.ends_with(".container") && file_name != own_file {
+            // Uno que no se pueda leer no debe impedir el guardado de este
+            if let Ok(other) = tokio::fs::read_to_string(entry.path()).await {
+                containers.push((file_name.clone(), other));
+            }
+        }
+        existing.insert(file_name);
+    }
+    let mut warnings = core::semantic_errors(content);
+    warnings.extend(core::missing_references(content, &existing));
+    warnings.extend(core::port_conflicts(content, &containers));
+    Ok(warnings)
+}
+
+/// Valida y guarda en disco el quadlet recibido, sin tocar systemd
+async fn write_quadlet(
+    app_state: &AppState,
+    extension: &str,
+    name: &str,
+    query: &SaveQuery,
+    headers: &HeaderMap,
+    body: &Bytes,
+) -> Result<Quadlet, AppError> {
+    let (template, expected_etag) = match SaveRequest::from_body(headers, body)? {
+        SaveRequest::Content(content) => (content, None),
+        SaveRequest::Versioned {
+            content,
+            expected_etag,
+        } => (content, expected_etag),
+    };
+    let content = if query.expand {
+        let (content, unresolved) = core::expand_vars(&template, &app_state.expand_vars, |var| {
+            std::env::var(var).ok()
+        });
+        if !unresolved.is_empty() {
+            warn!(
+                "Variables sin expandir en {}.{}: {}",
+                name,
+                extension,
+                unresolved.join(", ")
+            );
         }
+        content
+    } else {
+        template.clone()
     };
-    match quadlet.read().await {
-        Ok(_) => CustomResponse::api(StatusCode::OK, "quadlet", quadlet),
-        Err(e) => CustomResponse::empty(StatusCode::NOT_FOUND, &format!("Error: {}", e)),
+    let mut quadlet = Quadlet::new(name, extension, Some(content))?;
+    // La comprobación del etag y el guardado no deben intercalarse con otra escritura
+    let _guard = app_state.file_locks.lock(&quadlet.full_name()).await;
+    if let Some(expected) = &expected_etag {
+        check_etag(&quadlet, expected).await?;
+    }
+    let warnings = save_warnings(&quadlet, &get_quadlet_dir()).await?;
+    if query.strict && !warnings.is_empty() {
+        let messages: Vec<_> = warnings.iter().map(|w| w.message.as_str()).collect();
+        return Err(AppError::ValidationError(messages.join("; ")));
     }
+    quadlet.warnings = warnings;
+    quadlet
+        .save()
+        .await
+        .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+    if query.store_template {
+        quadlet
+            .save_template(&template)
+            .await
+            .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+    }
+    quadlet.etag = quadlet.content.as_deref().map(content_etag);
+    app_state.discover_cache.invalidate().await;
+    Ok(quadlet)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/quadlets/{extension}/{name}",
+    tag = "quadlets",
+    params(
+        ("extension" = String, Path, description = "Tipo de quadlet: container, volume, network..."),
+        ("name" = String, Path, description = "Nombre del quadlet, sin extensión"),
+        SaveQuery,
+    ),
+    request_body(content = String, content_type = "text/plain"),
+    responses(
+        (status = 200, body = ApiResponse<Quadlet>),
+        (status = 409, body = ErrorResponse),
+        (status = 413, body = ErrorResponse),
+        (status = 422, body = ErrorResponse),
+        (status = 503, body = ErrorResponse),
+    )
+)]
 async fn save_quadlet(
+    State(app_state): State<Arc<AppState>>,
+    claims: Option<Extension<TokenClaims>>,
     Path((extension, name)): Path<(String, String)>,
-    Json(content): Json<String>,
-) -> impl IntoResponse {
-    let quadlet = match Quadlet::new(&name, &extension, Some(content)) {
-        Ok(quadlet) => quadlet,
-        Err(e) => {
-            return CustomResponse::empty(
-                StatusCode::BAD_REQUEST,
-                &format!("Error creating quadlet {}.{}: {}", name, extension, e),
+    Query(query): Query<SaveQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<CustomResponse<Quadlet>, AppError> {
+    let result = async {
+        // 1. Guardar en disco
+        let quadlet = write_quadlet(&app_state, &extension, &name, &query, &headers, &body).await?;
+
+        // 2. Avisar a systemd que hay archivos nuevos (daemon-reload)
+        system::daemon_reload(&app_state.bus).await.map_err(|e| {
+            AppError::SystemdError(format!("Saved, but error with daemon reload: {}", e))
+        })?;
+        publish_status(&app_state, &quadlet.name, quadlet.kind).await;
+        Ok(CustomResponse::api(StatusCode::OK, "saved", quadlet))
+    }
+    .await;
+    let target = format!("{}.{}", name, extension);
+    audit(
+        &app_state,
+        claims.as_deref(),
+        "save",
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    result
+}
+
+/// Guarda, hace daemon-reload y arranca (o reinicia, si ya estaba activa) la unidad
+/// en una sola llamada. Si falla un paso tras guardar no se deshace nada: la
+/// respuesta indica cuál fue. Solo para administradores
+async fn apply_quadlet(
+    State(app_state): State<Arc<AppState>>,
+    AdminClaims(claims): AdminClaims,
+    Path((extension, name)): Path<(String, String)>,
+    Query(query): Query<SaveQuery>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<CustomResponse<ApplyResult>, AppError> {
+    let unit_name = QuadletType::from_extension(&extension)
+        .and_then(|kind| kind.unit_name(&name))
+        .ok_or_else(|| {
+            AppError::bad_request(&format!("'{}' no genera ninguna unidad", extension))
+        })?;
+    let quadlet = write_quadlet(&app_state, &extension, &name, &query, &headers, &body).await?;
+    info!("{} aplica {}", claims.sub, quadlet.full_name());
+
+    let applied = system::apply_unit(&app_state.bus, &unit_name).await;
+    Ok(applied_response(&app_state, &claims, quadlet, &unit_name, applied).await)
+}
+
+/// Registra el resultado de aplicar un quadlet ya guardado y arma la respuesta: 200
+/// si systemd lo aplicó, 503 con el paso que falló si no
+async fn applied_response(
+    app_state: &AppState,
+    claims: &TokenClaims,
+    quadlet: Quadlet,
+    unit_name: &str,
+    applied: Result<(), (ApplyStep, AppError)>,
+) -> CustomResponse<ApplyResult> {
+    app_state.discover_cache.invalidate().await;
+    let status = publish_status(app_state, &quadlet.name, quadlet.kind).await;
+    let result = match &applied {
+        Ok(()) => "ok".to_string(),
+        Err((step, e)) => format!("{}: {}", step.as_str(), e),
+    };
+    audit(
+        app_state,
+        Some(claims),
+        "apply",
+        &quadlet.full_name(),
+        &result,
+    )
+    .await;
+    let (code, message, failed_step, error) = match applied {
+        Ok(()) => (StatusCode::OK, "applied".to_string(), None, None),
+        Err((step, e)) => {
+            error!("Error aplicando {} en {}: {}", unit_name, step.as_str(), e);
+            let message = format!("Saved, but {} failed", step.as_str());
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                message,
+                Some(step),
+                Some(e.to_string()),
             )
         }
     };
-    // 1. Guardar en disco
-    if let Err(e) = quadlet.save().await {
-        return CustomResponse::empty(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            &format!("Error saving quadlet {}.{}: {}", name, extension, e),
-        );
-    }
+    CustomResponse::api(
+        code,
+        &message,
+        ApplyResult {
+            quadlet,
+            status,
+            failed_step,
+            error,
+        },
+    )
+}
 
-    // 2. Avisar a systemd que hay archivos nuevos (daemon-reload)
-    // Usamos la acción que definimos en el paso anterior
-    if let Err(e) = system::run_unit_action(&name, "daemon-reload").await {
-        return CustomResponse::empty(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            &format!("Saved, but error with daemon reload: {}", e),
-        );
+#[derive(Deserialize)]
+pub struct KeyUpdate {
+    pub section: String,
+    pub key: String,
+    /// `null` borra la clave
+    pub value: Option<String>,
+}
+
+/// Cambia una sola clave del quadlet sin tocar el resto del fichero
+async fn patch_quadlet(
+    State(app_state): State<Arc<AppState>>,
+    claims: Option<Extension<TokenClaims>>,
+    Path((extension, name)): Path<(String, String)>,
+    Json(update): Json<KeyUpdate>,
+) -> Result<CustomResponse<Quadlet>, AppError> {
+    let result = set_quadlet_key(&app_state, &extension, &name, &update).await;
+    let target = format!("{}.{}", name, extension);
+    audit(
+        &app_state,
+        claims.as_deref(),
+        "save",
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    result
+}
+
+async fn set_quadlet_key(
+    app_state: &AppState,
+    extension: &str,
+    name: &str,
+    update: &KeyUpdate,
+) -> Result<CustomResponse<Quadlet>, AppError> {
+    let mut quadlet = Quadlet::new(name, extension, None)?;
+    let guard = app_state.file_locks.lock(&quadlet.full_name()).await;
+    quadlet
+        .read()
+        .await
+        .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+    let content = core::set_key(
+        quadlet.content.as_deref().unwrap_or_default(),
+        &update.section,
+        &update.key,
+        update.value.as_deref(),
+    )?;
+    // La descripción ya va dentro del contenido
+    quadlet.description = None;
+    quadlet.content = Some(content);
+    quadlet
+        .save()
+        .await
+        .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+    quadlet.etag = quadlet.content.as_deref().map(content_etag);
+    drop(guard);
+    app_state.discover_cache.invalidate().await;
+
+    system::daemon_reload(&app_state.bus).await.map_err(|e| {
+        AppError::SystemdError(format!("Saved, but error with daemon reload: {}", e))
+    })?;
+    publish_status(app_state, &quadlet.name, quadlet.kind).await;
+    Ok(CustomResponse::api(StatusCode::OK, "patched", quadlet))
+}
+
+/// Compara el contenido en disco con el que se quiere guardar, sin guardarlo.
+/// Si el quadlet aún no existe se compara con un fichero vacío
+async fn diff_quadlet(
+    Path((extension, name)): Path<(String, String)>,
+    Json(content): Json<String>,
+) -> Result<CustomResponse<QuadletDiff>, AppError> {
+    let mut quadlet = Quadlet::new(&name, &extension, None)?;
+    let current = match quadlet.read().await {
+        Ok(()) => quadlet.content.take().unwrap_or_default(),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(AppError::from_io(e, &quadlet.full_name())),
+    };
+    let diff = QuadletDiff::new(&quadlet.full_name(), &current, &content);
+    Ok(CustomResponse::api(StatusCode::OK, "diff", diff))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/v1/quadlets/{extension}/{name}",
+    tag = "quadlets",
+    params(
+        ("extension" = String, Path, description = "Tipo de quadlet: container, volume, network..."),
+        ("name" = String, Path, description = "Nombre del quadlet, sin extensión"),
+    ),
+    responses(
+        (status = 200, body = ApiResponse<Quadlet>),
+        (status = 404, body = ErrorResponse),
+    )
+)]
+async fn delete_quadlet(
+    State(app_state): State<Arc<AppState>>,
+    claims: Option<Extension<TokenClaims>>,
+    Path((extension, name)): Path<(String, String)>,
+) -> Result<CustomResponse<Quadlet>, AppError> {
+    let result = async {
+        let quadlet = Quadlet::new(&name, &extension, None)?;
+        let guard = app_state.file_locks.lock(&quadlet.full_name()).await;
+        quadlet
+            .delete()
+            .await
+            .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+        drop(guard);
+        // Sin el fichero sus etiquetas ya no son de nadie
+        if let Err(e) = QuadletMeta::delete(&app_state.pool, &quadlet.full_name()).await {
+            warn!("Error deleting metadata of {}: {}", quadlet.full_name(), e);
+        }
+        app_state.discover_cache.invalidate().await;
+        publish_status(&app_state, &quadlet.name, quadlet.kind).await;
+        Ok(CustomResponse::api(StatusCode::OK, "deleted", quadlet))
     }
-    CustomResponse::api(StatusCode::OK, "saved", quadlet)
+    .await;
+    let target = format!("{}.{}", name, extension);
+    audit(
+        &app_state,
+        claims.as_deref(),
+        "delete",
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    result
+}
+
+#[derive(Deserialize)]
+pub struct CloneRequest {
+    pub new_name: String,
 }
 
-async fn delete_quadlet(Path((extension, name)): Path<(String, String)>) -> impl IntoResponse {
-    let quadlet = Quadlet::new(&name, &extension, None).unwrap();
-    match quadlet.delete().await {
-        Ok(_) => CustomResponse::api(StatusCode::OK, "deleted", quadlet),
-        Err(e) => CustomResponse::empty(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            &format!("Error deleting quadlet {}.{}: {}", name, extension, e),
-        ),
+async fn clone_quadlet(
+    State(app_state): State<Arc<AppState>>,
+    claims: Option<Extension<TokenClaims>>,
+    Path((extension, name)): Path<(String, String)>,
+    Json(payload): Json<CloneRequest>,
+) -> Result<CustomResponse<Quadlet>, AppError> {
+    let result = async {
+        let mut source = Quadlet::new(&name, &extension, None)?;
+        source
+            .read()
+            .await
+            .map_err(|_| AppError::not_found(&source.full_name()))?;
+
+        let target = Quadlet::new(&payload.new_name, &extension, source.content.take())?;
+        let guard = app_state.file_locks.lock(&target.full_name()).await;
+        if target.exists().await? {
+            return Err(AppError::bad_request(&format!(
+                "Quadlet {} already exists",
+                target.full_name()
+            )));
+        }
+
+        // No hace falta daemon-reload hasta que el usuario lo edite
+        target.save().await?;
+        drop(guard);
+        app_state.discover_cache.invalidate().await;
+        publish_status(&app_state, &target.name, target.kind).await;
+        Ok(CustomResponse::api(StatusCode::CREATED, "cloned", target))
     }
+    .await;
+    let target = format!("{}.{} -> {}", name, extension, payload.new_name);
+    audit(
+        &app_state,
+        claims.as_deref(),
+        "clone",
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    result
+}
+
+async fn read_templates() -> CustomResponse<Vec<QuadletTemplate>> {
+    CustomResponse::api(StatusCode::OK, "templates", core::list_templates())
 }
 
 #[derive(Deserialize)]
+pub struct TemplateRequest {
+    pub template: String,
+    /// Valores de las variables `{{...}}`. `name` toma por defecto el nombre del quadlet
+    #[serde(default)]
+    pub values: HashMap<String, String>,
+}
+
+async fn create_from_template(
+    State(app_state): State<Arc<AppState>>,
+    claims: Option<Extension<TokenClaims>>,
+    Path((extension, name)): Path<(String, String)>,
+    Json(mut payload): Json<TemplateRequest>,
+) -> Result<CustomResponse<Quadlet>, AppError> {
+    let result = async {
+        let template = core::find_template(&payload.template)
+            .ok_or_else(|| AppError::not_found(&format!("plantilla {}", payload.template)))?;
+        if QuadletType::from_extension(&extension) != Some(template.kind) {
+            return Err(AppError::bad_request(&format!(
+                "La plantilla {} genera un .{}, no un .{}",
+                template.id,
+                template.kind.as_str(),
+                extension
+            )));
+        }
+        payload
+            .values
+            .entry("name".to_string())
+            .or_insert_with(|| name.clone());
+        let content = core::render_template(&template, &payload.values)?;
+
+        let quadlet = Quadlet::new(&name, &extension, Some(content))?;
+        let guard = app_state.file_locks.lock(&quadlet.full_name()).await;
+        if quadlet.exists().await? {
+            return Err(AppError::bad_request(&format!(
+                "Quadlet {} already exists",
+                quadlet.full_name()
+            )));
+        }
+        quadlet
+            .save()
+            .await
+            .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+        drop(guard);
+        app_state.discover_cache.invalidate().await;
+        publish_status(&app_state, &quadlet.name, quadlet.kind).await;
+        Ok(CustomResponse::api(StatusCode::CREATED, "created", quadlet))
+    }
+    .await;
+    let target = format!("{}.{}", name, extension);
+    audit(
+        &app_state,
+        claims.as_deref(),
+        "from-template",
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    result
+}
+
+#[derive(Deserialize, ToSchema)]
 pub struct ActionRequest {
-    pub action: String, // "start", "stop", "restart", "daemon-reload"
+    /// Una acción desconocida se rechaza al deserializar el cuerpo con un 422
+    pub action: UnitAction,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct DiscoverQuery {
     pub kind: Option<String>,
     pub status: Option<String>,
+    /// "ndjson" para recibir los quadlets en streaming, uno por línea. En este
+    /// modo no se ordena ni se pagina: con `limit`, `offset` o `sort` es un 400
+    pub format: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+    /// "name" (por defecto), "status" o "modified"
+    pub sort: Option<String>,
+    /// Texto a buscar en el nombre, sin distinguir mayúsculas
+    pub q: Option<String>,
+    /// Buscar `q` también en el contenido de los ficheros
+    pub content: Option<bool>,
+    /// Solo los quadlets con esta etiqueta
+    pub tag: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ActionQuery {
+    /// Solo informar del estado esperado, sin ejecutar la acción
+    pub dry_run: Option<bool>,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/v1/quadlets/{extension}/{name}/action",
+    tag = "quadlets",
+    params(
+        ("extension" = String, Path, description = "Tipo de quadlet: container, volume, network..."),
+        ("name" = String, Path, description = "Nombre del quadlet, sin extensión"),
+        ActionQuery,
+    ),
+    request_body = ActionRequest,
+    responses(
+        (status = 200, body = ApiResponse<QuadletStatus>),
+        (status = 422, body = ErrorResponse),
+        (status = 503, body = ErrorResponse),
+        (status = 504, body = ErrorResponse),
+    )
+)]
 async fn run_action(
+    State(app_state): State<Arc<AppState>>,
+    claims: Option<Extension<TokenClaims>>,
     Path((extension, name)): Path<(String, String)>,
+    Query(query): Query<ActionQuery>,
     Json(payload): Json<ActionRequest>,
-) -> impl IntoResponse {
-    match system::run_unit_action(&name, &payload.action).await {
-        Ok(_) => {
-            // Si hacemos un cambio de estado, podemos emitir una notificación
-            // manual al canal de eventos si quisiéramos respuesta inmediata
-            StatusCode::OK
-        }
-        Err(e) => {
-            eprintln!("Error ejecutando {} en {}: {}", payload.action, name, e);
-            StatusCode::INTERNAL_SERVER_ERROR
+) -> Result<Response, AppError> {
+    if query.dry_run.unwrap_or(false) {
+        return Ok(
+            preview_action(&app_state, &extension, &name, payload.action)
+                .await?
+                .into_response(),
+        );
+    }
+
+    let kind: QuadletType = extension.parse()?;
+    let result = system::run_unit_action(&app_state.bus, &name, kind, payload.action).await;
+    // Aunque la acción falle el estado de la unidad puede haber cambiado
+    app_state.discover_cache.invalidate().await;
+    let target = format!("{}.{}", name, extension);
+    let action = payload.action.to_string();
+    audit(
+        &app_state,
+        claims.as_deref(),
+        &action,
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    let applied = result.map_err(|e| {
+        error!("Error ejecutando {} en {}: {}", payload.action, name, e);
+        e
+    })?;
+    if !applied {
+        // restart-if-active sobre una unidad parada: no hay nada que publicar
+        return Ok(CustomResponse::api(StatusCode::OK, "skipped", payload.action).into_response());
+    }
+
+    // El monitor avisará cuando acabe el trabajo, pero reset-failed no lanza ninguno
+    // y así los clientes ven el cambio de inmediato
+    let status = publish_status(&app_state, &name, kind).await;
+    if payload.action == UnitAction::Toggle {
+        // El interruptor de la interfaz necesita saber en qué estado ha quedado
+        return Ok(CustomResponse::api(StatusCode::OK, "toggled", status).into_response());
+    }
+    Ok(CustomResponse::api(StatusCode::OK, "action", payload.action).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct BulkActionRequest {
+    pub names: Vec<String>,
+    pub action: UnitAction,
+}
+
+/// Ejecuta una acción sobre varios quadlets a la vez. Solo para administradores
+async fn run_bulk_action(
+    State(app_state): State<Arc<AppState>>,
+    AdminClaims(claims): AdminClaims,
+    Json(payload): Json<BulkActionRequest>,
+) -> Result<CustomResponse<Vec<BulkActionResult>>, AppError> {
+    if payload.names.is_empty() || payload.names.len() > MAX_STATUS_BATCH {
+        return Err(AppError::bad_request(&format!(
+            "Se esperaban entre 1 y {} nombres",
+            MAX_STATUS_BATCH
+        )));
+    }
+    info!(
+        "{} ejecuta {} sobre {} quadlets",
+        claims.sub,
+        payload.action,
+        payload.names.len()
+    );
+    let result = system::run_bulk_action(&app_state.bus, &payload.names, payload.action).await;
+    app_state.discover_cache.invalidate().await;
+    let results = result?;
+    let action = payload.action.to_string();
+    for entry in &results {
+        let result = entry.error.as_deref().unwrap_or("ok");
+        audit(&app_state, Some(&claims), &action, &entry.name, result).await;
+    }
+    Ok(CustomResponse::api(StatusCode::OK, "actions", results))
+}
+
+#[derive(Deserialize)]
+pub struct StopAllQuery {
+    /// Parar solo los `container` o solo los `pod`
+    pub kind: Option<QuadletType>,
+}
+
+/// Botón de pánico: para a la vez todos los contenedores y pods activos, salvo el
+/// propio Quadly. Solo para administradores
+async fn stop_all_quadlets(
+    State(app_state): State<Arc<AppState>>,
+    AdminClaims(claims): AdminClaims,
+    Query(query): Query<StopAllQuery>,
+) -> Result<CustomResponse<Vec<BulkActionResult>>, AppError> {
+    if let Some(kind) = query.kind {
+        if !matches!(kind, QuadletType::Container | QuadletType::Pod) {
+            return Err(AppError::bad_request(&format!(
+                "stop-all solo para container o pod, no {}",
+                kind.as_str()
+            )));
         }
     }
+    warn!("{} para todos los quadlets activos", claims.sub);
+    let result = system::stop_all(&app_state.bus, query.kind).await;
+    app_state.discover_cache.invalidate().await;
+    let results = result?;
+    for entry in &results {
+        let result = entry.error.as_deref().unwrap_or("ok");
+        audit(&app_state, Some(&claims), "stop", &entry.name, result).await;
+    }
+    Ok(CustomResponse::api(StatusCode::OK, "stopped", results))
 }
 
 #[derive(Deserialize)]
+pub struct RelocateRequest {
+    pub to_scope: SystemdScope,
+}
+
+/// Mueve un quadlet del ámbito del servidor al otro (rootless <-> rootful) y hace
+/// daemon-reload en los dos. Solo para administradores
+async fn relocate_quadlet(
+    State(app_state): State<Arc<AppState>>,
+    AdminClaims(claims): AdminClaims,
+    Path((extension, name)): Path<(String, String)>,
+    Json(payload): Json<RelocateRequest>,
+) -> Result<CustomResponse<Quadlet>, AppError> {
+    let result = async {
+        if payload.to_scope == app_state.scope {
+            return Err(AppError::bad_request(&format!(
+                "El quadlet ya está en el ámbito {}",
+                app_state.scope
+            )));
+        }
+        let quadlet = Quadlet::new(&name, &extension, None)?;
+        let guard = app_state.file_locks.lock(&quadlet.full_name()).await;
+        let dest = quadlet.path_in(payload.to_scope);
+        if !quadlet.exists().await? {
+            return Err(AppError::not_found(&quadlet.full_name()));
+        }
+        if tokio::fs::try_exists(&dest).await? {
+            return Err(AppError::Conflict(format!("Ya existe {}", dest.display())));
+        }
+        let dest_dir = dest.parent().unwrap_or(&dest);
+        if !dir_writable(dest_dir).await {
+            return Err(AppError::BadRequest(format!(
+                "Este proceso no puede escribir en {}",
+                dest_dir.display()
+            )));
+        }
+        quadlet
+            .move_to(&dest)
+            .await
+            .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+        drop(guard);
+        app_state.discover_cache.invalidate().await;
+        info!(
+            "{} mueve {} al ámbito {}",
+            claims.sub,
+            quadlet.full_name(),
+            payload.to_scope
+        );
+
+        // Uno deja de generar la unidad y el otro empieza a hacerlo
+        let dest_bus = system::SystemdBus::new(payload.to_scope);
+        for bus in [&app_state.bus, &dest_bus] {
+            system::daemon_reload(bus).await.map_err(|e| {
+                AppError::SystemdError(format!("Moved, but error with daemon reload: {}", e))
+            })?;
+        }
+        Ok(CustomResponse::api(StatusCode::OK, "relocated", quadlet))
+    }
+    .await;
+    let target = format!("{}.{}", name, extension);
+    audit(
+        &app_state,
+        Some(&claims),
+        "relocate",
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    result
+}
+
+/// daemon-reload manual, para cuando se han editado quadlets fuera de Quadly.
+/// Solo para administradores
+async fn reload_units(
+    State(app_state): State<Arc<AppState>>,
+    AdminClaims(claims): AdminClaims,
+) -> Result<CustomResponse<()>, AppError> {
+    info!("{} ejecuta daemon-reload", claims.sub);
+    let result = system::daemon_reload(&app_state.bus).await;
+    app_state.discover_cache.invalidate().await;
+    audit(&app_state, Some(&claims), "reload", "", &outcome(&result)).await;
+    result?;
+    Ok(CustomResponse::empty(StatusCode::OK, "reloaded"))
+}
+
+/// Estado actual y esperado de una acción, sin invocar a systemd
+async fn preview_action(
+    app_state: &AppState,
+    extension: &str,
+    name: &str,
+    action: UnitAction,
+) -> Result<CustomResponse<ActionPreview>, AppError> {
+    let quadlet = Quadlet::new(name, extension, None)?;
+    let current = system::get_status(&app_state.bus, &quadlet.name, quadlet.kind).await;
+    let expected = system::predict_status(current, action);
+    Ok(CustomResponse::api(
+        StatusCode::OK,
+        "dry-run",
+        ActionPreview {
+            action,
+            current,
+            expected,
+        },
+    ))
+}
+
+/// Devuelve la unidad systemd que el generador de podman produjo para el quadlet
+async fn read_generated_unit(
+    State(app_state): State<Arc<AppState>>,
+    Path((extension, name)): Path<(String, String)>,
+) -> Result<CustomResponse<String>, AppError> {
+    let quadlet = Quadlet::new(&name, &extension, None)?;
+    let unit_name = unit_name(&quadlet)?;
+    let unit = system::get_generated_unit(
+        app_state.scope,
+        &unit_name,
+        app_state.quadlet_generator.as_deref(),
+    )
+    .await?;
+    Ok(CustomResponse::api(StatusCode::OK, "unit", unit))
+}
+
+/// Unidad que podman genera para el quadlet
+fn unit_name(quadlet: &Quadlet) -> Result<String, AppError> {
+    quadlet
+        .kind
+        .unit_name(&quadlet.name)
+        .ok_or_else(|| AppError::bad_request("El quadlet no genera ningún servicio"))
+}
+
+/// Directorio `{servicio}.d` con los drop-ins de la unidad generada por el quadlet
+fn dropin_dir(extension: &str, name: &str) -> Result<std::path::PathBuf, AppError> {
+    let quadlet = Quadlet::new(name, extension, None)?;
+    Ok(get_unit_dir().join(format!("{}.d", unit_name(&quadlet)?)))
+}
+
+async fn read_dropins(
+    Path((extension, name)): Path<(String, String)>,
+) -> Result<CustomResponse<Vec<String>>, AppError> {
+    let files = system::list_dropins(&dropin_dir(&extension, &name)?).await?;
+    Ok(CustomResponse::api(StatusCode::OK, "dropins", files))
+}
+
+async fn read_dropin(
+    Path((extension, name, file)): Path<(String, String, String)>,
+) -> Result<CustomResponse<String>, AppError> {
+    let content = system::read_dropin(&dropin_dir(&extension, &name)?, &file).await?;
+    Ok(CustomResponse::api(StatusCode::OK, "dropin", content))
+}
+
+/// Guarda un drop-in y recarga systemd para que lo aplique. El quadlet tiene que
+/// existir. Solo para administradores
+async fn save_dropin(
+    State(app_state): State<Arc<AppState>>,
+    AdminClaims(claims): AdminClaims,
+    Path((extension, name, file)): Path<(String, String, String)>,
+    content: String,
+) -> Result<CustomResponse<String>, AppError> {
+    let target = format!("{}.{}/{}", name, extension, file);
+    let result = async {
+        let quadlet = Quadlet::new(&name, &extension, None)?;
+        if !quadlet.exists().await? {
+            return Err(AppError::not_found(&quadlet.full_name()));
+        }
+        let dir = dropin_dir(&extension, &name)?;
+        let guard = app_state
+            .file_locks
+            .lock(&dir.join(&file).to_string_lossy())
+            .await;
+        system::write_dropin(&dir, &file, &content).await?;
+        drop(guard);
+        system::daemon_reload(&app_state.bus).await.map_err(|e| {
+            AppError::SystemdError(format!("Saved, but error with daemon reload: {}", e))
+        })?;
+        Ok(CustomResponse::api(StatusCode::OK, "saved", content))
+    }
+    .await;
+    audit(
+        &app_state,
+        Some(&claims),
+        "dropin",
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    result
+}
+
+/// Estado de la unidad del quadlet con el código de salida y el motivo del último fin
+#[utoipa::path(
+    get,
+    path = "/api/v1/quadlets/{extension}/{name}/status",
+    tag = "quadlets",
+    params(
+        ("extension" = String, Path, description = "Tipo de quadlet: container, volume, network..."),
+        ("name" = String, Path, description = "Nombre del quadlet, sin extensión"),
+    ),
+    responses((status = 200, body = ApiResponse<StatusDetail>))
+)]
+async fn read_status_detail(
+    State(app_state): State<Arc<AppState>>,
+    Path((extension, name)): Path<(String, String)>,
+) -> Result<CustomResponse<StatusDetail>, AppError> {
+    let quadlet = Quadlet::new(&name, &extension, None)?;
+    let detail = system::get_status_detail(&app_state.bus, &quadlet.name, quadlet.kind).await;
+    Ok(CustomResponse::api(StatusCode::OK, "status", detail))
+}
+
+/// Consumo de memoria, CPU y tareas de la unidad del quadlet
+async fn read_resources(
+    State(app_state): State<Arc<AppState>>,
+    Path((extension, name)): Path<(String, String)>,
+) -> Result<CustomResponse<UnitResources>, AppError> {
+    let quadlet = Quadlet::new(&name, &extension, None)?;
+    let resources = system::get_unit_resources(&app_state.bus, &quadlet.name, quadlet.kind).await;
+    Ok(CustomResponse::api(StatusCode::OK, "resources", resources))
+}
+
+/// Devuelve el `podman run` equivalente a un quadlet `.container`
+async fn export_podman_run(
+    Path((extension, name)): Path<(String, String)>,
+) -> Result<CustomResponse<String>, AppError> {
+    let mut quadlet = Quadlet::new(&name, &extension, None)?;
+    if quadlet.kind != QuadletType::Container {
+        return Err(AppError::bad_request(
+            "Solo los quadlets .container se pueden exportar a podman run",
+        ));
+    }
+    quadlet
+        .read()
+        .await
+        .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+    let command = core::podman_run_command(quadlet.content.as_deref().unwrap_or_default())?;
+    Ok(CustomResponse::api(StatusCode::OK, "podman-run", command))
+}
+
+/// Descarga la imagen de un `.container` para que el arranque no se quede
+/// esperando al pull. Solo para administradores
+async fn pull_image(
+    State(app_state): State<Arc<AppState>>,
+    AdminClaims(claims): AdminClaims,
+    Path((extension, name)): Path<(String, String)>,
+) -> Result<CustomResponse<ImagePull>, AppError> {
+    let result = async {
+        let mut quadlet = Quadlet::new(&name, &extension, None)?;
+        if quadlet.kind != QuadletType::Container {
+            return Err(AppError::bad_request(
+                "Solo los quadlets .container tienen imagen que descargar",
+            ));
+        }
+        quadlet
+            .read()
+            .await
+            .map_err(|e| AppError::from_io(e, &quadlet.full_name()))?;
+        let (image, _) = core::container_summary(quadlet.content.as_deref().unwrap_or_default())?;
+        let image = image.ok_or_else(|| AppError::bad_request("El quadlet no define 'Image'"))?;
+        info!("{} descarga la imagen {}", claims.sub, image);
+        let pull = system::pull_image(&image).await?;
+        Ok(CustomResponse::api(StatusCode::OK, "pulled", pull))
+    }
+    .await;
+    let target = format!("{}.{}", name, extension);
+    audit(
+        &app_state,
+        Some(&claims),
+        "pull",
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    result
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
 pub struct LogsQuery {
     pub lines: Option<u32>,
+    /// "text" (por defecto) o "json" para recibir entradas estructuradas
+    pub format: Option<String>,
+    /// Inicio de la ventana: RFC 3339 o relativo ("2 hours ago")
+    pub since: Option<String>,
+    /// Fin de la ventana, mismo formato que `since`
+    pub until: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/v1/quadlets/{extension}/{name}/logs",
+    tag = "quadlets",
+    params(
+        ("extension" = String, Path, description = "Tipo de quadlet: container, volume, network..."),
+        ("name" = String, Path, description = "Nombre del quadlet, sin extensión"),
+        LogsQuery,
+    ),
+    responses(
+        (status = 200, body = ApiResponse<String>),
+        (status = 503, body = ErrorResponse),
+    )
+)]
 async fn get_quadlet_logs(
-    Path(name): Path<String>,
+    State(app_state): State<Arc<AppState>>,
+    Path((extension, name)): Path<(String, String)>,
     Query(params): Query<LogsQuery>,
-) -> impl IntoResponse {
+) -> Result<Response, AppError> {
+    let kind: QuadletType = extension.parse()?;
     let lines = params.lines.unwrap_or(50); // Por defecto 50 líneas
+    let window = system::LogWindow {
+        since: params.since,
+        until: params.until,
+    };
 
-    match system::get_service_logs(&name, lines) {
-        Ok(logs) => (StatusCode::OK, logs).into_response(),
-        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    if params.format.as_deref() == Some("json") {
+        let entries =
+            system::get_service_logs_json(app_state.scope, &name, kind, lines, &window).await?;
+        return Ok(CustomResponse::api(StatusCode::OK, "logs", entries).into_response());
     }
+
+    let logs = system::get_service_logs(app_state.scope, &name, kind, lines, &window).await?;
+    Ok((StatusCode::OK, logs).into_response())
+}
+
+#[derive(Deserialize)]
+pub struct ErrorsQuery {
+    /// Entradas del journal a revisar, de todas las unidades
+    pub lines: Option<u32>,
 }
 
-async fn discover_quadlets(Query(params): Query<DiscoverQuery>) -> impl IntoResponse {
-    match system::discover_quadlets().await {
-        Ok(mut quadlets) => {
-            // Filtrar por kind si se especifica
-            if let Some(kind_filter) = &params.kind {
-                if let Some(quadlet_type) = crate::models::QuadletType::from_extension(kind_filter)
-                {
-                    quadlets.retain(|q| q.kind == quadlet_type);
+/// Errores recientes del journal agrupados por quadlet, para un panel de fallos
+async fn read_errors(
+    State(app_state): State<Arc<AppState>>,
+    Query(query): Query<ErrorsQuery>,
+) -> Result<CustomResponse<Vec<UnitErrors>>, AppError> {
+    let lines = query.lines.unwrap_or(200);
+    let errors = system::get_error_summary(app_state.scope, lines).await?;
+    Ok(CustomResponse::api(StatusCode::OK, "errors", errors))
+}
+
+/// Búsqueda de texto sobre los quadlets descubiertos
+#[derive(Clone)]
+struct Search {
+    needle: String,
+    content: bool,
+}
+
+impl Search {
+    fn new(query: &str, content: bool) -> Self {
+        Self {
+            needle: query.to_lowercase(),
+            content,
+        }
+    }
+
+    /// Indica si el quadlet contiene el texto buscado en su nombre o, si se ha pedido,
+    /// en su contenido. Solo se lee el fichero cuando el nombre no coincide
+    async fn matches(&self, quadlet: &QuadletInfo) -> bool {
+        if quadlet.name.to_lowercase().contains(&self.needle) {
+            return true;
+        }
+        if !self.content {
+            return false;
+        }
+        let path = get_quadlet_dir().join(format!("{}.{}", quadlet.name, quadlet.kind.as_str()));
+        match tokio::fs::read_to_string(&path).await {
+            Ok(body) => body.to_lowercase().contains(&self.needle),
+            Err(_) => false,
+        }
+    }
+}
+
+impl DiscoverQuery {
+    fn search(&self) -> Option<Search> {
+        self.q
+            .as_deref()
+            .filter(|q| !q.is_empty())
+            .map(|q| Search::new(q, self.content.unwrap_or(false)))
+    }
+
+    fn page(&self) -> PageQuery {
+        PageQuery {
+            limit: self.limit,
+            offset: self.offset,
+            sort: self.sort.clone(),
+        }
+    }
+
+    /// Indica si un quadlet cumple los filtros de la consulta
+    fn matches(&self, quadlet: &QuadletInfo) -> bool {
+        // Filtrar por kind si se especifica
+        if let Some(kind_filter) = &self.kind {
+            if let Some(quadlet_type) = QuadletType::from_extension(kind_filter) {
+                if quadlet.kind != quadlet_type {
+                    return false;
+                }
+            }
+        }
+
+        // Filtrar por status si se especifica
+        if let Some(status_filter) = &self.status {
+            let target_status = match status_filter.to_lowercase().as_str() {
+                "active" => Some(QuadletStatus::Active),
+                "inactive" => Some(QuadletStatus::Inactive),
+                "failed" => Some(QuadletStatus::Failed),
+                "activating" => Some(QuadletStatus::Activating),
+                "deactivating" => Some(QuadletStatus::Deactivating),
+                "notloaded" => Some(QuadletStatus::NotLoaded),
+                "unknown" => Some(QuadletStatus::Unknown),
+                _ => None,
+            };
+
+            if let Some(target) = target_status {
+                if quadlet.status != Some(target) {
+                    return false;
+                }
+            }
+        }
+
+        if let Some(tag) = &self.tag {
+            if !quadlet.meta.as_ref().is_some_and(|meta| meta.has_tag(tag)) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Serializa un stream de quadlets como JSON delimitado por saltos de línea
+fn ndjson_body<S>(quadlets: S) -> Body
+where
+    S: Stream<Item = anyhow::Result<QuadletInfo>> + Send + 'static,
+{
+    Body::from_stream(quadlets.map(|quadlet| {
+        let mut line = serde_json::to_vec(&quadlet?)?;
+        line.push(b'\n');
+        Ok::<_, anyhow::Error>(line)
+    }))
+}
+
+/// Abre un WebSocket que envía en vivo cada línea de log del servicio del quadlet
+async fn stream_quadlet_logs(
+    State(app_state): State<Arc<AppState>>,
+    ws: WebSocketUpgrade,
+    Path((extension, name)): Path<(String, String)>,
+) -> Result<Response, AppError> {
+    let quadlet = Quadlet::new(&name, &extension, None)?;
+    let child = system::follow_service_logs(app_state.scope, &quadlet.name, quadlet.kind)?;
+    Ok(ws.on_upgrade(move |socket| forward_logs(socket, child)))
+}
+
+async fn forward_logs(mut socket: WebSocket, child: Child) {
+    let (tx, mut rx) = mpsc::channel(64);
+    let pipe = tokio::spawn(system::pipe_lines(child, tx));
+
+    loop {
+        tokio::select! {
+            line = rx.recv() => match line {
+                Some(line) => {
+                    if socket.send(Message::Text(line.into())).await.is_err() {
+                        break;
+                    }
                 }
+                None => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                _ => {}
+            },
+        }
+    }
+
+    // Al cerrar el canal journalctl se mata y se recoge
+    drop(rx);
+    match pipe.await {
+        Ok(Err(e)) => error!("Error stopping journalctl: {}", e),
+        Err(e) => error!("Error joining log stream task: {}", e),
+        Ok(Ok(_)) => {}
+    }
+}
+
+/// Grafo de dependencias entre pods, contenedores, redes y volúmenes
+async fn read_graph() -> Result<CustomResponse<QuadletGraph>, AppError> {
+    let dir = get_quadlet_dir();
+    let mut files = Vec::new();
+    if tokio::fs::try_exists(&dir).await.unwrap_or(false) {
+        let io_error = |e| AppError::from_io(e, &dir.display().to_string());
+        let mut entries = tokio::fs::read_dir(&dir).await.map_err(io_error)?;
+        while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+            let Some(file_name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if system::split_quadlet_file_name(&file_name).is_none() {
+                continue;
+            }
+            let content = tokio::fs::read_to_string(entry.path())
+                .await
+                .map_err(|e| AppError::from_io(e, &file_name))?;
+            files.push((file_name, content));
+        }
+    }
+    files.sort();
+    Ok(CustomResponse::api(
+        StatusCode::OK,
+        "graph",
+        core::build_graph(&files),
+    ))
+}
+
+/// Todos los `.container` con su imagen y puertos. Los que no se pueden interpretar
+/// se incluyen igualmente marcados con `parse_error`
+async fn read_summaries(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<CustomResponse<Vec<QuadletSummary>>, AppError> {
+    let quadlets = app_state
+        .discover_cache
+        .get_or_refresh(|| system::discover_quadlets(&app_state.bus))
+        .await
+        .map_err(|e| AppError::StorageError(format!("Error discovering quadlets: {}", e)))?;
+    let mut summaries = Vec::new();
+    for quadlet in quadlets {
+        if quadlet.kind != QuadletType::Container {
+            continue;
+        }
+        if let Some(summary) = container_summary(quadlet).await {
+            summaries.push(summary);
+        }
+    }
+    Ok(CustomResponse::api(StatusCode::OK, "quadlets", summaries))
+}
+
+/// Resumen de un `.container` descubierto. Si se borró después de listarlo se omite;
+/// si no se puede leer se incluye con `parse_error`, como uno mal escrito
+async fn container_summary(quadlet: QuadletInfo) -> Option<QuadletSummary> {
+    let parsed =
+        match Quadlet::read_by_extension_and_name(quadlet.kind.as_str(), &quadlet.name).await {
+            Ok(content) => core::container_summary(&content).ok(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return None,
+            Err(e) => {
+                warn!("Error reading {}: {}", quadlet.name, e);
+                None
             }
+        };
+    let parse_error = parsed.is_none();
+    let (image, ports) = parsed.unwrap_or_default();
+    Some(QuadletSummary {
+        name: quadlet.name,
+        kind: quadlet.kind,
+        status: quadlet.status,
+        image,
+        ports,
+        parse_error,
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/v1/quadlets/discover",
+    tag = "quadlets",
+    params(DiscoverQuery),
+    responses((status = 200, body = ApiResponse<Vec<QuadletInfo>>))
+)]
+async fn discover_quadlets(
+    State(app_state): State<Arc<AppState>>,
+    Query(params): Query<DiscoverQuery>,
+) -> Result<Response, AppError> {
+    let ndjson = params.format.as_deref() == Some("ndjson");
+    if ndjson && (params.limit.is_some() || params.offset.is_some() || params.sort.is_some()) {
+        return Err(AppError::bad_request(
+            "El formato ndjson no admite limit, offset ni sort",
+        ));
+    }
+    // Las etiquetas no están en la caché: cambiarlas no obliga a volver a escanear
+    let metas = QuadletMeta::read_all(&app_state.pool).await?;
 
-            // Filtrar por status si se especifica
-            if let Some(status_filter) = &params.status {
-                let target_status = match status_filter.to_lowercase().as_str() {
-                    "active" => Some(crate::models::QuadletStatus::Active),
-                    "inactive" => Some(crate::models::QuadletStatus::Inactive),
-                    "failed" => Some(crate::models::QuadletStatus::Failed),
-                    "activating" => Some(crate::models::QuadletStatus::Activating),
-                    "deactivating" => Some(crate::models::QuadletStatus::Deactivating),
-                    "unknown" => Some(crate::models::QuadletStatus::Unknown),
-                    _ => None,
-                };
-
-                if let Some(target) = target_status {
-                    quadlets.retain(|q| q.status == Some(target));
+    // En modo ndjson cada quadlet se envía en cuanto se descubre
+    if ndjson {
+        let search = params.search();
+        let quadlets = system::discover_quadlets_stream(app_state.bus.clone())
+            .map_ok(move |mut quadlet| {
+                attach_meta(&mut quadlet, &metas);
+                quadlet
+            })
+            .try_filter(move |quadlet| {
+                let matches = params.matches(quadlet);
+                let search = search.clone();
+                let quadlet = quadlet.clone();
+                async move {
+                    match search {
+                        Some(search) => matches && search.matches(&quadlet).await,
+                        None => matches,
+                    }
                 }
+            });
+        return Ok((
+            [(header::CONTENT_TYPE, "application/x-ndjson")],
+            ndjson_body(quadlets),
+        )
+            .into_response());
+    }
+
+    let mut quadlets = app_state
+        .discover_cache
+        .get_or_refresh(|| system::discover_quadlets(&app_state.bus))
+        .await
+        .map_err(|e| AppError::StorageError(format!("Error discovering quadlets: {}", e)))?;
+    for quadlet in &mut quadlets {
+        attach_meta(quadlet, &metas);
+    }
+    quadlets.retain(|q| params.matches(q));
+    if let Some(search) = params.search() {
+        let mut found = Vec::with_capacity(quadlets.len());
+        for quadlet in quadlets {
+            if search.matches(&quadlet).await {
+                found.push(quadlet);
+            }
+        }
+        quadlets = found;
+    }
+    let (quadlets, total) = params.page().apply(quadlets)?;
+    Ok(CustomResponse::paginated(StatusCode::OK, "quadlets", quadlets, total).into_response())
+}
+
+/// Añade a un quadlet descubierto las etiquetas y la descripción de la base de datos
+fn attach_meta(quadlet: &mut QuadletInfo, metas: &HashMap<String, QuadletMeta>) {
+    let file = format!("{}.{}", quadlet.name, quadlet.kind.as_str());
+    quadlet.meta = metas.get(&file).cloned();
+}
+
+/// Guarda las etiquetas y la descripción de un quadlet, sin tocar su fichero.
+/// Sin etiquetas ni descripción se borran
+async fn save_meta(
+    State(app_state): State<Arc<AppState>>,
+    claims: Option<Extension<TokenClaims>>,
+    Path((extension, name)): Path<(String, String)>,
+    Json(meta): Json<QuadletMeta>,
+) -> Result<CustomResponse<QuadletMeta>, AppError> {
+    let result = async {
+        let quadlet = Quadlet::new(&name, &extension, None)?;
+        if !quadlet.exists().await? {
+            return Err(AppError::not_found(&quadlet.full_name()));
+        }
+        let meta = meta.normalized()?;
+        meta.save(&app_state.pool, &quadlet.full_name()).await?;
+        Ok(CustomResponse::api(StatusCode::OK, "meta", meta))
+    }
+    .await;
+    let target = format!("{}.{}", name, extension);
+    audit(
+        &app_state,
+        claims.as_deref(),
+        "meta",
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    result
+}
+
+#[derive(Deserialize)]
+pub struct LintQuery {
+    /// Incluir también los quadlets sin problemas
+    #[serde(default)]
+    pub all: bool,
+}
+
+/// Revisa la sintaxis y la validez de todos los quadlets del directorio sin tocar
+/// nada. Por defecto solo devuelve los que tienen algún problema
+async fn lint_quadlets(
+    Query(query): Query<LintQuery>,
+) -> Result<CustomResponse<Vec<QuadletLint>>, AppError> {
+    let results = lint_dir(&get_quadlet_dir(), query.all).await?;
+    Ok(CustomResponse::api(StatusCode::OK, "lint", results))
+}
+
+/// Revisión de los quadlets de `dir`. Un directorio que todavía no existe no tiene
+/// nada que revisar
+async fn lint_dir(dir: &std::path::Path, all: bool) -> Result<Vec<QuadletLint>, AppError> {
+    let io_error = |e| AppError::from_io(e, &dir.display().to_string());
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(io_error(e)),
+    };
+    let mut results = Vec::new();
+    while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+        let Some((name, kind)) = entry
+            .file_name()
+            .to_str()
+            .and_then(system::split_quadlet_file_name)
+        else {
+            continue;
+        };
+        let errors = match tokio::fs::read_to_string(entry.path()).await {
+            Ok(content) => core::lint_errors(kind, &content),
+            Err(e) => vec![ValidationError {
+                field: "File".to_string(),
+                message: e.to_string(),
+            }],
+        };
+        if all || !errors.is_empty() {
+            results.push(QuadletLint { name, kind, errors });
+        }
+    }
+    results.sort_by(|a, b| (&a.name, a.kind.as_str()).cmp(&(&b.name, b.kind.as_str())));
+    Ok(results)
+}
+
+/// Unidades de podman que siguen en systemd sin su quadlet, para poder limpiarlas
+async fn read_orphans(
+    State(app_state): State<Arc<AppState>>,
+) -> Result<CustomResponse<Vec<OrphanUnit>>, AppError> {
+    let orphans = system::get_orphan_units(&app_state.bus).await?;
+    Ok(CustomResponse::api(StatusCode::OK, "orphans", orphans))
+}
+
+/// Copia de seguridad de todos los quadlets en un `.tar.gz`. Solo para administradores,
+/// porque los quadlets pueden contener secretos
+async fn export_quadlets(_admin: AdminClaims) -> Response {
+    let file_name = format!(
+        "quadlets-{}.tar.gz",
+        chrono::Utc::now().format("%Y%m%d-%H%M%S")
+    );
+    (
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", file_name),
+            ),
+        ],
+        Body::from_stream(system::export_archive(get_quadlet_dir())),
+    )
+        .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct ImportQuery {
+    /// Sustituir los quadlets que ya existen. Por defecto se conservan
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+/// Restaura una copia de seguridad generada por `export_quadlets`. Devuelve los
+/// ficheros escritos. Solo para administradores
+async fn import_quadlets(
+    State(app_state): State<Arc<AppState>>,
+    AdminClaims(claims): AdminClaims,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<CustomResponse<Vec<String>>, AppError> {
+    let result = async {
+        let imported = system::import_archive(body, get_quadlet_dir(), query.overwrite).await?;
+        info!("{} restaura {} quadlets", claims.sub, imported.len());
+        if imported.is_empty() {
+            return Ok(imported);
+        }
+        app_state.discover_cache.invalidate().await;
+
+        system::daemon_reload(&app_state.bus).await.map_err(|e| {
+            AppError::SystemdError(format!("Imported, but error with daemon reload: {}", e))
+        })?;
+        for (name, kind) in imported
+            .iter()
+            .filter_map(|f| system::split_quadlet_file_name(f))
+        {
+            publish_status(&app_state, &name, kind).await;
+        }
+        Ok(imported)
+    }
+    .await;
+    // Un registro por restauración, con los ficheros escritos si fue bien
+    let target = match &result {
+        Ok(imported) => imported.join(","),
+        Err(_) => String::new(),
+    };
+    audit(
+        &app_state,
+        Some(&claims),
+        "import",
+        &target,
+        &outcome(&result),
+    )
+    .await;
+    let imported = result?;
+    Ok(CustomResponse::api(StatusCode::OK, "imported", imported))
+}
+
+/// Convierte un docker-compose en quadlets y los devuelve como vista previa, sin guardarlos
+async fn import_compose(body: String) -> Result<CustomResponse<Vec<Quadlet>>, AppError> {
+    let quadlets = core::compose_to_quadlets(&body)?;
+    Ok(CustomResponse::api(StatusCode::OK, "preview", quadlets))
+}
+
+/// Número máximo de quadlets por consulta de estado en lote
+const MAX_STATUS_BATCH: usize = 100;
+
+/// Quadlet cuyo estado se pide: solo el nombre o el nombre con su tipo
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum StatusTarget {
+    Name(String),
+    Typed { name: String, kind: QuadletType },
+}
+
+#[derive(Deserialize)]
+pub struct StatusRequest {
+    pub names: Vec<StatusTarget>,
+}
+
+async fn batch_status(
+    State(app_state): State<Arc<AppState>>,
+    Json(payload): Json<StatusRequest>,
+) -> Result<CustomResponse<Vec<QuadletInfo>>, AppError> {
+    if payload.names.len() > MAX_STATUS_BATCH {
+        return Err(AppError::BadRequest(format!(
+            "Como máximo se pueden consultar {} quadlets a la vez",
+            MAX_STATUS_BATCH
+        )));
+    }
+    let targets = payload
+        .names
+        .into_iter()
+        .map(|target| match target {
+            StatusTarget::Name(name) => (name, None),
+            StatusTarget::Typed { name, kind } => (name, Some(kind)),
+        })
+        .collect::<Vec<_>>();
+    for (name, _) in &targets {
+        validate_quadlet_name(name)?;
+    }
+
+    let quadlets = system::get_statuses(&app_state.bus, targets).await;
+    Ok(CustomResponse::api(StatusCode::OK, "quadlets", quadlets))
+}
+
+/// Convierte un receptor del canal de eventos en eventos SSE. Si el cliente se queda
+/// atrás y pierde mensajes, recibe un evento "resync" para que vuelva a pedir el listado
+fn status_events(
+    rx: broadcast::Receiver<QuadletInfo>,
+) -> impl Stream<Item = Result<Event, axum::Error>> {
+    futures_util::stream::unfold(rx, |mut rx| async move {
+        let event = match rx.recv().await {
+            Ok(quadlet) => Event::default().event("status").json_data(quadlet),
+            Err(RecvError::Lagged(skipped)) => {
+                Ok(Event::default().event("resync").data(skipped.to_string()))
+            }
+            Err(RecvError::Closed) => return None,
+        };
+        Some((event, rx))
+    })
+}
+
+async fn quadlet_events(
+    State(app_state): State<Arc<AppState>>,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+    Sse::new(status_events(app_state.events.subscribe())).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::middleware::DEFAULT_MAX_BODY_BYTES;
+    use crate::models::{ErrorResponse, Role};
+
+    async fn next_frame(body: &mut axum::body::BodyDataStream) -> String {
+        let frame = body.next().await.unwrap().unwrap();
+        String::from_utf8(frame.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_events_stream_status_changes() {
+        let state = Arc::new(AppState::for_tests().await);
+        let response = quadlet_events(State(state.clone())).await.into_response();
+        let mut body = response.into_body().into_data_stream();
+
+        state
+            .events
+            .send(QuadletInfo {
+                name: "web".to_string(),
+                kind: QuadletType::Container,
+                status: Some(QuadletStatus::Active),
+                modified: None,
+                health: None,
+                enablement: None,
+                meta: None,
+            })
+            .unwrap();
+
+        let frame = next_frame(&mut body).await;
+        assert!(frame.starts_with("event: status\n"));
+        let data = frame
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let quadlet: QuadletInfo = serde_json::from_str(data).unwrap();
+        assert_eq!(quadlet.name, "web");
+        assert_eq!(quadlet.status, Some(QuadletStatus::Active));
+    }
+
+    #[tokio::test]
+    async fn test_events_resync_when_lagged() {
+        let (tx, rx) = broadcast::channel(1);
+        for name in ["web", "db"] {
+            tx.send(QuadletInfo {
+                name: name.to_string(),
+                kind: QuadletType::Container,
+                status: None,
+                modified: None,
+                health: None,
+                enablement: None,
+                meta: None,
+            })
+            .unwrap();
+        }
+        let body = Sse::new(status_events(rx).take(1))
+            .into_response()
+            .into_body();
+        let frame = next_frame(&mut body.into_data_stream()).await;
+        assert!(frame.starts_with("event: resync\n"));
+        assert!(frame.contains("data: 1\n"));
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_emits_one_object_per_quadlet() {
+        let quadlets = futures_util::stream::iter(["web", "db", "cache"].map(|name| {
+            Ok(QuadletInfo {
+                name: name.to_string(),
+                kind: QuadletType::Volume,
+                status: Some(QuadletStatus::Unknown),
+                modified: None,
+                health: None,
+                enablement: None,
+                meta: None,
+            })
+        }));
+        let bytes = axum::body::to_bytes(ndjson_body(quadlets), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+
+        let names: Vec<String> = body
+            .lines()
+            .map(|line| serde_json::from_str::<QuadletInfo>(line).unwrap().name)
+            .collect();
+        assert_eq!(names, vec!["web", "db", "cache"]);
+        assert!(body.ends_with('\n'));
+    }
+
+    #[tokio::test]
+    async fn test_batch_status_mixes_existing_and_missing() {
+        for (name, ext) in [("batch-data", "volume"), ("batch-net", "network")] {
+            Quadlet::new(name, ext, Some("[Unit]\n".into()))
+                .unwrap()
+                .save()
+                .await
+                .unwrap();
+        }
+        let state = Arc::new(AppState::for_tests().await);
+        let payload: StatusRequest = serde_json::from_value(serde_json::json!({
+            "names": ["batch-data", "batch-missing", {"name": "batch-net", "kind": "network"}]
+        }))
+        .unwrap();
+
+        let response = batch_status(State(state), Json(payload))
+            .await
+            .unwrap()
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let quadlets: Vec<QuadletInfo> = serde_json::from_value(body["data"].clone()).unwrap();
+
+        let summary: Vec<_> = quadlets
+            .iter()
+            .map(|q| (q.name.as_str(), q.kind, q.status))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "batch-data",
+                    QuadletType::Volume,
+                    Some(QuadletStatus::Unknown)
+                ),
+                (
+                    "batch-missing",
+                    QuadletType::Any,
+                    Some(QuadletStatus::Unknown)
+                ),
+                (
+                    "batch-net",
+                    QuadletType::Network,
+                    Some(QuadletStatus::Unknown)
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_status_rejects_oversized_batch() {
+        let state = Arc::new(AppState::for_tests().await);
+        let names = (0..=MAX_STATUS_BATCH)
+            .map(|i| StatusTarget::Name(format!("app{}", i)))
+            .collect();
+        let result = batch_status(State(state), Json(StatusRequest { names })).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_quadlet_busts_discover_cache() {
+        Quadlet::new("cached-vol", "volume", Some("[Volume]\n".into()))
+            .unwrap()
+            .save()
+            .await
+            .unwrap();
+        let state = Arc::new(AppState::for_tests().await);
+        let scans = std::sync::atomic::AtomicUsize::new(0);
+        let scan = || async {
+            scans.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok::<_, AppError>(Vec::new())
+        };
+
+        state.discover_cache.get_or_refresh(scan).await.unwrap();
+        state.discover_cache.get_or_refresh(scan).await.unwrap();
+        assert_eq!(scans.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        delete_quadlet(
+            State(state.clone()),
+            None,
+            Path(("volume".to_string(), "cached-vol".to_string())),
+        )
+        .await
+        .unwrap();
+        state.discover_cache.get_or_refresh(scan).await.unwrap();
+        assert_eq!(scans.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_name_and_content() {
+        Quadlet::new(
+            "search-alpha",
+            "container",
+            Some("[Container]\nImage=docker.io/library/Needle\n".into()),
+        )
+        .unwrap()
+        .save()
+        .await
+        .unwrap();
+        let alpha = QuadletInfo {
+            name: "search-alpha".to_string(),
+            kind: QuadletType::Container,
+            status: None,
+            modified: None,
+            health: None,
+            enablement: None,
+            meta: None,
+        };
+
+        // Por nombre, sin distinguir mayúsculas
+        assert!(Search::new("ALPHA", false).matches(&alpha).await);
+        // El contenido solo se consulta si se pide
+        assert!(!Search::new("needle", false).matches(&alpha).await);
+        assert!(Search::new("needle", true).matches(&alpha).await);
+        assert!(!Search::new("haystack", true).matches(&alpha).await);
+    }
+
+    #[tokio::test]
+    async fn test_discover_filters_by_tag() {
+        for name in ["tagged-prod", "tagged-dev", "tagged-none"] {
+            let path = get_quadlet_dir().join(format!("{}.volume", name));
+            tokio::fs::write(path, "[Volume]\n").await.unwrap();
+        }
+        let state = Arc::new(AppState::for_tests().await);
+        for (name, tags) in [
+            ("tagged-prod", vec!["prod", "monitoring"]),
+            ("tagged-dev", vec!["dev"]),
+        ] {
+            save_meta(
+                State(state.clone()),
+                None,
+                Path(("volume".to_string(), name.to_string())),
+                Json(QuadletMeta {
+                    tags: tags.into_iter().map(str::to_string).collect(),
+                    description: Some(format!("Datos de {}", name)),
+                }),
+            )
+            .await
+            .unwrap();
+        }
+
+        let discover = |tag: &str| {
+            let state = state.clone();
+            let params = DiscoverQuery {
+                kind: None,
+                status: None,
+                format: None,
+                limit: None,
+                offset: None,
+                sort: None,
+                q: Some("tagged-".to_string()),
+                content: None,
+                tag: Some(tag.to_string()).filter(|tag| !tag.is_empty()),
+            };
+            async move {
+                let response = discover_quadlets(State(state), Query(params))
+                    .await
+                    .unwrap();
+                let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+                serde_json::from_value::<Vec<QuadletInfo>>(body["data"].clone()).unwrap()
             }
+        };
+        let prod = discover("prod").await;
+        assert_eq!(prod.len(), 1);
+        assert_eq!(prod[0].name, "tagged-prod");
+        let meta = prod[0].meta.as_ref().unwrap();
+        assert_eq!(meta.tags, vec!["monitoring", "prod"]);
+        assert_eq!(meta.description.as_deref(), Some("Datos de tagged-prod"));
+        assert!(discover("staging").await.is_empty());
+        let all = discover("").await;
+        assert_eq!(all.len(), 3);
+        assert!(all
+            .iter()
+            .any(|q| q.name == "tagged-none" && q.meta.is_none()));
+
+        // Al borrar el quadlet se borran también sus metadatos
+        let _ = delete_quadlet(
+            State(state.clone()),
+            None,
+            Path(("volume".to_string(), "tagged-prod".to_string())),
+        )
+        .await;
+        let metas = QuadletMeta::read_all(&state.pool).await.unwrap();
+        assert!(!metas.contains_key("tagged-prod.volume"));
+        assert!(metas.contains_key("tagged-dev.volume"));
+        for name in ["tagged-dev", "tagged-none"] {
+            let path = get_quadlet_dir().join(format!("{}.volume", name));
+            tokio::fs::remove_file(path).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_meta_of_missing_quadlet_is_not_found() {
+        let state = Arc::new(AppState::for_tests().await);
+        let result = save_meta(
+            State(state),
+            None,
+            Path(("container".to_string(), "meta-missing".to_string())),
+            Json(QuadletMeta::default()),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+    }
 
-            CustomResponse::api(StatusCode::OK, "quadlets", quadlets)
+    #[tokio::test]
+    async fn test_discover_sorts_by_modified() {
+        for name in ["recent-old", "recent-new"] {
+            let path = get_quadlet_dir().join(format!("{}.volume", name));
+            tokio::fs::write(path, "[Volume]\n").await.unwrap();
         }
-        Err(e) => CustomResponse::empty(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            &format!("Error discovering quadlets: {}", e),
-        ),
+        let an_hour_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(3600);
+        std::fs::File::options()
+            .write(true)
+            .open(get_quadlet_dir().join("recent-old.volume"))
+            .unwrap()
+            .set_modified(an_hour_ago)
+            .unwrap();
+
+        let state = Arc::new(AppState::for_tests().await);
+        let params = DiscoverQuery {
+            kind: None,
+            status: None,
+            format: None,
+            limit: None,
+            offset: None,
+            sort: Some("modified".to_string()),
+            q: Some("recent-".to_string()),
+            content: None,
+            tag: None,
+        };
+        let response = discover_quadlets(State(state), Query(params))
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let quadlets: Vec<QuadletInfo> = serde_json::from_value(body["data"].clone()).unwrap();
+        let names: Vec<_> = quadlets.iter().map(|q| q.name.as_str()).collect();
+        assert_eq!(names, vec!["recent-new", "recent-old"]);
+        assert!(quadlets.iter().all(|q| q.modified.is_some()));
+    }
+
+    #[tokio::test]
+    async fn test_summary_flags_malformed_quadlets() {
+        for (name, content) in [
+            (
+                "summary-ok",
+                "[Container]\nImage=nginx\nPublishPort=8080:80\n",
+            ),
+            ("summary-bad", "esto no es un quadlet\n"),
+        ] {
+            let path = get_quadlet_dir().join(format!("{}.container", name));
+            tokio::fs::write(path, content).await.unwrap();
+        }
+        let state = Arc::new(AppState::for_tests().await);
+        let response = read_summaries(State(state)).await.unwrap().into_response();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let summaries: Vec<QuadletSummary> = serde_json::from_value(body["data"].clone()).unwrap();
+
+        let ok = summaries.iter().find(|s| s.name == "summary-ok").unwrap();
+        assert!(!ok.parse_error);
+        assert_eq!(ok.image.as_deref(), Some("nginx"));
+        assert_eq!(ok.ports, vec!["8080:80"]);
+
+        let bad = summaries.iter().find(|s| s.name == "summary-bad").unwrap();
+        assert!(bad.parse_error);
+        assert_eq!(bad.image, None);
+        assert!(bad.ports.is_empty());
+    }
+
+    async fn error_body(response: Response) -> ErrorResponse {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_missing_quadlet_returns_error_response() {
+        let state = Arc::new(AppState::for_tests().await);
+        let response = read_quadlet(
+            State(state),
+            Path(("container".to_string(), "missing-one".to_string())),
+            Query(ReadQuery { redact: false }),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body = error_body(response).await;
+        assert_eq!(body.error, "not_found");
+        assert_eq!(body.status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_read_redacted_quadlet() {
+        let content = "[Container]\nImage=postgres\nEnvironment=POSTGRES_PASSWORD=s3cr3t\n";
+        Quadlet::new("redact-db", "container", Some(content.into()))
+            .unwrap()
+            .save()
+            .await
+            .unwrap();
+        let read = |redact| async move {
+            let state = Arc::new(AppState::for_tests().await);
+            let response = read_quadlet(
+                State(state),
+                Path(("container".to_string(), "redact-db".to_string())),
+                Query(ReadQuery { redact }),
+            )
+            .await
+            .unwrap()
+            .into_response();
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()["data"].clone()
+        };
+
+        let redacted = read(true).await;
+        assert_eq!(
+            redacted["content"],
+            "[Container]\nImage=postgres\nEnvironment=POSTGRES_PASSWORD=***\n"
+        );
+        // El etag es el del fichero real, así que se puede guardar tras leerlo redactado
+        assert_eq!(redacted["etag"], content_etag(content));
+        assert_eq!(read(false).await["content"], content);
+    }
+
+    #[tokio::test]
+    async fn test_read_quadlet_by_file() {
+        use tower::ServiceExt;
+
+        let content = "[Volume]\nLabel=app=by-file\n";
+        Quadlet::new("by-file-data", "volume", Some(content.into()))
+            .unwrap()
+            .save()
+            .await
+            .unwrap();
+        let state = Arc::new(AppState::for_tests().await);
+        let get = |uri: &str| {
+            router()
+                .with_state(state.clone())
+                .oneshot(axum::http::Request::get(uri).body(Body::empty()).unwrap())
+        };
+
+        let response = get("/by-file/by-file-data.volume").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["data"]["name"], "by-file-data");
+        assert_eq!(body["data"]["content"], content);
+
+        let response = get("/by-file/by-file-missing.volume").await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_schema_route_does_not_hide_quadlets() {
+        use tower::ServiceExt;
+
+        let content = "[Volume]\nLabel=app=schema\n";
+        Quadlet::new("schema", "volume", Some(content.into()))
+            .unwrap()
+            .save()
+            .await
+            .unwrap();
+        let state = Arc::new(AppState::for_tests().await);
+        let get = |uri: &str| {
+            router()
+                .with_state(state.clone())
+                .oneshot(axum::http::Request::get(uri).body(Body::empty()).unwrap())
+        };
+        let data = |response: Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()["data"].clone()
+        };
+
+        let response = get("/volume/schema").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(data(response).await["content"], content);
+        let response = get("/schema/volume").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(data(response).await["$schema"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_summary_of_vanished_or_unreadable_file() {
+        let info = |name: &str| QuadletInfo {
+            name: name.to_string(),
+            kind: QuadletType::Container,
+            status: None,
+            modified: None,
+            health: None,
+            enablement: None,
+            meta: None,
+        };
+        assert!(container_summary(info("summary-gone")).await.is_none());
+
+        // Un directorio con nombre de quadlet no se puede leer como fichero
+        tokio::fs::create_dir_all(get_quadlet_dir().join("summary-dir.container"))
+            .await
+            .unwrap();
+        let summary = container_summary(info("summary-dir")).await.unwrap();
+        assert!(summary.parse_error);
+        assert!(summary.image.is_none());
+        tokio::fs::remove_dir(get_quadlet_dir().join("summary-dir.container"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_discover_rejects_paging() {
+        use tower::ServiceExt;
+
+        let state = Arc::new(AppState::for_tests().await);
+        for query in ["limit=5", "offset=1", "sort=status"] {
+            let uri = format!("/discover?format=ndjson&{}", query);
+            let response = router()
+                .with_state(state.clone())
+                .oneshot(axum::http::Request::get(&uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{}", query);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lint_missing_dir_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let lints = lint_dir(&dir.path().join("no-existe"), true).await.unwrap();
+        assert!(lints.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_lint_reports_invalid_quadlets() {
+        let dir = get_quadlet_dir();
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        for (file, content) in [
+            ("lint-ok.container", "[Container]\nImage=nginx\n"),
+            ("lint-ok.volume", "[Volume]\n"),
+            (
+                "lint-no-image.container",
+                "[Container]\nContainerName=web\n",
+            ),
+            ("lint-broken.network", "[Network\nSubnet=10.0.0.0/24\n"),
+        ] {
+            tokio::fs::write(dir.join(file), content).await.unwrap();
+        }
+        let lint = |all| async move {
+            lint_quadlets(Query(LintQuery { all }))
+                .await
+                .unwrap()
+                .into_response()
+        };
+        let data = |response: Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            let results: Vec<QuadletLint> = serde_json::from_value(body["data"].clone()).unwrap();
+            // El directorio es compartido con el resto de tests
+            results
+                .into_iter()
+                .filter(|lint| lint.name.starts_with("lint-"))
+                .collect::<Vec<_>>()
+        };
+
+        let problems = data(lint(false).await).await;
+        let names: Vec<_> = problems.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, vec!["lint-broken", "lint-no-image"]);
+        assert_eq!(problems[0].kind, QuadletType::Network);
+        assert_eq!(problems[0].errors[0].field, "Syntax");
+        assert_eq!(problems[1].errors[0].field, "Container.Image");
+
+        let all = data(lint(true).await).await;
+        assert_eq!(all.len(), 4);
+        assert!(all
+            .iter()
+            .filter(|l| l.name == "lint-ok")
+            .all(|l| l.errors.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_read_quadlet_by_file_unknown_extension() {
+        for filename in ["web.txt", "web.any", "web"] {
+            let state = Arc::new(AppState::for_tests().await);
+            let result = read_quadlet_by_file(
+                State(state),
+                Path(filename.to_string()),
+                Query(ReadQuery { redact: false }),
+            )
+            .await;
+            assert!(
+                matches!(result, Err(AppError::BadRequest(_))),
+                "{}",
+                filename
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_requests_return_error_response() {
+        let state = Arc::new(AppState::for_tests().await);
+        let response = read_quadlet(
+            State(state),
+            Path(("container".to_string(), "..".to_string())),
+            Query(ReadQuery { redact: false }),
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error_body(response).await.error, "validation_error");
+
+        let response = read_quadlets(Path("exe".to_string()), Query(PageQuery::default()))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = error_body(response).await;
+        assert_eq!(body.error, "bad_request");
+        assert_eq!(body.status, 400);
+    }
+
+    #[tokio::test]
+    async fn test_mutations_publish_events() {
+        Quadlet::new(
+            "events-src",
+            "container",
+            Some("[Container]\nImage=nginx\n".into()),
+        )
+        .unwrap()
+        .save()
+        .await
+        .unwrap();
+        let state = Arc::new(AppState::for_tests().await);
+        let mut rx = state.events.subscribe();
+
+        clone_quadlet(
+            State(state.clone()),
+            None,
+            Path(("container".to_string(), "events-src".to_string())),
+            Json(CloneRequest {
+                new_name: "events-dst".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+        delete_quadlet(
+            State(state),
+            None,
+            Path(("container".to_string(), "events-dst".to_string())),
+        )
+        .await
+        .unwrap();
+
+        for _ in 0..2 {
+            let info = rx.try_recv().unwrap();
+            assert_eq!(info.name, "events-dst");
+            assert_eq!(info.kind, QuadletType::Container);
+            assert!(info.status.is_some());
+        }
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_save_with_stale_etag_conflicts() {
+        let original = "[Container]\nImage=nginx\n";
+        Quadlet::new("etag-web", "container", Some(original.into()))
+            .unwrap()
+            .save()
+            .await
+            .unwrap();
+        let state = Arc::new(AppState::for_tests().await);
+        let save = |content: &str, expected_etag: Option<String>| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, "application/json".parse().unwrap());
+            let body = serde_json::json!({"content": content, "expected_etag": expected_etag});
+            save_quadlet(
+                State(state.clone()),
+                None,
+                Path(("container".to_string(), "etag-web".to_string())),
+                Query(SaveQuery::default()),
+                headers,
+                Bytes::from(body.to_string()),
+            )
+        };
+
+        let result = save("[Container]\nImage=caddy\n", Some(content_etag("otro"))).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        let mut current = Quadlet::new("etag-web", "container", None).unwrap();
+        current.read().await.unwrap();
+        assert_eq!(current.content.as_deref(), Some(original));
+
+        // Sin etag se sobrescribe. El daemon-reload falla sin D-Bus, pero el
+        // fichero ya está guardado
+        let _ = save("[Container]\nImage=caddy\n", None).await;
+        current.read().await.unwrap();
+        assert_eq!(
+            current.content.as_deref(),
+            Some("[Container]\nImage=caddy\n")
+        );
+
+        // Con el etag vigente tampoco hay conflicto
+        let result = save("[Container]\nImage=httpd\n", current.etag.clone()).await;
+        assert!(!matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_save_warns_about_dangling_network() {
+        let content = "[Container]\nImage=nginx\nNetwork=dangling.network\n";
+        let quadlet = Quadlet::new("dangling-web", "container", Some(content.into())).unwrap();
+        let warnings = save_warnings(&quadlet, &get_quadlet_dir()).await.unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "Container.Network");
+
+        // En modo estricto el aviso es un error y no se guarda nada
+        let state = Arc::new(AppState::for_tests().await);
+        let result = save_quadlet(
+            State(state),
+            None,
+            Path(("container".to_string(), "dangling-web".to_string())),
+            Query(SaveQuery {
+                strict: true,
+                ..Default::default()
+            }),
+            content_type("text/plain"),
+            Bytes::from(content),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+        assert!(!quadlet.exists().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_first_save_into_missing_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let quadlet_dir = dir.path().join("containers/systemd");
+        let content = "[Container]\nImage=nginx\nNetwork=first.network\n";
+        let quadlet = Quadlet::new("first-web", "container", Some(content.into())).unwrap();
+
+        // Sin directorio no hay error, solo el aviso de la red que no existe
+        let warnings = save_warnings(&quadlet, &quadlet_dir).await.unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "Container.Network");
+
+        let path = quadlet_dir.join(quadlet.full_name());
+        crate::models::write_quadlet_file(&path, content)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+    }
+
+    #[tokio::test]
+    async fn test_save_warns_about_port_in_use() {
+        let content = "[Container]\nImage=nginx\nPublishPort=47123:80\n";
+        let taken = Quadlet::new("port-owner", "container", Some(content.into())).unwrap();
+        taken.save().await.unwrap();
+        // El propio fichero no cuenta como conflicto
+        assert!(save_warnings(&taken, &get_quadlet_dir())
+            .await
+            .unwrap()
+            .is_empty());
+
+        let quadlet = Quadlet::new("port-thief", "container", Some(content.into())).unwrap();
+        let warnings = save_warnings(&quadlet, &get_quadlet_dir()).await.unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field, "Container.PublishPort");
+        assert!(warnings[0].message.contains("'port-owner.container'"));
+    }
+
+    #[tokio::test]
+    async fn test_save_expands_allowed_vars() {
+        std::env::set_var("QUADLY_TEST_DATA_DIR", "/srv/data");
+        std::env::set_var("QUADLY_TEST_TOKEN", "s3cr3t");
+        let state = AppState {
+            expand_vars: vec!["QUADLY_TEST_DATA_DIR".to_string()],
+            ..AppState::for_tests().await
+        };
+        let template = "[Container]\nImage=nginx\nVolume=${QUADLY_TEST_DATA_DIR}/web:/data\n\
+                        Environment=TOKEN=${QUADLY_TEST_TOKEN}\n";
+        let query = SaveQuery {
+            expand: true,
+            store_template: true,
+            ..Default::default()
+        };
+        let quadlet = write_quadlet(
+            &state,
+            "container",
+            "expand-web",
+            &query,
+            &content_type("text/plain"),
+            &Bytes::from(template),
+        )
+        .await
+        .unwrap();
+
+        // La variable permitida se expande; la otra, aunque exista, no se filtra
+        let saved = tokio::fs::read_to_string(quadlet.path()).await.unwrap();
+        assert_eq!(
+            saved,
+            "[Container]\nImage=nginx\nVolume=/srv/data/web:/data\n\
+             Environment=TOKEN=${QUADLY_TEST_TOKEN}\n"
+        );
+        let stored = tokio::fs::read_to_string(quadlet.template_path())
+            .await
+            .unwrap();
+        assert_eq!(stored, template);
+        // Fuera de cualquier subdirectorio y con un sufijo que no es de quadlet
+        assert_eq!(quadlet.template_path().parent(), quadlet.path().parent());
+        assert!(system::split_quadlet_file_name("expand-web.container.tmpl").is_none());
+
+        quadlet.delete().await.unwrap();
+        assert!(!quadlet.template_path().exists());
+    }
+
+    #[tokio::test]
+    async fn test_save_without_expand_keeps_vars() {
+        std::env::set_var("QUADLY_TEST_HOST", "nas");
+        let state = AppState {
+            expand_vars: vec!["QUADLY_TEST_HOST".to_string()],
+            ..AppState::for_tests().await
+        };
+        let content = "[Container]\nImage=nginx\nHostName=${QUADLY_TEST_HOST}\n";
+        let quadlet = write_quadlet(
+            &state,
+            "container",
+            "no-expand-web",
+            &SaveQuery::default(),
+            &content_type("text/plain"),
+            &Bytes::from(content),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            tokio::fs::read_to_string(quadlet.path()).await.unwrap(),
+            content
+        );
+        assert!(!quadlet.template_path().exists());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_saves_with_same_etag() {
+        let state = AppState::for_tests().await;
+        let original = write_quadlet(
+            &state,
+            "container",
+            "locked-web",
+            &SaveQuery::default(),
+            &content_type("text/plain"),
+            &Bytes::from("[Container]\nImage=nginx\n"),
+        )
+        .await
+        .unwrap();
+        let mut headers = content_type("text/plain");
+        headers.insert(
+            header::IF_MATCH,
+            original.etag.as_deref().unwrap().parse().unwrap(),
+        );
+
+        // Los dos leyeron la misma versión: solo uno puede guardar encima
+        let save = |image: &'static str| {
+            let body = Bytes::from(format!("[Container]\nImage={}\n", image));
+            let (state, headers) = (&state, &headers);
+            async move {
+                write_quadlet(
+                    state,
+                    "container",
+                    "locked-web",
+                    &SaveQuery::default(),
+                    headers,
+                    &body,
+                )
+                .await
+            }
+        };
+        let (first, second) = tokio::join!(save("caddy"), save("httpd"));
+        let (saved, rejected) = match (first, second) {
+            (Ok(saved), Err(rejected)) | (Err(rejected), Ok(saved)) => (saved, rejected),
+            (first, second) => panic!(
+                "se esperaba un solo guardado: {:?} {:?}",
+                first.err(),
+                second.err()
+            ),
+        };
+        assert!(matches!(rejected, AppError::Conflict(_)));
+        let on_disk = tokio::fs::read_to_string(saved.path()).await.unwrap();
+        assert_eq!(Some(on_disk.as_str()), saved.content.as_deref());
+        assert_eq!(saved.etag.as_deref(), Some(content_etag(&on_disk).as_str()));
+
+        saved.delete().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_relocate_to_same_scope_is_rejected() {
+        let state = Arc::new(AppState::for_tests().await);
+        let result = relocate_quadlet(
+            State(state),
+            AdminClaims(admin_claims()),
+            Path(("container".to_string(), "relocate-web".to_string())),
+            Json(RelocateRequest {
+                to_scope: SystemdScope::User,
+            }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_stop_all_only_for_containers_and_pods() {
+        let state = Arc::new(AppState::for_tests().await);
+        let result = stop_all_quadlets(
+            State(state),
+            AdminClaims(admin_claims()),
+            Query(StopAllQuery {
+                kind: Some(QuadletType::Volume),
+            }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_save_writes_audit_entry() {
+        let state = Arc::new(AppState::for_tests().await);
+        // Lo que responda systemd depende de la máquina: solo importa el registro
+        let result = save_quadlet(
+            State(state.clone()),
+            Some(Extension(admin_claims())),
+            Path(("container".to_string(), "audit-web".to_string())),
+            Query(SaveQuery::default()),
+            content_type("text/plain"),
+            Bytes::from("[Container]\nImage=nginx\n"),
+        )
+        .await;
+
+        let (entries, total) = AuditEntry::read_page(&state.pool, 10, 0).await.unwrap();
+        assert_eq!(total, 1);
+        assert_eq!(entries[0].username, "admin");
+        assert_eq!(entries[0].action, "save");
+        assert_eq!(entries[0].target, "audit-web.container");
+        assert_eq!(entries[0].result, outcome(&result));
+    }
+
+    #[tokio::test]
+    async fn test_clone_writes_audit_entry() {
+        Quadlet::new("audit-src", "volume", Some("[Volume]\n".into()))
+            .unwrap()
+            .save()
+            .await
+            .unwrap();
+        let state = Arc::new(AppState::for_tests().await);
+        let claims = TokenClaims {
+            sub: "bob".to_string(),
+            role: Role::Viewer,
+            iat: 0,
+            exp: usize::MAX,
+        };
+        clone_quadlet(
+            State(state.clone()),
+            Some(Extension(claims)),
+            Path(("volume".to_string(), "audit-src".to_string())),
+            Json(CloneRequest {
+                new_name: "audit-copy".to_string(),
+            }),
+        )
+        .await
+        .unwrap();
+
+        let (entries, _) = AuditEntry::read_page(&state.pool, 10, 0).await.unwrap();
+        assert_eq!(entries[0].username, "bob");
+        assert_eq!(entries[0].action, "clone");
+        assert_eq!(entries[0].target, "audit-src.volume -> audit-copy");
+        assert_eq!(entries[0].result, "ok");
+        Quadlet::new("audit-copy", "volume", None)
+            .unwrap()
+            .delete()
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_effective_config_applies_dropins() {
+        let content = "[Container]\nImage=nginx\nEnvironment=TZ=UTC\n";
+        Quadlet::new("effective-web", "container", Some(content.into()))
+            .unwrap()
+            .save()
+            .await
+            .unwrap();
+        let dir = get_quadlet_dir().join("effective-web.container.d");
+        system::write_dropin(&dir, "10-env.conf", "[Container]\nEnvironment=DEBUG=1\n")
+            .await
+            .unwrap();
+
+        let response = read_effective(Path(("container".to_string(), "effective-web".to_string())))
+            .await
+            .unwrap()
+            .into_response();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(
+            body["data"]["Container"],
+            serde_json::json!({"Image": "nginx", "Environment": "TZ=UTC, DEBUG=1"})
+        );
+    }
+
+    fn content_type(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, value.parse().unwrap());
+        headers
+    }
+
+    /// Claims de un administrador con un token que no caduca
+    fn admin_claims() -> TokenClaims {
+        TokenClaims {
+            sub: "admin".to_string(),
+            role: Role::Admin,
+            iat: 0,
+            exp: usize::MAX,
+        }
+    }
+
+    #[test]
+    fn test_save_request_accepts_bare_content() {
+        let request: SaveRequest = serde_json::from_str("\"[Container]\\n\"").unwrap();
+        assert!(matches!(request, SaveRequest::Content(_)));
+    }
+
+    #[test]
+    fn test_save_body_as_text_plain() {
+        let mut headers = content_type("text/plain; charset=utf-8");
+        headers.insert(header::IF_MATCH, "\"abc\"".parse().unwrap());
+        let request = SaveRequest::from_body(&headers, b"[Container]\nImage=nginx\n").unwrap();
+        match request {
+            SaveRequest::Versioned {
+                content,
+                expected_etag,
+            } => {
+                assert_eq!(content, "[Container]\nImage=nginx\n");
+                assert_eq!(expected_etag.as_deref(), Some("abc"));
+            }
+            _ => panic!("se esperaba el contenido en texto plano"),
+        }
+    }
+
+    #[test]
+    fn test_save_body_as_json() {
+        let headers = content_type("application/json");
+        let request =
+            SaveRequest::from_body(&headers, b"\"[Container]\\nImage=nginx\\n\"").unwrap();
+        assert!(matches!(request, SaveRequest::Content(c) if c == "[Container]\nImage=nginx\n"));
+
+        // Texto sin comillas enviado como JSON
+        let result = SaveRequest::from_body(&headers, b"[Container]\nImage=nginx\n");
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_reload_requires_admin() {
+        use axum::middleware;
+        use jsonwebtoken::{encode, EncodingKey, Header};
+        use tower::ServiceExt;
+
+        let state = Arc::new(AppState::for_tests().await);
+        let app = router()
+            .layer(middleware::from_fn_with_state(
+                state.clone(),
+                crate::api::authenticate,
+            ))
+            .with_state(state.clone());
+        let token = |role: Role| {
+            let now = chrono::Utc::now().timestamp() as usize;
+            let claims = crate::models::TokenClaims {
+                sub: "alice".to_string(),
+                role,
+                iat: now,
+                exp: now + 60,
+            };
+            let key = EncodingKey::from_secret(state.secret.as_bytes());
+            encode(&Header::default(), &claims, &key).unwrap()
+        };
+        let reload = |token: String| {
+            axum::http::Request::post("/reload")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app
+            .clone()
+            .oneshot(reload(token(Role::Viewer)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let anonymous = axum::http::Request::post("/reload")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(anonymous).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // El administrador pasa el guard. Lo que haga la recarga depende de si
+        // hay D-Bus en la máquina, así que solo se comprueban los permisos
+        let response = app.oneshot(reload(token(Role::Admin))).await.unwrap();
+        assert_ne!(response.status(), StatusCode::FORBIDDEN);
+        assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_dropins_of_a_pod() {
+        let path = |file: &str| {
+            Path((
+                "pod".to_string(),
+                "dropin-app".to_string(),
+                file.to_string(),
+            ))
+        };
+        let state = Arc::new(AppState::for_tests().await);
+        // Sin el quadlet no hay unidad a la que añadir nada
+        let result = save_dropin(
+            State(state.clone()),
+            AdminClaims(admin_claims()),
+            path("override.conf"),
+            "[Service]\nRestart=always\n".to_string(),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+        assert!(!get_unit_dir().join("dropin-app-pod.service.d").exists());
+
+        Quadlet::new("dropin-app", "pod", Some("[Pod]\n".into()))
+            .unwrap()
+            .save()
+            .await
+            .unwrap();
+        // El daemon-reload falla sin D-Bus, pero el drop-in ya está escrito
+        let _ = save_dropin(
+            State(state),
+            AdminClaims(admin_claims()),
+            path("override.conf"),
+            "[Service]\nRestart=always\n".to_string(),
+        )
+        .await;
+        assert!(get_unit_dir()
+            .join("dropin-app-pod.service.d/override.conf")
+            .exists());
+
+        let response = read_dropins(Path(("pod".to_string(), "dropin-app".to_string())))
+            .await
+            .unwrap()
+            .into_response();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["data"], serde_json::json!(["override.conf"]));
+
+        let result = read_dropin(path("../../etc.conf")).await;
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_pull_requires_image() {
+        Quadlet::new(
+            "pull-noimage",
+            "container",
+            Some("[Container]\nExec=sleep 1\n".into()),
+        )
+        .unwrap()
+        .save()
+        .await
+        .unwrap();
+        let state = Arc::new(AppState::for_tests().await);
+        let result = pull_image(
+            State(state.clone()),
+            AdminClaims(admin_claims()),
+            Path(("container".to_string(), "pull-noimage".to_string())),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+        let (entries, _) = AuditEntry::read_page(&state.pool, 10, 0).await.unwrap();
+        assert_eq!(entries[0].action, "pull");
+        assert_ne!(entries[0].result, "ok");
+    }
+
+    #[tokio::test]
+    async fn test_apply_reports_failed_start_after_saving() {
+        let state = AppState::for_tests().await;
+        let content = "[Container]\nImage=nginx\n";
+        let quadlet = write_quadlet(
+            &state,
+            "container",
+            "apply-web",
+            &SaveQuery::default(),
+            &content_type("text/plain"),
+            &Bytes::from(content),
+        )
+        .await
+        .unwrap();
+        // systemd recarga bien pero la unidad no arranca
+        let manager = system::FakeManager {
+            fail_start: true,
+            ..Default::default()
+        };
+        let applied = system::dispatch_apply(&manager, "apply-web.service").await;
+        let response = applied_response(
+            &state,
+            &admin_claims(),
+            quadlet,
+            "apply-web.service",
+            applied,
+        )
+        .await
+        .into_response();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["data"]["failed_step"], "start");
+        assert!(body["data"]["error"]
+            .as_str()
+            .unwrap()
+            .contains("failed to start"));
+        assert_eq!(body["data"]["quadlet"]["content"], content);
+
+        let mut saved = Quadlet::new("apply-web", "container", None).unwrap();
+        saved.read().await.unwrap();
+        assert_eq!(saved.content.as_deref(), Some(content));
+        let (entries, _) = AuditEntry::read_page(&state.pool, 10, 0).await.unwrap();
+        assert_eq!(entries[0].action, "apply");
+        assert!(entries[0].result.starts_with("start: "));
+    }
+
+    #[tokio::test]
+    async fn test_patch_quadlet_edits_file() {
+        Quadlet::new(
+            "patch-web",
+            "container",
+            Some("[Container]\nImage=nginx\n".into()),
+        )
+        .unwrap()
+        .save()
+        .await
+        .unwrap();
+        let state = Arc::new(AppState::for_tests().await);
+        // El daemon-reload falla sin D-Bus, pero el fichero ya está guardado
+        let _ = patch_quadlet(
+            State(state),
+            None,
+            Path(("container".to_string(), "patch-web".to_string())),
+            Json(KeyUpdate {
+                section: "Container".to_string(),
+                key: "Image".to_string(),
+                value: Some("docker.io/nginx".to_string()),
+            }),
+        )
+        .await;
+        let mut patched = Quadlet::new("patch-web", "container", None).unwrap();
+        patched.read().await.unwrap();
+        assert_eq!(
+            patched.content.as_deref(),
+            Some("[Container]\nImage=docker.io/nginx\n")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_diff_against_missing_quadlet() {
+        let result = diff_quadlet(
+            Path(("container".to_string(), "diff-new".to_string())),
+            Json("[Container]\nImage=nginx\n".to_string()),
+        )
+        .await;
+        let response = result.unwrap().into_response();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["data"]["has_changes"], true);
+        assert!(body["data"]["diff"]
+            .as_str()
+            .unwrap()
+            .contains("@@ -0,0 +1,2 @@\n+[Container]\n+Image=nginx\n"));
+    }
+
+    #[tokio::test]
+    async fn test_create_from_template() {
+        let state = Arc::new(AppState::for_tests().await);
+        let request = || TemplateRequest {
+            template: "named-volume".to_string(),
+            values: HashMap::new(),
+        };
+        let result = create_from_template(
+            State(state.clone()),
+            None,
+            Path(("volume".to_string(), "tpl-data".to_string())),
+            Json(request()),
+        )
+        .await;
+        assert!(result.is_ok());
+
+        let mut created = Quadlet::new("tpl-data", "volume", None).unwrap();
+        created.read().await.unwrap();
+        assert_eq!(
+            created.content.as_deref(),
+            Some("[Volume]\nVolumeName=tpl-data\n")
+        );
+
+        // La plantilla es de volumen, no de contenedor
+        let result = create_from_template(
+            State(state),
+            None,
+            Path(("container".to_string(), "tpl-data".to_string())),
+            Json(request()),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_does_not_touch_systemd() {
+        let state = Arc::new(AppState::for_tests().await);
+        let response = run_action(
+            State(state.clone()),
+            None,
+            Path(("volume".to_string(), "dry-data".to_string())),
+            Query(ActionQuery {
+                dry_run: Some(true),
+            }),
+            Json(ActionRequest {
+                action: UnitAction::Stop,
+            }),
+        )
+        .await
+        .unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let preview: ActionPreview = serde_json::from_value(body["data"].clone()).unwrap();
+        assert_eq!(preview.current, QuadletStatus::Unknown);
+        assert_eq!(preview.expected, QuadletStatus::Inactive);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_action_is_unprocessable() {
+        use tower::ServiceExt;
+
+        let state = Arc::new(AppState::for_tests().await);
+        let app = router().with_state(state);
+        let request = axum::http::Request::post("/volume/dry-data/action?dry_run=true")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(r#"{"action": "explode"}"#))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert!(String::from_utf8_lossy(&bytes).contains("unknown variant `explode`"));
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_is_rejected() {
+        use tower::ServiceExt;
+
+        let state = Arc::new(AppState::for_tests().await);
+        let app = router().with_state(state);
+        let body = format!(
+            "[Container]\nImage=alpine\n#{}\n",
+            "x".repeat(DEFAULT_MAX_BODY_BYTES)
+        );
+        // Con Content-Length lo rechaza la capa; sin él, el extractor al leerlo
+        for content_length in [true, false] {
+            let mut request = axum::http::Request::post("/container/huge-web");
+            if content_length {
+                request = request.header(header::CONTENT_LENGTH, body.len());
+            }
+            let request = request.body(Body::from(body.clone())).unwrap();
+            let response = app.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let error: ErrorResponse = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(error.error, "payload_too_large");
+        }
+        assert!(!get_quadlet_dir().join("huge-web.container").exists());
+    }
+
+    #[tokio::test]
+    async fn test_podman_run_only_for_containers() {
+        let result = export_podman_run(Path(("volume".to_string(), "data".to_string()))).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_clone_quadlet() {
+        let source = Quadlet::new(
+            "clone-src",
+            "container",
+            Some("[Container]\nImage=nginx\n".into()),
+        )
+        .unwrap();
+        source.save().await.unwrap();
+
+        let state = Arc::new(AppState::for_tests().await);
+        let result = clone_quadlet(
+            State(state),
+            None,
+            Path(("container".to_string(), "clone-src".to_string())),
+            Json(CloneRequest {
+                new_name: "clone-dst".to_string(),
+            }),
+        )
+        .await;
+        assert!(result.is_ok());
+
+        let mut cloned = Quadlet::new("clone-dst", "container", None).unwrap();
+        cloned.read().await.unwrap();
+        assert_eq!(cloned.content, source.content);
+    }
+
+    #[tokio::test]
+    async fn test_clone_quadlet_rejects_existing_name() {
+        for name in ["collision-src", "collision-dst"] {
+            Quadlet::new(
+                name,
+                "container",
+                Some(format!("[Container]\nImage={}\n", name)),
+            )
+            .unwrap()
+            .save()
+            .await
+            .unwrap();
+        }
+
+        let state = Arc::new(AppState::for_tests().await);
+        let result = clone_quadlet(
+            State(state),
+            None,
+            Path(("container".to_string(), "collision-src".to_string())),
+            Json(CloneRequest {
+                new_name: "collision-dst".to_string(),
+            }),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        // El destino no se ha sobrescrito
+        let mut existing = Quadlet::new("collision-dst", "container", None).unwrap();
+        existing.read().await.unwrap();
+        assert_eq!(
+            existing.content.as_deref(),
+            Some("[Container]\nImage=collision-dst\n")
+        );
     }
 }
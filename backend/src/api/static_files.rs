@@ -0,0 +1,46 @@
+use std::path::Path;
+use tower_http::services::{ServeDir, ServeFile};
+
+/// Sirve el build del frontend. Las rutas que no corresponden a ningún fichero
+/// devuelven `index.html` para que el enrutado lo resuelva React
+pub fn static_files(dir: &str) -> ServeDir<ServeFile> {
+    ServeDir::new(dir).fallback(ServeFile::new(Path::new(dir).join("index.html")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    async fn get(dir: &Path, uri: &str) -> (StatusCode, Vec<u8>) {
+        let response = static_files(dir.to_str().unwrap())
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(Body::new(response.into_body()), usize::MAX)
+            .await
+            .unwrap();
+        (status, bytes.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_spa_fallback_and_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("index.html"), "<html>quadly</html>").unwrap();
+        std::fs::create_dir(dir.path().join("assets")).unwrap();
+        std::fs::write(dir.path().join("assets/app.js"), "console.log(1)").unwrap();
+
+        let (status, body) = get(dir.path(), "/quadlets/container/web").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"<html>quadly</html>");
+
+        let (status, body) = get(dir.path(), "/assets/app.js").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"console.log(1)");
+    }
+}
@@ -0,0 +1,26 @@
+use axum::{body::Body, extract::Request, response::Response};
+use std::time::Duration;
+use tracing::{field::Empty, Span};
+
+/// Span de cada petición. `sub` lo rellena `authenticate` si hay un token válido.
+/// Solo se registra la ruta, sin la query, para no volcar parámetros al log
+pub fn request_span(req: &Request<Body>) -> Span {
+    tracing::info_span!(
+        "request",
+        method = %req.method(),
+        path = req.uri().path(),
+        sub = Empty,
+        status = Empty,
+        latency_ms = Empty,
+    )
+}
+
+pub fn on_request(_req: &Request<Body>, _span: &Span) {
+    tracing::debug!("started");
+}
+
+pub fn on_response(res: &Response<Body>, latency: Duration, span: &Span) {
+    span.record("status", res.status().as_u16());
+    span.record("latency_ms", latency.as_millis() as u64);
+    tracing::info!("finished");
+}
@@ -0,0 +1,237 @@
+use crate::models::{AppError, Quadlet};
+use serde::Deserialize;
+use serde_yaml_ng::Value;
+use std::collections::BTreeMap;
+
+/// Subconjunto de un `docker-compose.yml` que sabemos traducir a quadlets
+#[derive(Debug, Deserialize)]
+struct ComposeFile {
+    #[serde(default)]
+    services: BTreeMap<String, ComposeService>,
+    #[serde(default)]
+    networks: BTreeMap<String, Option<Value>>,
+    #[serde(default)]
+    volumes: BTreeMap<String, Option<Value>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ComposeService {
+    image: Option<String>,
+    #[serde(default)]
+    ports: Vec<Value>,
+    #[serde(default)]
+    environment: Option<Value>,
+    #[serde(default)]
+    volumes: Vec<Value>,
+    #[serde(default)]
+    depends_on: Option<Value>,
+    #[serde(default)]
+    networks: Option<Value>,
+}
+
+/// Convierte un escalar de YAML (texto, número o booleano) en texto
+fn scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Nombres de una lista (`[a, b]`) o de las claves de un mapa (`{a: ..., b: ...}`),
+/// las dos formas que admite compose para `depends_on` y `networks`
+fn names(value: &Option<Value>) -> Vec<String> {
+    match value {
+        Some(Value::Sequence(items)) => items.iter().filter_map(scalar).collect(),
+        Some(Value::Mapping(map)) => map.keys().filter_map(scalar).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Variables de entorno en forma de lista (`KEY=value`) o de mapa (`KEY: value`)
+fn environment(value: &Option<Value>) -> Vec<String> {
+    match value {
+        Some(Value::Sequence(items)) => items.iter().filter_map(scalar).collect(),
+        Some(Value::Mapping(map)) => map
+            .iter()
+            .filter_map(|(key, value)| {
+                let key = scalar(key)?;
+                Some(match scalar(value) {
+                    Some(value) => format!("{}={}", key, value),
+                    None => key,
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Entrecomilla un valor de systemd si contiene espacios
+fn quote(value: &str) -> String {
+    if value.chars().any(char::is_whitespace) {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Traduce un volumen en sintaxis corta. Los volúmenes con nombre pasan a apuntar
+/// a su quadlet `.volume` para que systemd los cree antes que el contenedor
+fn volume(spec: &str, named: &BTreeMap<String, Option<Value>>) -> String {
+    match spec.split_once(':') {
+        Some((source, rest)) if named.contains_key(source) => {
+            format!("{}.volume:{}", source, rest)
+        }
+        _ => spec.to_string(),
+    }
+}
+
+fn container_content(
+    name: &str,
+    service: &ComposeService,
+    named_volumes: &BTreeMap<String, Option<Value>>,
+) -> Result<String, AppError> {
+    let image = service.image.as_deref().ok_or_else(|| {
+        AppError::BadRequest(format!(
+            "El servicio '{}' no tiene 'image'; 'build' no está soportado",
+            name
+        ))
+    })?;
+
+    let mut unit = vec![format!("Description={}", name)];
+    for dependency in names(&service.depends_on) {
+        unit.push(format!("Requires={}.service", dependency));
+        unit.push(format!("After={}.service", dependency));
+    }
+
+    let mut container = vec![format!("Image={}", image)];
+    for port in service.ports.iter().filter_map(scalar) {
+        container.push(format!("PublishPort={}", port));
+    }
+    for variable in environment(&service.environment) {
+        container.push(format!("Environment={}", quote(&variable)));
+    }
+    // La sintaxis larga de volúmenes (mapas) no se traduce
+    for spec in service.volumes.iter().filter_map(scalar) {
+        container.push(format!("Volume={}", volume(&spec, named_volumes)));
+    }
+    for network in names(&service.networks) {
+        container.push(format!("Network={}.network", network));
+    }
+
+    Ok(format!(
+        "[Unit]\n{}\n\n[Container]\n{}\n\n[Install]\nWantedBy=default.target\n",
+        unit.join("\n"),
+        container.join("\n")
+    ))
+}
+
+/// Convierte un `docker-compose.yml` en quadlets: un `.container` por servicio y un
+/// `.network`/`.volume` por cada red o volumen con nombre. Los quadlets no se guardan
+pub fn compose_to_quadlets(yaml: &str) -> Result<Vec<Quadlet>, AppError> {
+    let compose: ComposeFile = serde_yaml_ng::from_str(yaml)
+        .map_err(|e| AppError::ParseError(format!("docker-compose inválido: {}", e)))?;
+    if compose.services.is_empty() {
+        return Err(AppError::bad_request(
+            "El compose no define ningún servicio",
+        ));
+    }
+
+    let mut quadlets = Vec::new();
+    for name in compose.networks.keys() {
+        quadlets.push(Quadlet::new(name, "network", Some("[Network]\n".into()))?);
+    }
+    for name in compose.volumes.keys() {
+        quadlets.push(Quadlet::new(name, "volume", Some("[Volume]\n".into()))?);
+    }
+    for (name, service) in &compose.services {
+        let content = container_content(name, service, &compose.volumes)?;
+        quadlets.push(Quadlet::new(name, "container", Some(content))?);
+    }
+    Ok(quadlets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::QuadletType;
+
+    const COMPOSE: &str = r#"
+services:
+  web:
+    image: docker.io/library/nginx:latest
+    ports:
+      - "8080:80"
+    environment:
+      SERVER_NAME: example.com
+      GREETING: hello world
+    depends_on:
+      - db
+    networks:
+      - backend
+  db:
+    image: docker.io/library/postgres:16
+    environment:
+      - POSTGRES_PASSWORD=secret
+    volumes:
+      - dbdata:/var/lib/postgresql/data
+      - ./init:/docker-entrypoint-initdb.d:ro
+    networks:
+      backend: {}
+networks:
+  backend:
+volumes:
+  dbdata:
+"#;
+
+    fn content_of<'a>(quadlets: &'a [Quadlet], name: &str, kind: QuadletType) -> &'a str {
+        quadlets
+            .iter()
+            .find(|q| q.name == name && q.kind == kind)
+            .and_then(|q| q.content.as_deref())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_two_services_with_shared_network() {
+        let quadlets = compose_to_quadlets(COMPOSE).unwrap();
+        let files: Vec<String> = quadlets.iter().map(|q| q.full_name()).collect();
+        assert_eq!(
+            files,
+            vec![
+                "backend.network",
+                "dbdata.volume",
+                "db.container",
+                "web.container"
+            ]
+        );
+
+        let web = content_of(&quadlets, "web", QuadletType::Container);
+        assert!(web.contains("Image=docker.io/library/nginx:latest\n"));
+        assert!(web.contains("PublishPort=8080:80\n"));
+        assert!(web.contains("Environment=SERVER_NAME=example.com\n"));
+        assert!(web.contains("Environment=\"GREETING=hello world\"\n"));
+        assert!(web.contains("Requires=db.service\nAfter=db.service\n"));
+        assert!(web.contains("Network=backend.network\n"));
+
+        let db = content_of(&quadlets, "db", QuadletType::Container);
+        assert!(db.contains("Environment=POSTGRES_PASSWORD=secret\n"));
+        assert!(db.contains("Volume=dbdata.volume:/var/lib/postgresql/data\n"));
+        assert!(db.contains("Volume=./init:/docker-entrypoint-initdb.d:ro\n"));
+        assert!(db.contains("Network=backend.network\n"));
+        assert!(!db.contains("Requires="));
+    }
+
+    #[test]
+    fn test_service_without_image_is_rejected() {
+        let result = compose_to_quadlets("services:\n  app:\n    build: .\n");
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_invalid_yaml_is_a_parse_error() {
+        let result = compose_to_quadlets("services: [unterminated");
+        assert!(matches!(result, Err(AppError::ParseError(_))));
+    }
+}
@@ -0,0 +1,127 @@
+use super::sections::Sections;
+use crate::models::AppError;
+use std::collections::{BTreeMap, HashMap};
+
+/// Quadlet ya interpretado, con las secciones ordenadas y accesos tipados a las
+/// claves que más se consultan. Las claves repetidas van juntas separadas por ", ",
+/// como las deja `parse_quadlet`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QuadletContent {
+    sections: Sections,
+}
+
+impl QuadletContent {
+    /// Claves de una sección, si el quadlet la tiene
+    pub fn section(&self, name: &str) -> Option<&BTreeMap<String, String>> {
+        self.sections.get(name)
+    }
+
+    /// Secciones por orden de nombre
+    pub fn sections(&self) -> impl Iterator<Item = (&str, &BTreeMap<String, String>)> {
+        self.sections
+            .iter()
+            .map(|(name, pairs)| (name.as_str(), pairs))
+    }
+
+    /// Valor de una clave tal cual. Si se repite, todos juntos separados por ", "
+    pub fn get(&self, section: &str, key: &str) -> Option<&str> {
+        self.section(section)?.get(key).map(String::as_str)
+    }
+
+    /// Cada uno de los valores de una clave, que puede repetirse
+    pub fn values(&self, section: &str, key: &str) -> Vec<&str> {
+        self.get(section, key)
+            .map(|value| value.split(", ").filter(|v| !v.is_empty()).collect())
+            .unwrap_or_default()
+    }
+
+    /// `Image=` de `[Container]`
+    pub fn image(&self) -> Option<&str> {
+        self.get("Container", "Image")
+    }
+
+    /// `PublishPort=` de `[Container]`, en el orden del fichero
+    pub fn ports(&self) -> Vec<&str> {
+        self.values("Container", "PublishPort")
+    }
+
+    /// Asignaciones `CLAVE=valor` de los `Environment=` de `[Container]`. Cada línea
+    /// puede tener varias, separadas con la sintaxis de comillas de systemd
+    pub fn environment(&self) -> Result<Vec<String>, AppError> {
+        let mut variables = Vec::new();
+        for environment in self.values("Container", "Environment") {
+            variables.extend(words(environment)?);
+        }
+        Ok(variables)
+    }
+
+    /// El mapa completo de secciones, para quien necesita recorrerlo entero
+    pub fn into_sections(self) -> Sections {
+        self.sections
+    }
+}
+
+impl From<HashMap<String, HashMap<String, String>>> for QuadletContent {
+    fn from(data: HashMap<String, HashMap<String, String>>) -> Self {
+        let sections = data
+            .into_iter()
+            .map(|(section, pairs)| (section, pairs.into_iter().collect()))
+            .collect();
+        Self { sections }
+    }
+}
+
+/// Separa un valor con la sintaxis de comillas de systemd en palabras
+pub(super) fn words(value: &str) -> Result<Vec<String>, AppError> {
+    shlex::split(value)
+        .ok_or_else(|| AppError::ParseError(format!("Comillas sin cerrar en '{}'", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parser::parse_quadlet_typed;
+    use super::*;
+
+    const SAMPLE: &str = "[Unit]\nDescription=Web\n\n[Container]\n\
+                          Image=docker.io/library/nginx:latest\n\
+                          PublishPort=8080:80\nPublishPort=8443:443\n\
+                          Environment=TZ=Europe/Madrid \"GREETING=hola mundo\"\n\
+                          Environment=DEBUG=1\n\n\
+                          [Install]\nWantedBy=default.target\n";
+
+    #[test]
+    fn test_container_accessors() {
+        let content = parse_quadlet_typed(SAMPLE).unwrap();
+        assert_eq!(content.image(), Some("docker.io/library/nginx:latest"));
+        assert_eq!(content.ports(), vec!["8080:80", "8443:443"]);
+        assert_eq!(
+            content.environment().unwrap(),
+            vec!["TZ=Europe/Madrid", "GREETING=hola mundo", "DEBUG=1"]
+        );
+        assert_eq!(content.get("Unit", "Description"), Some("Web"));
+        assert_eq!(
+            content.section("Install").unwrap()["WantedBy"],
+            "default.target"
+        );
+        let names: Vec<_> = content.sections().map(|(name, _)| name).collect();
+        assert_eq!(names, vec!["Container", "Install", "Unit"]);
+    }
+
+    #[test]
+    fn test_missing_keys_are_empty() {
+        let content = parse_quadlet_typed("[Volume]\nLabel=app=web\n").unwrap();
+        assert_eq!(content.image(), None);
+        assert!(content.ports().is_empty());
+        assert!(content.environment().unwrap().is_empty());
+        assert!(content.section("Container").is_none());
+    }
+
+    #[test]
+    fn test_unclosed_quotes_in_environment() {
+        let content = parse_quadlet_typed("[Container]\nEnvironment=\"A=1\n").unwrap();
+        assert!(matches!(
+            content.environment(),
+            Err(AppError::ParseError(_))
+        ));
+    }
+}
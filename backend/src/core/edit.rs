@@ -0,0 +1,172 @@
+use super::parser::parse_quadlet;
+use crate::models::AppError;
+
+fn is_valid_name(name: &str, extra: &[char]) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || extra.contains(&c))
+}
+
+/// Cambia, añade o (con `None`) borra una clave de una sección editando el texto
+/// línea a línea, de forma que se conservan el orden, los comentarios y el resto
+/// de claves. Si la clave se repite, el nuevo valor sustituye a todas sus apariciones
+pub fn set_key(
+    content: &str,
+    section: &str,
+    key: &str,
+    value: Option<&str>,
+) -> Result<String, AppError> {
+    if !is_valid_name(section, &['-']) {
+        return Err(AppError::validation_error(
+            "section",
+            "nombre de sección inválido",
+        ));
+    }
+    if !is_valid_name(key, &['-', '_']) {
+        return Err(AppError::validation_error(
+            "key",
+            "nombre de clave inválido",
+        ));
+    }
+    if value.is_some_and(|v| v.contains(['\n', '\r'])) {
+        return Err(AppError::validation_error(
+            "value",
+            "no puede contener saltos de línea",
+        ));
+    }
+    let content = if content.is_empty() || content.ends_with('\n') {
+        content.to_string()
+    } else {
+        format!("{}\n", content)
+    };
+    parse_quadlet(&content).map_err(|e| AppError::quadlet_parse_error(section, &e.to_string()))?;
+
+    let header = format!("[{}]", section);
+    let mut lines: Vec<String> = Vec::new();
+    let mut in_section = false;
+    let mut written = false;
+    // Posición tras la última línea con contenido de la sección
+    let mut insert_at = None;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            in_section = trimmed == header;
+            if in_section {
+                insert_at = Some(lines.len() + 1);
+            }
+        } else if in_section {
+            let is_key = trimmed
+                .split_once('=')
+                .is_some_and(|(k, _)| k.trim() == key);
+            if is_key {
+                match value {
+                    Some(value) if !written => {
+                        lines.push(format!("{}={}", key, value));
+                        written = true;
+                        insert_at = Some(lines.len());
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            if !trimmed.is_empty() {
+                insert_at = Some(lines.len() + 1);
+            }
+        }
+        lines.push(line.to_string());
+    }
+
+    if let (Some(value), false) = (value, written) {
+        let line = format!("{}={}", key, value);
+        match insert_at {
+            Some(index) => lines.insert(index, line),
+            None => {
+                if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push(header);
+                lines.push(line);
+            }
+        }
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "# Servidor web\n\
+        [Unit]\n\
+        Description=web\n\
+        \n\
+        [Container]\n\
+        Image=nginx\n\
+        # Puertos\n\
+        PublishPort=80:80\n\
+        PublishPort=443:443\n\
+        \n\
+        [Install]\n\
+        WantedBy=default.target\n";
+
+    #[test]
+    fn test_add_key() {
+        let content = set_key(CONTENT, "Container", "Environment", Some("DEBUG=1")).unwrap();
+        assert!(content.contains("PublishPort=443:443\nEnvironment=DEBUG=1\n\n[Install]"));
+        assert!(content.starts_with("# Servidor web\n"));
+
+        let content = set_key(CONTENT, "Service", "Restart", Some("always")).unwrap();
+        assert!(content.ends_with("WantedBy=default.target\n\n[Service]\nRestart=always\n"));
+    }
+
+    #[test]
+    fn test_update_key() {
+        let content = set_key(CONTENT, "Container", "Image", Some("docker.io/nginx")).unwrap();
+        assert_eq!(
+            content,
+            CONTENT.replace("Image=nginx", "Image=docker.io/nginx")
+        );
+
+        // Las claves repetidas quedan en una sola
+        let content = set_key(CONTENT, "Container", "PublishPort", Some("8080:80")).unwrap();
+        assert!(content.contains("# Puertos\nPublishPort=8080:80\n\n[Install]"));
+
+        // Las secciones de extensión, como la de Quadly, también se pueden editar
+        let content = set_key(
+            "[X-Quadly]\nDescription=a\n",
+            "X-Quadly",
+            "Description",
+            Some("b"),
+        );
+        assert_eq!(content.unwrap(), "[X-Quadly]\nDescription=b\n");
+    }
+
+    #[test]
+    fn test_delete_key() {
+        let content = set_key(CONTENT, "Container", "PublishPort", None).unwrap();
+        assert!(content.contains("Image=nginx\n# Puertos\n\n[Install]"));
+        assert!(!content.contains("PublishPort"));
+        // Borrar una clave que no existe no cambia nada
+        assert_eq!(
+            set_key(CONTENT, "Container", "Exec", None).unwrap(),
+            CONTENT
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_input() {
+        for (section, key, value) in [
+            ("Con tainer", "Image", "nginx"),
+            ("Container", "Im=age", "nginx"),
+            ("Container", "Image", "nginx\n[Service]"),
+        ] {
+            assert!(matches!(
+                set_key(CONTENT, section, key, Some(value)),
+                Err(AppError::ValidationError(_))
+            ));
+        }
+    }
+}
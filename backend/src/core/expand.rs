@@ -0,0 +1,91 @@
+/// Variables de entorno que se pueden usar como `${VAR}` si no se indica `EXPAND_VARS`
+pub const DEFAULT_EXPAND_VARS: [&str; 3] = ["HOSTNAME", "HOME", "USER"];
+
+fn is_var_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Sustituye cada `${VAR}` por el valor que da `lookup`, solo si `VAR` está en
+/// `allowed`: nunca se expone el resto del entorno. Las que no están permitidas o
+/// no tienen valor se dejan tal cual y se devuelven, sin repetir, para avisar
+pub fn expand_vars(
+    content: &str,
+    allowed: &[String],
+    lookup: impl Fn(&str) -> Option<String>,
+) -> (String, Vec<String>) {
+    let mut expanded = String::with_capacity(content.len());
+    let mut unresolved: Vec<String> = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let name = &after[..end];
+        let value = (is_var_name(name) && allowed.iter().any(|var| var == name))
+            .then(|| lookup(name))
+            .flatten();
+        match value {
+            Some(value) => expanded.push_str(&value),
+            None => {
+                expanded.push_str(&rest[start..start + 2 + end + 1]);
+                if !unresolved.iter().any(|var| var == name) {
+                    unresolved.push(name.to_string());
+                }
+            }
+        }
+        rest = &after[end + 1..];
+    }
+    expanded.push_str(rest);
+    (expanded, unresolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(name: &str) -> Option<String> {
+        match name {
+            "HOSTNAME" => Some("nas".to_string()),
+            "DATA_DIR" => Some("/srv/data".to_string()),
+            "SECRET_TOKEN" => Some("s3cr3t".to_string()),
+            _ => None,
+        }
+    }
+
+    fn allowed() -> Vec<String> {
+        vec![
+            "HOSTNAME".to_string(),
+            "DATA_DIR".to_string(),
+            "USER".to_string(),
+        ]
+    }
+
+    #[test]
+    fn test_allowed_vars_are_expanded() {
+        let content = "[Container]\nHostName=${HOSTNAME}\nVolume=${DATA_DIR}/web:/data\n";
+        let (expanded, unresolved) = expand_vars(content, &allowed(), env);
+        assert_eq!(
+            expanded,
+            "[Container]\nHostName=nas\nVolume=/srv/data/web:/data\n"
+        );
+        assert!(unresolved.is_empty());
+    }
+
+    #[test]
+    fn test_disallowed_and_unknown_vars_are_left_untouched() {
+        let content = "Environment=T=${SECRET_TOKEN} U=${USER} ${SECRET_TOKEN} $HOME ${}\n";
+        let (expanded, unresolved) = expand_vars(content, &allowed(), env);
+        assert_eq!(expanded, content);
+        assert_eq!(unresolved, vec!["SECRET_TOKEN", "USER", ""]);
+    }
+
+    #[test]
+    fn test_unclosed_var_is_kept() {
+        let (expanded, unresolved) = expand_vars("A=${HOSTNAME} B=${HOSTNAME", &allowed(), env);
+        assert_eq!(expanded, "A=nas B=${HOSTNAME");
+        assert!(unresolved.is_empty());
+    }
+}
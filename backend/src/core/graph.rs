@@ -0,0 +1,178 @@
+use super::parser::parse_quadlet_typed;
+use crate::models::{GraphEdge, GraphNode, QuadletGraph, QuadletType};
+use std::collections::{BTreeMap, HashMap};
+
+/// Claves que referencian a otro quadlet, con la extensión del referenciado
+const REFERENCE_KEYS: [(&str, &str); 3] = [
+    ("Pod", ".pod"),
+    ("Network", ".network"),
+    ("Volume", ".volume"),
+];
+
+fn node(id: &str, missing: bool) -> GraphNode {
+    let (name, extension) = id.rsplit_once('.').unwrap_or((id, ""));
+    GraphNode {
+        id: id.to_string(),
+        name: name.to_string(),
+        kind: QuadletType::from_extension(extension).unwrap_or(QuadletType::Any),
+        missing,
+    }
+}
+
+/// Construye el grafo de dependencias a partir de los quadlets `(fichero, contenido)`.
+/// Los ficheros que no se pueden interpretar aparecen como nodos sin aristas
+pub fn build_graph(files: &[(String, String)]) -> QuadletGraph {
+    let mut nodes: BTreeMap<String, GraphNode> = files
+        .iter()
+        .map(|(id, _)| (id.clone(), node(id, false)))
+        .collect();
+    let mut edges = Vec::new();
+    for (id, content) in files {
+        let Ok(content) = parse_quadlet_typed(content) else {
+            continue;
+        };
+        for (section, _) in content.sections() {
+            for (key, extension) in REFERENCE_KEYS {
+                for value in content.values(section, key) {
+                    // `datos.volume:/data:Z` o `red.network:ip=10.0.0.2`
+                    let reference = value.split(':').next().unwrap_or_default();
+                    if !reference.ends_with(extension) {
+                        continue;
+                    }
+                    nodes
+                        .entry(reference.to_string())
+                        .or_insert_with(|| node(reference, true));
+                    edges.push(GraphEdge {
+                        from: id.clone(),
+                        to: reference.to_string(),
+                        key: key.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    let cycles = find_cycles(&edges);
+    QuadletGraph {
+        nodes: nodes.into_values().collect(),
+        edges,
+        cycles,
+    }
+}
+
+/// Ciclos del grafo mediante un recorrido en profundidad: cada arista que vuelve
+/// a un nodo de la rama actual cierra un ciclo
+fn find_cycles(edges: &[GraphEdge]) -> Vec<Vec<String>> {
+    let mut adjacency: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for edge in edges {
+        adjacency.entry(&edge.from).or_default().push(&edge.to);
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Visit {
+        InProgress,
+        Done,
+    }
+
+    fn visit<'a>(
+        node: &'a str,
+        adjacency: &BTreeMap<&'a str, Vec<&'a str>>,
+        state: &mut HashMap<&'a str, Visit>,
+        path: &mut Vec<&'a str>,
+        cycles: &mut Vec<Vec<String>>,
+    ) {
+        state.insert(node, Visit::InProgress);
+        path.push(node);
+        for &next in adjacency.get(node).into_iter().flatten() {
+            match state.get(next) {
+                Some(Visit::InProgress) => {
+                    let start = path.iter().position(|&n| n == next).unwrap_or(0);
+                    cycles.push(path[start..].iter().map(|n| n.to_string()).collect());
+                }
+                Some(Visit::Done) => {}
+                None => visit(next, adjacency, state, path, cycles),
+            }
+        }
+        path.pop();
+        state.insert(node, Visit::Done);
+    }
+
+    let mut state = HashMap::new();
+    let mut cycles = Vec::new();
+    for &node in adjacency.keys() {
+        if !state.contains_key(node) {
+            visit(node, &adjacency, &mut state, &mut Vec::new(), &mut cycles);
+        }
+    }
+    cycles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(id, content)| (id.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_pod_with_two_containers_sharing_a_network() {
+        let graph = build_graph(&files(&[
+            ("app.pod", "[Pod]\nNetwork=front.network\n"),
+            ("front.network", "[Network]\n"),
+            (
+                "web.container",
+                "[Container]\nImage=nginx\nPod=app.pod\nNetwork=front.network\n",
+            ),
+            (
+                "api.container",
+                "[Container]\nImage=api\nPod=app.pod\nNetwork=front.network\n\
+                 Volume=data.volume:/data\n",
+            ),
+        ]));
+
+        let ids: Vec<_> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "api.container",
+                "app.pod",
+                "data.volume",
+                "front.network",
+                "web.container"
+            ]
+        );
+        let data = graph.nodes.iter().find(|n| n.id == "data.volume").unwrap();
+        assert!(data.missing);
+        assert_eq!(data.kind, QuadletType::Volume);
+
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|e| (e.from.as_str(), e.to.as_str(), e.key.as_str()))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![
+                ("app.pod", "front.network", "Network"),
+                ("web.container", "app.pod", "Pod"),
+                ("web.container", "front.network", "Network"),
+                ("api.container", "app.pod", "Pod"),
+                ("api.container", "front.network", "Network"),
+                ("api.container", "data.volume", "Volume"),
+            ]
+        );
+        assert!(graph.cycles.is_empty());
+    }
+
+    #[test]
+    fn test_cycles_are_reported() {
+        let graph = build_graph(&files(&[
+            ("a.pod", "[Pod]\nNetwork=b.network\n"),
+            ("b.network", "[Network]\nPod=a.pod\n"),
+        ]));
+        assert_eq!(graph.cycles, vec![vec!["a.pod", "b.network"]]);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::models::QuadletType;
+use serde_json::{json, Map, Value};
+
+/// Clave conocida de una sección de quadlet
+pub struct KnownKey {
+    pub section: &'static str,
+    pub key: &'static str,
+    /// La clave es obligatoria para que el quadlet sea válido
+    pub required: bool,
+    /// La clave puede aparecer varias veces (ej: `Volume=`)
+    pub repeatable: bool,
+}
+
+const fn key(section: &'static str, key: &'static str) -> KnownKey {
+    KnownKey {
+        section,
+        key,
+        required: false,
+        repeatable: false,
+    }
+}
+
+const fn required(section: &'static str, key: &'static str) -> KnownKey {
+    KnownKey {
+        section,
+        key,
+        required: true,
+        repeatable: false,
+    }
+}
+
+const fn repeatable(section: &'static str, key: &'static str) -> KnownKey {
+    KnownKey {
+        section,
+        key,
+        required: false,
+        repeatable: true,
+    }
+}
+
+/// Secciones de systemd comunes a todos los tipos de quadlet
+const COMMON_KEYS: &[KnownKey] = &[
+    key("Unit", "Description"),
+    repeatable("Unit", "After"),
+    repeatable("Unit", "Before"),
+    repeatable("Unit", "Requires"),
+    repeatable("Unit", "Wants"),
+    key("Service", "Restart"),
+    key("Service", "RestartSec"),
+    key("Service", "TimeoutStartSec"),
+    key("Service", "TimeoutStopSec"),
+    repeatable("Install", "WantedBy"),
+    repeatable("Install", "RequiredBy"),
+];
+
+const CONTAINER_KEYS: &[KnownKey] = &[
+    required("Container", "Image"),
+    key("Container", "ContainerName"),
+    key("Container", "Exec"),
+    key("Container", "Entrypoint"),
+    repeatable("Container", "Environment"),
+    repeatable("Container", "EnvironmentFile"),
+    repeatable("Container", "PublishPort"),
+    repeatable("Container", "Volume"),
+    repeatable("Container", "Network"),
+    key("Container", "Pod"),
+    repeatable("Container", "Label"),
+    repeatable("Container", "Secret"),
+    key("Container", "User"),
+    key("Container", "Group"),
+    key("Container", "WorkingDir"),
+    key("Container", "AutoUpdate"),
+    key("Container", "HealthCmd"),
+    key("Container", "HealthInterval"),
+    repeatable("Container", "PodmanArgs"),
+];
+
+const NETWORK_KEYS: &[KnownKey] = &[
+    key("Network", "NetworkName"),
+    key("Network", "Driver"),
+    repeatable("Network", "Subnet"),
+    repeatable("Network", "Gateway"),
+    key("Network", "IPv6"),
+    key("Network", "Internal"),
+    repeatable("Network", "Label"),
+    repeatable("Network", "PodmanArgs"),
+];
+
+const VOLUME_KEYS: &[KnownKey] = &[
+    key("Volume", "VolumeName"),
+    key("Volume", "Driver"),
+    key("Volume", "Device"),
+    key("Volume", "Type"),
+    key("Volume", "Options"),
+    key("Volume", "User"),
+    key("Volume", "Group"),
+    repeatable("Volume", "Label"),
+    repeatable("Volume", "PodmanArgs"),
+];
+
+const POD_KEYS: &[KnownKey] = &[
+    key("Pod", "PodName"),
+    repeatable("Pod", "PublishPort"),
+    repeatable("Pod", "Network"),
+    repeatable("Pod", "Volume"),
+    repeatable("Pod", "PodmanArgs"),
+];
+
+const KUBE_KEYS: &[KnownKey] = &[
+    required("Kube", "Yaml"),
+    repeatable("Kube", "ConfigMap"),
+    repeatable("Kube", "Network"),
+    repeatable("Kube", "PublishPort"),
+    key("Kube", "AutoUpdate"),
+    repeatable("Kube", "PodmanArgs"),
+];
+
+const IMAGE_KEYS: &[KnownKey] = &[
+    required("Image", "Image"),
+    key("Image", "AllTags"),
+    key("Image", "Arch"),
+    key("Image", "OS"),
+    key("Image", "Creds"),
+    key("Image", "TLSVerify"),
+    repeatable("Image", "PodmanArgs"),
+];
+
+/// `ImageTag` o `File` es obligatoria, pero no las dos: de eso se encarga el validador
+const BUILD_KEYS: &[KnownKey] = &[
+    key("Build", "ImageTag"),
+    key("Build", "File"),
+    key("Build", "SetWorkingDirectory"),
+    repeatable("Build", "Annotation"),
+    key("Build", "Arch"),
+    key("Build", "AuthFile"),
+    repeatable("Build", "Environment"),
+    key("Build", "ForceRM"),
+    repeatable("Build", "Label"),
+    repeatable("Build", "Network"),
+    repeatable("Build", "PodmanArgs"),
+    key("Build", "Pull"),
+    repeatable("Build", "Secret"),
+    key("Build", "Target"),
+    key("Build", "TLSVerify"),
+    repeatable("Build", "Volume"),
+];
+
+/// Devuelve las claves propias de un tipo de quadlet (sin las secciones comunes de systemd)
+pub fn type_keys(kind: QuadletType) -> &'static [KnownKey] {
+    match kind {
+        QuadletType::Container => CONTAINER_KEYS,
+        QuadletType::Network => NETWORK_KEYS,
+        QuadletType::Volume => VOLUME_KEYS,
+        QuadletType::Pod => POD_KEYS,
+        QuadletType::Kube => KUBE_KEYS,
+        QuadletType::Image => IMAGE_KEYS,
+        QuadletType::Build => BUILD_KEYS,
+        QuadletType::Any => &[],
+    }
+}
+
+/// Devuelve todas las claves conocidas para un tipo de quadlet
+pub fn known_keys(kind: QuadletType) -> impl Iterator<Item = &'static KnownKey> {
+    type_keys(kind).iter().chain(COMMON_KEYS.iter())
+}
+
+/// Construye un JSON schema que describe las secciones y claves conocidas de un tipo de quadlet
+pub fn json_schema(kind: QuadletType) -> Value {
+    let mut sections: Map<String, Value> = Map::new();
+    let mut required_sections: Vec<&str> = Vec::new();
+
+    for known in known_keys(kind) {
+        let section = sections.entry(known.section).or_insert_with(|| {
+            json!({
+                "type": "object",
+                "properties": {},
+                "required": [],
+                "additionalProperties": true,
+            })
+        });
+        let value_schema = if known.repeatable {
+            json!({ "type": "array", "items": { "type": "string" } })
+        } else {
+            json!({ "type": "string" })
+        };
+        section["properties"][known.key] = value_schema;
+        if known.required {
+            if let Some(required) = section["required"].as_array_mut() {
+                required.push(json!(known.key));
+            }
+            if !required_sections.contains(&known.section) {
+                required_sections.push(known.section);
+            }
+        }
+    }
+
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": format!("{} quadlet", kind.as_str()),
+        "type": "object",
+        "properties": sections,
+        "required": required_sections,
+        "additionalProperties": true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_schema_requires_image() {
+        let schema = json_schema(QuadletType::Container);
+        let container = &schema["properties"]["Container"];
+        assert_eq!(container["required"], json!(["Image"]));
+        assert_eq!(container["properties"]["Image"]["type"], "string");
+        assert_eq!(container["properties"]["PublishPort"]["type"], "array");
+        assert_eq!(schema["required"], json!(["Container"]));
+    }
+
+    #[test]
+    fn test_any_schema_has_only_common_sections() {
+        let schema = json_schema(QuadletType::Any);
+        assert!(schema["properties"].get("Container").is_none());
+        assert!(schema["properties"].get("Unit").is_some());
+        assert_eq!(schema["required"], json!([]));
+    }
+}
@@ -1,2 +1,27 @@
+mod compose;
+mod content;
+mod edit;
+mod expand;
+mod graph;
+mod keys;
 mod validator;
 mod parser;
+mod podman_run;
+mod ports;
+mod redact;
+mod sections;
+mod summary;
+mod templates;
+
+pub use compose::compose_to_quadlets;
+pub use edit::set_key;
+pub use expand::{expand_vars, DEFAULT_EXPAND_VARS};
+pub use graph::build_graph;
+pub use keys::json_schema;
+pub use podman_run::podman_run_command;
+pub use ports::port_conflicts;
+pub use redact::{redact_content, DEFAULT_REDACT_PATTERNS};
+pub use sections::{merge_dropin, parsed_sections, Sections};
+pub use summary::container_summary;
+pub use templates::{find_template, list_templates, render_template};
+pub use validator::{build_errors, lint_errors, missing_references, semantic_errors};
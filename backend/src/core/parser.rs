@@ -2,12 +2,16 @@ use pest::Parser;
 use pest_derive::Parser;
 use std::collections::HashMap;
 use anyhow::{anyhow, Result};
+use super::content::QuadletContent;
 
 #[derive(Parser)]
 #[grammar = "core/quadlet.pest"]
 pub struct QuadletParser;
 
-/// Parsea el contenido de un archivo .container a una estructura de datos
+/// Parsea el contenido de un archivo .container a una estructura de datos.
+///
+/// Una clave antes de la primera `[Sección]` es un error: systemd la ignoraría y
+/// el quadlet no haría lo que parece, así que se rechaza indicando la línea
 pub fn parse_quadlet(content: &str) -> Result<HashMap<String, HashMap<String, String>>> {
     let file = QuadletParser::parse(Rule::file, content)
         .map_err(|e| anyhow!("Error de sintaxis: {}", e))?
@@ -40,24 +44,54 @@ pub fn parse_quadlet(content: &str) -> Result<HashMap<String, HashMap<String, St
                     }
                 }
             }
+            Rule::pair => {
+                let (line, _) = record.line_col();
+                let key = record.into_inner().next().unwrap().as_str();
+                return Err(anyhow!(
+                    "Línea {}: la clave '{}' está fuera de una sección; añade antes una cabecera como [Container]",
+                    line,
+                    key
+                ));
+            }
             _ => {}
         }
     }
     Ok(data)
 }
 
-/// Convierte el mapa de datos de nuevo a formato string .container
-pub fn serialize_quadlet(data: &HashMap<String, HashMap<String, String>>) -> String {
-    let mut output = String::new();
-    for (section, pairs) in data {
-        output.push_str(&format!("[{}]\n", section));
-        for (key, value) in pairs {
-            // Si el valor tiene comas (claves múltiples), las separamos al escribir
-            for val in value.split(", ") {
-                output.push_str(&format!("{}={}\n", key, val));
-            }
-        }
-        output.push_str("\n");
+/// Como `parse_quadlet`, pero admite contenido sin salto de línea final, que la
+/// gramática exige en cada línea
+pub fn parse_content(content: &str) -> Result<HashMap<String, HashMap<String, String>>> {
+    if content.ends_with('\n') {
+        parse_quadlet(content)
+    } else {
+        parse_quadlet(&format!("{}\n", content))
+    }
+}
+
+/// Como `parse_content`, pero con el resultado en un `QuadletContent` que ya sabe
+/// leer la imagen, los puertos, el entorno...
+pub fn parse_quadlet_typed(content: &str) -> Result<QuadletContent> {
+    parse_content(content).map(QuadletContent::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_before_first_section_is_rejected() {
+        let content = "# snippet\nImage=nginx\n\n[Container]\nPublishPort=8080:80\n";
+        let error = parse_quadlet(content).unwrap_err().to_string();
+        assert!(error.starts_with("Línea 2:"), "{}", error);
+        assert!(error.contains("'Image'"), "{}", error);
+    }
+
+    #[test]
+    fn test_keys_inside_sections_are_parsed() {
+        let content = "# snippet\n\n[Container]\nImage=nginx\nVolume=a:/a\nVolume=b:/b\n";
+        let data = parse_quadlet(content).unwrap();
+        assert_eq!(data["Container"]["Image"], "nginx");
+        assert_eq!(data["Container"]["Volume"], "a:/a, b:/b");
     }
-    output
 }
@@ -0,0 +1,84 @@
+use super::content::words;
+use super::parser::parse_quadlet_typed;
+use crate::models::AppError;
+
+/// Los volúmenes que apuntan a un quadlet `.volume` se llaman `systemd-<nombre>` en podman
+fn volume_source(spec: &str) -> String {
+    match spec.split_once(".volume:") {
+        Some((name, rest)) if !name.contains('/') => format!("systemd-{}:{}", name, rest),
+        _ => spec.to_string(),
+    }
+}
+
+/// Traduce un quadlet `.container` al `podman run` equivalente, con cada argumento
+/// escapado para poder pegarlo en una shell
+pub fn podman_run_command(content: &str) -> Result<String, AppError> {
+    let content = parse_quadlet_typed(content)
+        .map_err(|e| AppError::quadlet_parse_error("container", &e.to_string()))?;
+    if content.section("Container").is_none() {
+        return Err(AppError::bad_request(
+            "El quadlet no tiene sección [Container]",
+        ));
+    }
+    let image = content
+        .image()
+        .ok_or_else(|| AppError::bad_request("El quadlet no define 'Image'"))?;
+
+    let mut args = vec!["podman".to_string(), "run".to_string()];
+    for port in content.ports() {
+        args.extend(["-p".to_string(), port.to_string()]);
+    }
+    for volume in content.values("Container", "Volume") {
+        args.extend(["-v".to_string(), volume_source(volume)]);
+    }
+    for variable in content.environment()? {
+        args.extend(["-e".to_string(), variable]);
+    }
+    for podman_args in content.values("Container", "PodmanArgs") {
+        args.extend(words(podman_args)?);
+    }
+    args.push(image.to_string());
+    if let Some(exec) = content.get("Container", "Exec") {
+        args.extend(words(exec)?);
+    }
+
+    shlex::try_join(args.iter().map(String::as_str))
+        .map_err(|e| AppError::bad_request(&format!("No se puede escapar el comando: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ports_and_environment() {
+        let content = "[Container]\n\
+            Image=docker.io/library/nginx:latest\n\
+            PublishPort=8080:80\n\
+            PublishPort=8443:443\n\
+            Environment=SERVER_NAME=example.com \"GREETING=hello world\"\n\
+            Volume=data.volume:/usr/share/nginx/html:ro\n\
+            PodmanArgs=--memory 512m\n\
+            Exec=nginx -g 'daemon off;'\n";
+        let command = podman_run_command(content).unwrap();
+        assert_eq!(
+            command,
+            "podman run -p 8080:80 -p 8443:443 \
+             -v systemd-data:/usr/share/nginx/html:ro \
+             -e 'SERVER_NAME=example.com' -e 'GREETING=hello world' \
+             --memory 512m docker.io/library/nginx:latest nginx -g 'daemon off;'"
+        );
+    }
+
+    #[test]
+    fn test_missing_image_is_rejected() {
+        let result = podman_run_command("[Container]\nPublishPort=80:80\n");
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn test_missing_container_section_is_rejected() {
+        let result = podman_run_command("[Volume]\n");
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,184 @@
+use super::parser::parse_quadlet_typed;
+use crate::models::ValidationError;
+
+/// Puertos del host que reserva un `PublishPort=`
+#[derive(Debug, Clone, PartialEq)]
+struct HostPorts {
+    /// IP en la que escucha; `None` son todas las interfaces
+    ip: Option<String>,
+    start: u16,
+    end: u16,
+    protocol: String,
+}
+
+impl HostPorts {
+    fn overlaps(&self, other: &HostPorts) -> bool {
+        let same_ip = match (&self.ip, &other.ip) {
+            (Some(a), Some(b)) => a == b,
+            // Escuchar en todas las interfaces choca con cualquier IP
+            _ => true,
+        };
+        same_ip
+            && self.protocol == other.protocol
+            && self.start <= other.end
+            && other.start <= self.end
+    }
+
+    fn describe(&self) -> String {
+        let ports = if self.start == self.end {
+            self.start.to_string()
+        } else {
+            format!("{}-{}", self.start, self.end)
+        };
+        match &self.ip {
+            Some(ip) => format!("{}:{}/{}", ip, ports, self.protocol),
+            None => format!("{}/{}", ports, self.protocol),
+        }
+    }
+}
+
+fn port_range(value: &str) -> Option<(u16, u16)> {
+    match value.split_once('-') {
+        Some((start, end)) => Some((start.parse().ok()?, end.parse().ok()?)),
+        None => {
+            let port = value.parse().ok()?;
+            Some((port, port))
+        }
+    }
+}
+
+/// Interpreta un `PublishPort=`: `80`, `8080:80`, `127.0.0.1:8080:80`,
+/// `[::1]:8080:80`, `8080-8090:80-90` o con `/udp`. `None` si no fija un puerto
+/// del host (podman elige uno libre) o no se entiende
+fn host_ports(spec: &str) -> Option<HostPorts> {
+    let (spec, protocol) = spec.rsplit_once('/').unwrap_or((spec, "tcp"));
+    // Lo que va tras el último ':' es el puerto del contenedor
+    let (host, _) = spec.rsplit_once(':')?;
+    let (ip, ports) = if let Some(rest) = host.strip_prefix('[') {
+        let (ip, ports) = rest.split_once("]:")?;
+        (Some(ip), ports)
+    } else {
+        match host.rsplit_once(':') {
+            Some((ip, ports)) => (Some(ip), ports),
+            None => (None, host),
+        }
+    };
+    let (start, end) = port_range(ports)?;
+    let ip = ip
+        .filter(|ip| !matches!(*ip, "" | "0.0.0.0" | "::"))
+        .map(str::to_string);
+    Some(HostPorts {
+        ip,
+        start,
+        end,
+        protocol: protocol.to_lowercase(),
+    })
+}
+
+fn published_ports(content: &str) -> Vec<HostPorts> {
+    let Ok(content) = parse_quadlet_typed(content) else {
+        return Vec::new();
+    };
+    content.ports().into_iter().filter_map(host_ports).collect()
+}
+
+/// Puertos del host que `content` publica y que ya publica alguno de los otros
+/// `.container`, dados como pares (fichero, contenido)
+pub fn port_conflicts(content: &str, others: &[(String, String)]) -> Vec<ValidationError> {
+    let ports = published_ports(content);
+    let mut errors = Vec::new();
+    if ports.is_empty() {
+        return errors;
+    }
+    for (file_name, other) in others {
+        for taken in published_ports(other) {
+            for port in ports.iter().filter(|port| port.overlaps(&taken)) {
+                errors.push(ValidationError {
+                    field: "Container.PublishPort".to_string(),
+                    message: format!(
+                        "El puerto {} choca con {} de '{}'",
+                        port.describe(),
+                        taken.describe(),
+                        file_name
+                    ),
+                });
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn others(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(file, content)| (file.to_string(), content.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_host_ports_forms() {
+        let ports = |spec| host_ports(spec).map(|p| (p.ip, p.start, p.end, p.protocol));
+        let tcp = "tcp".to_string();
+        assert_eq!(ports("8080:80"), Some((None, 8080, 8080, tcp.clone())));
+        assert_eq!(
+            ports("127.0.0.1:8080:80"),
+            Some((Some("127.0.0.1".to_string()), 8080, 8080, tcp.clone()))
+        );
+        assert_eq!(
+            ports("[::1]:8080:80"),
+            Some((Some("::1".to_string()), 8080, 8080, tcp.clone()))
+        );
+        assert_eq!(ports("8000-8010:80-90"), Some((None, 8000, 8010, tcp)));
+        assert_eq!(
+            ports("5353:53/udp"),
+            Some((None, 5353, 5353, "udp".to_string()))
+        );
+        // Sin puerto del host no hay nada reservado
+        assert_eq!(ports("80"), None);
+        assert_eq!(ports("127.0.0.1::80"), None);
+    }
+
+    #[test]
+    fn test_direct_collision() {
+        let content = "[Container]\nImage=caddy\nPublishPort=8080:80\n";
+        let others = others(&[
+            (
+                "web.container",
+                "[Container]\nImage=nginx\nPublishPort=8080:80\n",
+            ),
+            (
+                "dns.container",
+                "[Container]\nImage=dns\nPublishPort=8080:53/udp\n",
+            ),
+        ]);
+        let errors = port_conflicts(content, &others);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("'web.container'"));
+    }
+
+    #[test]
+    fn test_range_overlap() {
+        let content = "[Container]\nImage=app\nPublishPort=127.0.0.1:9005:80\n";
+        let others = others(&[
+            (
+                "range.container",
+                "[Container]\nImage=a\nPublishPort=9000-9010:9000-9010\n",
+            ),
+            (
+                "other-ip.container",
+                "[Container]\nImage=b\nPublishPort=10.0.0.1:9005:80\n",
+            ),
+            (
+                "below.container",
+                "[Container]\nImage=c\nPublishPort=8990-8999:80-89\n",
+            ),
+        ]);
+        let errors = port_conflicts(content, &others);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("'range.container'"));
+    }
+}
@@ -0,0 +1,96 @@
+/// Patrones por defecto de las claves cuyo valor se oculta al redactar
+pub const DEFAULT_REDACT_PATTERNS: [&str; 4] = ["Environment", "Secret", "PASSWORD", "TOKEN"];
+
+/// Máscara que sustituye a los valores ocultos
+const MASK: &str = "***";
+
+/// Una clave se oculta si contiene alguno de los patrones, sin distinguir mayúsculas
+fn is_sensitive(key: &str, patterns: &[String]) -> bool {
+    let key = key.to_lowercase();
+    patterns
+        .iter()
+        .any(|pattern| key.contains(&pattern.to_lowercase()))
+}
+
+/// `Environment=A=1 "B=dos palabras"` pasa a `Environment=A=*** B=***`: los nombres de
+/// las variables siguen visibles. Si las comillas no cierran se oculta todo el valor
+fn redact_environment(value: &str) -> String {
+    match shlex::split(value) {
+        Some(words) => words
+            .iter()
+            .map(|word| match word.split_once('=') {
+                Some((name, _)) => format!("{}={}", name, MASK),
+                None => word.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        None => MASK.to_string(),
+    }
+}
+
+/// Oculta los valores de las claves sensibles de un quadlet, línea a línea para
+/// conservar comentarios, orden y formato. Solo afecta a lo que se muestra
+pub fn redact_content(content: &str, patterns: &[String]) -> String {
+    let mut redacted: Vec<String> = content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with(['#', ';', '[']) {
+                return line.to_string();
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return line.to_string();
+            };
+            if value.trim().is_empty() || !is_sensitive(key.trim(), patterns) {
+                return line.to_string();
+            }
+            let value = if key.trim() == "Environment" {
+                redact_environment(value.trim())
+            } else {
+                MASK.to_string()
+            };
+            format!("{}={}", key, value)
+        })
+        .collect();
+    if content.ends_with('\n') {
+        redacted.push(String::new());
+    }
+    redacted.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> Vec<String> {
+        DEFAULT_REDACT_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_environment_values_are_masked() {
+        let content = "[Container]\n# credenciales\nImage=postgres\n\
+                       Environment=POSTGRES_USER=app \"POSTGRES_PASSWORD=muy secreta\"\n\
+                       Secret=db-pass,type=env,target=PGPASS\n\
+                       Volume=/srv/db:/var/lib/postgresql\n";
+        assert_eq!(
+            redact_content(content, &defaults()),
+            "[Container]\n# credenciales\nImage=postgres\n\
+             Environment=POSTGRES_USER=*** POSTGRES_PASSWORD=***\n\
+             Secret=***\n\
+             Volume=/srv/db:/var/lib/postgresql\n"
+        );
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let content = "[Container]\nImage=nginx\nLabel=team=web";
+        let patterns = vec!["label".to_string()];
+        assert_eq!(
+            redact_content(content, &patterns),
+            "[Container]\nImage=nginx\nLabel=***"
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use super::content::QuadletContent;
+use super::keys::known_keys;
+use super::parser::parse_quadlet_typed;
+use crate::models::{AppError, QuadletType};
+use std::collections::BTreeMap;
+
+/// Secciones de un quadlet: `{ sección: { clave: valor } }`
+pub type Sections = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Secciones de un quadlet como `{ sección: { clave: valor } }`, ordenadas para que
+/// la respuesta sea estable. Las claves repetidas van juntas separadas por ", "
+pub fn parsed_sections(file_name: &str, content: &str) -> Result<Sections, AppError> {
+    parse_quadlet_typed(content)
+        .map(QuadletContent::into_sections)
+        .map_err(|e| AppError::quadlet_parse_error(file_name, &e.to_string()))
+}
+
+/// Aplica un drop-in sobre las secciones de un quadlet. Cada clave sustituye a la
+/// anterior salvo las repetibles (`Environment`, `Volume`...), que se añaden; una
+/// asignación vacía de una repetible vacía la lista, como en systemd
+pub fn merge_dropin(
+    sections: &mut Sections,
+    kind: QuadletType,
+    file_name: &str,
+    content: &str,
+) -> Result<(), AppError> {
+    for (section, pairs) in parsed_sections(file_name, content)? {
+        let merged = sections.entry(section.clone()).or_default();
+        for (key, value) in pairs {
+            let repeatable = known_keys(kind)
+                .any(|known| known.repeatable && known.section == section && known.key == key);
+            if !repeatable {
+                merged.insert(key, value);
+            } else if value.is_empty() {
+                merged.remove(&key);
+            } else {
+                merged
+                    .entry(key)
+                    .and_modify(|current| {
+                        current.push_str(", ");
+                        current.push_str(&value);
+                    })
+                    .or_insert(value);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_container_with_several_sections() {
+        let content = "[Unit]\nDescription=Web\n\n[Container]\nImage=nginx\n\
+                       PublishPort=8080:80\nPublishPort=8443:443\n\n\
+                       [Install]\nWantedBy=default.target\n";
+        let sections = parsed_sections("web.container", content).unwrap();
+        assert_eq!(
+            serde_json::to_value(&sections).unwrap(),
+            serde_json::json!({
+                "Container": {"Image": "nginx", "PublishPort": "8080:80, 8443:443"},
+                "Install": {"WantedBy": "default.target"},
+                "Unit": {"Description": "Web"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_merge_dropin_overrides_and_appends() {
+        let mut sections = parsed_sections(
+            "web.container",
+            "[Container]\nImage=nginx\nEnvironment=TZ=UTC\nVolume=data:/data\n",
+        )
+        .unwrap();
+        let dropin = "[Container]\nImage=nginx:1.27\nEnvironment=DEBUG=1\nVolume=\n\n\
+                      [Service]\nRestart=always\n";
+        merge_dropin(
+            &mut sections,
+            QuadletType::Container,
+            "10-debug.conf",
+            dropin,
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&sections).unwrap(),
+            serde_json::json!({
+                "Container": {"Image": "nginx:1.27", "Environment": "TZ=UTC, DEBUG=1"},
+                "Service": {"Restart": "always"}
+            })
+        );
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let result = parsed_sections("web.container", "[Container\nImage=nginx\n");
+        match result {
+            Err(AppError::ParseError(msg)) => assert!(msg.contains("web.container"), "{}", msg),
+            other => panic!("se esperaba ParseError, obtenido {:?}", other.map(|_| ())),
+        }
+    }
+}
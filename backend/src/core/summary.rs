@@ -0,0 +1,28 @@
+use super::parser::parse_quadlet_typed;
+use crate::models::AppError;
+
+/// Imagen y puertos publicados de un quadlet `.container`
+pub fn container_summary(content: &str) -> Result<(Option<String>, Vec<String>), AppError> {
+    let content = parse_quadlet_typed(content)
+        .map_err(|e| AppError::quadlet_parse_error("container", &e.to_string()))?;
+    let ports = content.ports().into_iter().map(str::to_string).collect();
+    Ok((content.image().map(str::to_string), ports))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_and_ports() {
+        let content = "[Container]\nImage=nginx\nPublishPort=8080:80\nPublishPort=8443:443";
+        let (image, ports) = container_summary(content).unwrap();
+        assert_eq!(image.as_deref(), Some("nginx"));
+        assert_eq!(ports, vec!["8080:80", "8443:443"]);
+    }
+
+    #[test]
+    fn test_malformed_content_is_an_error() {
+        assert!(container_summary("esto no es un quadlet\n").is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::models::{AppError, QuadletTemplate, QuadletType};
+use std::collections::HashMap;
+
+/// Plantillas incluidas: (id, tipo, descripción, contenido)
+const TEMPLATES: &[(&str, QuadletType, &str, &str)] = &[
+    (
+        "basic-container",
+        QuadletType::Container,
+        "Contenedor con una imagen y un puerto publicado",
+        "[Unit]
+Description={{name}}
+
+[Container]
+Image={{image}}
+PublishPort={{port}}
+
+[Install]
+WantedBy=default.target
+",
+    ),
+    (
+        "web-with-volume",
+        QuadletType::Container,
+        "Servidor web que sirve los ficheros de un volumen con nombre",
+        "[Unit]
+Description={{name}}
+
+[Container]
+Image={{image}}
+PublishPort={{port}}:80
+Volume={{volume}}.volume:{{path}}:ro
+
+[Install]
+WantedBy=default.target
+",
+    ),
+    (
+        "basic-pod",
+        QuadletType::Pod,
+        "Pod al que se unen los contenedores con Pod={{name}}.pod",
+        "[Unit]
+Description={{name}}
+
+[Pod]
+PodName={{name}}
+PublishPort={{port}}
+
+[Install]
+WantedBy=default.target
+",
+    ),
+    (
+        "named-volume",
+        QuadletType::Volume,
+        "Volumen con nombre gestionado por systemd",
+        "[Volume]
+VolumeName={{name}}
+",
+    ),
+    (
+        "bridge-network",
+        QuadletType::Network,
+        "Red bridge con subred propia",
+        "[Network]
+NetworkName={{name}}
+Driver=bridge
+Subnet={{subnet}}
+",
+    ),
+];
+
+/// Nombres de las marcas `{{...}}` de un texto, sin repetir y en orden de aparición
+fn placeholders(content: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let key = rest[start + 2..start + 2 + len].trim().to_string();
+        if !found.contains(&key) {
+            found.push(key);
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    found
+}
+
+/// Lista de plantillas disponibles
+pub fn list_templates() -> Vec<QuadletTemplate> {
+    TEMPLATES
+        .iter()
+        .map(|(id, kind, description, content)| QuadletTemplate {
+            id: id.to_string(),
+            kind: *kind,
+            description: description.to_string(),
+            placeholders: placeholders(content),
+            content: content.to_string(),
+        })
+        .collect()
+}
+
+/// Busca una plantilla por su identificador
+pub fn find_template(id: &str) -> Option<QuadletTemplate> {
+    list_templates()
+        .into_iter()
+        .find(|template| template.id == id)
+}
+
+/// Sustituye las marcas de la plantilla. Falla si queda alguna sin valor
+pub fn render_template(
+    template: &QuadletTemplate,
+    values: &HashMap<String, String>,
+) -> Result<String, AppError> {
+    let missing: Vec<&str> = template
+        .placeholders
+        .iter()
+        .filter(|key| !values.contains_key(key.as_str()))
+        .map(String::as_str)
+        .collect();
+    if !missing.is_empty() {
+        return Err(AppError::validation_error(
+            "values",
+            &format!("faltan variables de la plantilla: {}", missing.join(", ")),
+        ));
+    }
+    let mut content = template.content.clone();
+    for key in &template.placeholders {
+        content = content.replace(&format!("{{{{{}}}}}", key), &values[key]);
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_render_with_all_values() {
+        let template = find_template("basic-container").unwrap();
+        assert_eq!(template.placeholders, vec!["name", "image", "port"]);
+        let content = render_template(
+            &template,
+            &values(&[
+                ("name", "web"),
+                ("image", "docker.io/library/nginx:latest"),
+                ("port", "8080:80"),
+            ]),
+        )
+        .unwrap();
+        assert!(content.contains("Description=web\n"));
+        assert!(content.contains("Image=docker.io/library/nginx:latest\n"));
+        assert!(content.contains("PublishPort=8080:80\n"));
+        assert!(!content.contains("{{"));
+    }
+
+    #[test]
+    fn test_render_with_missing_value() {
+        let template = find_template("basic-container").unwrap();
+        let result = render_template(&template, &values(&[("name", "web")]));
+        match result {
+            Err(AppError::ValidationError(msg)) => assert!(msg.contains("image, port"), "{}", msg),
+            _ => panic!("se esperaba un error de validación"),
+        }
+    }
+
+    #[test]
+    fn test_templates_have_unique_ids() {
+        let templates = list_templates();
+        for template in &templates {
+            let count = templates.iter().filter(|t| t.id == template.id).count();
+            assert_eq!(count, 1, "id repetido: {}", template.id);
+        }
+    }
+}
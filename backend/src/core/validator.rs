@@ -1,28 +1,23 @@
-use serde::Serialize;
-use ts_rs::TS;
-use std::collections::HashMap;
-
-#[derive(Serialize, TS)]
-#[ts(export, export_to = "../../frontend/src/bindings/ValidationError.ts")]
-pub struct ValidationError {
-    pub field: String,
-    pub message: String,
-}
+use super::parser::{parse_content, parse_quadlet_typed};
+use crate::models::{QuadletType, ValidationError};
+use std::collections::{HashMap, HashSet};
 
 pub struct SemanticValidator;
 
 impl SemanticValidator {
-    pub fn validate(parsed_data: &HashMap<String, HashMap<String, String>>) -> Vec<ValidationError> {
+    pub fn validate(
+        parsed_data: &HashMap<String, HashMap<String, String>>,
+    ) -> Vec<ValidationError> {
         let mut errors = Vec::new();
 
         // 1. Validar existencia de la sección [Container]
         if let Some(container_section) = parsed_data.get("Container") {
-            
             // 2. Validar campo obligatorio: Image
             if !container_section.contains_key("Image") {
                 errors.push(ValidationError {
                     field: "Container.Image".to_string(),
-                    message: "La clave 'Image' es obligatoria para definir un contenedor.".to_string(),
+                    message: "La clave 'Image' es obligatoria para definir un contenedor."
+                        .to_string(),
                 });
             }
 
@@ -42,6 +37,207 @@ impl SemanticValidator {
             });
         }
 
+        // 4. Secciones de systemd que quadlet copia tal cual a la unidad generada
+        if let Some(service_section) = parsed_data.get("Service") {
+            if let Some(restart) = service_section.get("Restart") {
+                if !RESTART_VALUES.contains(&restart.as_str()) {
+                    errors.push(ValidationError {
+                        field: "Service.Restart".to_string(),
+                        message: format!(
+                            "Valor de 'Restart' no válido: '{}'. Se admite: {}",
+                            restart,
+                            RESTART_VALUES.join(", ")
+                        ),
+                    });
+                }
+            }
+        }
+        if let Some(install_section) = parsed_data.get("Install") {
+            let mut keys: Vec<_> = install_section.keys().collect();
+            keys.sort();
+            for key in keys {
+                if !INSTALL_KEYS.contains(&key.as_str()) {
+                    errors.push(ValidationError {
+                        field: format!("Install.{}", key),
+                        message: format!("Clave desconocida en [Install]: '{}'", key),
+                    });
+                }
+            }
+        }
+
         errors
     }
 }
+
+/// Errores semánticos de un `.container`. Si el contenido no se puede interpretar
+/// no hay nada que comprobar: de eso ya avisa el parser
+pub fn semantic_errors(content: &str) -> Vec<ValidationError> {
+    parse_content(content)
+        .map(|data| SemanticValidator::validate(&data))
+        .unwrap_or_default()
+}
+
+/// Un `.build` necesita la sección `[Build]` con `ImageTag=` o `File=`
+fn validate_build(parsed_data: &HashMap<String, HashMap<String, String>>) -> Vec<ValidationError> {
+    let Some(build_section) = parsed_data.get("Build") else {
+        return vec![ValidationError {
+            field: "Global".to_string(),
+            message: "No se encontró la sección obligatoria [Build].".to_string(),
+        }];
+    };
+    if build_section.contains_key("ImageTag") || build_section.contains_key("File") {
+        return Vec::new();
+    }
+    vec![ValidationError {
+        field: "Build.ImageTag".to_string(),
+        message: "Un build necesita la clave 'ImageTag' o 'File'.".to_string(),
+    }]
+}
+
+/// Errores semánticos de un `.build`. Como en `semantic_errors`, si el contenido no
+/// se puede interpretar no hay nada que comprobar
+pub fn build_errors(content: &str) -> Vec<ValidationError> {
+    parse_content(content)
+        .map(|data| validate_build(&data))
+        .unwrap_or_default()
+}
+
+/// Problemas de un quadlet de cualquier tipo: el error de sintaxis si no se puede
+/// interpretar y, para los `.container` y `.build`, los errores semánticos
+pub fn lint_errors(kind: QuadletType, content: &str) -> Vec<ValidationError> {
+    match parse_content(content) {
+        Err(e) => vec![ValidationError {
+            field: "Syntax".to_string(),
+            message: e.to_string(),
+        }],
+        Ok(data) if kind == QuadletType::Container => SemanticValidator::validate(&data),
+        Ok(data) if kind == QuadletType::Build => validate_build(&data),
+        Ok(_) => Vec::new(),
+    }
+}
+
+/// Valores que admite `Restart=` en `[Service]`
+const RESTART_VALUES: [&str; 7] = [
+    "no",
+    "on-success",
+    "on-failure",
+    "on-abnormal",
+    "on-watchdog",
+    "on-abort",
+    "always",
+];
+
+/// Claves que systemd entiende en `[Install]`
+const INSTALL_KEYS: [&str; 6] = [
+    "WantedBy",
+    "RequiredBy",
+    "UpheldBy",
+    "Alias",
+    "Also",
+    "DefaultInstance",
+];
+
+/// Claves de un `.container` que pueden apuntar a otro quadlet, con la extensión del referenciado
+const REFERENCE_KEYS: [(&str, &str); 2] = [("Volume", ".volume"), ("Network", ".network")];
+
+/// Referencias de un `.container` a quadlets `.volume`/`.network` que no están entre
+/// los ficheros `existing`. Si el contenido no se puede interpretar no hay nada que comprobar
+pub fn missing_references(content: &str, existing: &HashSet<String>) -> Vec<ValidationError> {
+    let Ok(content) = parse_quadlet_typed(content) else {
+        return Vec::new();
+    };
+    let mut errors = Vec::new();
+    for (key, extension) in REFERENCE_KEYS {
+        for value in content.values("Container", key) {
+            // `datos.volume:/data:Z` o `red.network:ip=10.0.0.2`
+            let reference = value.split(':').next().unwrap_or_default();
+            if reference.ends_with(extension) && !existing.contains(reference) {
+                errors.push(ValidationError {
+                    field: format!("Container.{}", key),
+                    message: format!("No existe el quadlet '{}'", reference),
+                });
+            }
+        }
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dangling_network_reference() {
+        let existing = HashSet::from(["datos.volume".to_string(), "front.network".to_string()]);
+        let content = "[Container]\nImage=nginx\nVolume=datos.volume:/data\n\
+                       Network=front.network\nNetwork=back.network:ip=10.0.0.2\n";
+        assert_eq!(
+            missing_references(content, &existing),
+            vec![ValidationError {
+                field: "Container.Network".to_string(),
+                message: "No existe el quadlet 'back.network'".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_host_paths_and_named_networks_are_not_references() {
+        let content = "[Container]\nImage=nginx\nVolume=/srv/data:/data\nNetwork=host\n";
+        assert!(missing_references(content, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_build_needs_image_tag_or_file() {
+        assert!(build_errors("[Build]\nImageTag=localhost/web\n").is_empty());
+        assert!(build_errors("[Build]\nFile=/srv/web/Containerfile\n").is_empty());
+
+        let errors = build_errors("[Build]\nSetWorkingDirectory=unit\n");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "Build.ImageTag");
+
+        let errors = lint_errors(QuadletType::Build, "[Container]\nImage=nginx\n");
+        assert_eq!(
+            errors[0].message,
+            "No se encontró la sección obligatoria [Build]."
+        );
+    }
+
+    #[test]
+    fn test_invalid_restart_value() {
+        let content = "[Container]\nImage=nginx\n\n[Service]\nRestart=sometimes\n";
+        let errors = semantic_errors(content);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "Service.Restart");
+        assert!(errors[0].message.contains("'sometimes'"));
+
+        let content = "[Container]\nImage=nginx\n\n[Service]\nRestart=on-failure\n";
+        assert!(semantic_errors(content).is_empty());
+    }
+
+    #[test]
+    fn test_unknown_install_key() {
+        let content = "[Container]\nImage=nginx\n\n[Install]\n\
+                       WantedBy=default.target\nWantedby=multi-user.target\n";
+        assert_eq!(
+            semantic_errors(content),
+            vec![ValidationError {
+                field: "Install.Wantedby".to_string(),
+                message: "Clave desconocida en [Install]: 'Wantedby'".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_lint_errors() {
+        let errors = lint_errors(QuadletType::Volume, "[Volume\nLabel=x\n");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "Syntax");
+
+        // Solo los contenedores pasan por el validador semántico
+        assert!(lint_errors(QuadletType::Volume, "[Volume]\nLabel=x\n").is_empty());
+        assert_eq!(
+            lint_errors(QuadletType::Container, "[Container]\nContainerName=web\n")[0].field,
+            "Container.Image"
+        );
+    }
+}
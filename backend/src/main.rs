@@ -1,28 +1,39 @@
 use axum::{
-    routing::{get, post},
+    middleware,
+    routing::get,
     Router,
 };
 use sqlx::{
-    migrate::{
-        Migrator,
-        MigrateDatabase,
-    },
+    migrate::MigrateDatabase,
     sqlite::SqlitePool
 };
-use std::net::SocketAddr;
-use tower_http::cors::CorsLayer;
+use std::net::{IpAddr, SocketAddr};
+use tokio::sync::broadcast;
+use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-use std::{env::var, str::FromStr, sync::Arc, path::Path};
-use tracing::{debug, error};
-use crate::models::AppState;
+use std::{env::var, str::FromStr, sync::Arc, path::Path, time::Duration};
+use tracing::{error, warn};
+use crate::models::{AppState, DiscoverCache, FileLocks, SystemdScope, DEFAULT_DISCOVER_TTL};
 
 mod api;
 mod core;
 mod models;
 mod system;
 
+/// Dirección en la que escucha el servidor: `BIND_ADDR` (por defecto todas las
+/// interfaces) con el puerto indicado
+fn bind_addr(value: Option<&str>, port: u16) -> Result<SocketAddr, String> {
+    let ip = match value.map(str::trim) {
+        None | Some("") => IpAddr::from([0, 0, 0, 0]),
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("BIND_ADDR no es una dirección IP válida: '{}'", value))?,
+    };
+    Ok(SocketAddr::new(ip, port))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Inicializar el trazado de logs (útil para depurar)
@@ -42,6 +53,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parse()
         .unwrap_or(3000);
     info!("Port: {}", port);
+    let addr = bind_addr(var("BIND_ADDR").ok().as_deref(), port)?;
+    info!("Bind address: {}", addr);
+    let tls_paths = system::tls_paths(var("TLS_CERT").ok(), var("TLS_KEY").ok());
+    let tls_config = match &tls_paths {
+        Some((cert, key)) => {
+            info!("TLS cert: {}, key: {}", cert, key);
+            Some(system::load_tls_config(Path::new(cert), Path::new(key)).await.map_err(|e| {
+                error!("Failed to load TLS config: {:#}", e);
+                e
+            })?)
+        }
+        None => {
+            warn!("TLS not configured, serving plain HTTP. Use a TLS proxy in production");
+            None
+        }
+    };
 
 
     if !sqlx::Sqlite::database_exists(&db_url)
@@ -61,44 +88,194 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         info!("Database already exists");
     }
 
-    let migrations = if var("RUST_ENV") == Ok("production".to_string()){
-        std::env::current_exe().unwrap().parent().unwrap().join("migrations")
-    }else{
-        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
-        Path::new(&crate_dir).join("migrations")
-    };
-    info!("{}", &migrations.display());
+    let migrations = system::migrations_dir().map_err(|e| {
+        error!("Failed to locate database migrations: {:#}", e);
+        e
+    })?;
+    info!("Migrations: {}", migrations.display());
 
     let pool = SqlitePool::connect(&db_url).await.expect("Failed to connect to database");
 
-    Migrator::new(migrations)
-        .await?
-        .run(&pool)
-        .await
-        .expect("Failed to run database migrations");
+    system::run_migrations(&pool, &migrations).await.map_err(|e| {
+        error!("Failed to run database migrations: {:#}", e);
+        e
+    })?;
+
+    // Administrador inicial. Sin usuarios ni QUADLY_ADMIN_PASS no se puede arrancar
+    system::init_db(&pool).await.map_err(|e| {
+        error!("Failed to initialize users: {}", e);
+        e
+    })?;
+
+    // Comprobar que el generador de quadlets está instalado, si no, systemd nunca
+    // generará las unidades y los cambios parecerán no tener efecto
+    let quadlet_generator = system::probe_quadlet_generator(|path| path.exists());
+    match &quadlet_generator {
+        Some(path) => info!("Quadlet generator: {}", path.display()),
+        None => warn!(
+            "⚠️  Quadlet generator not found! Saved quadlets will not generate systemd units. Is podman installed?"
+        ),
+    }
 
     // Configuración de CORS para permitir al frontend de React comunicarse
     let cors = CorsLayer::permissive(); // En producción deberías restringirlo
 
+    let access_log = matches!(var("ACCESS_LOG").as_deref(), Ok("true") | Ok("1"));
+    info!("Access log: {}", access_log);
+
+    // Claves que se ocultan al leer quadlets con ?redact=true, separadas por comas
+    let redact_patterns: Vec<String> = match var("REDACT_PATTERNS") {
+        Ok(patterns) => patterns
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect(),
+        Err(_) => core::DEFAULT_REDACT_PATTERNS
+            .iter()
+            .map(|p| p.to_string())
+            .collect(),
+    };
+    info!("Redact patterns: {:?}", redact_patterns);
+
+    // Variables que se pueden usar como ${VAR} al guardar con ?expand=true
+    let expand_vars: Vec<String> = match var("EXPAND_VARS") {
+        Ok(vars) => vars
+            .split(',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+            .collect(),
+        Err(_) => core::DEFAULT_EXPAND_VARS
+            .iter()
+            .map(|v| v.to_string())
+            .collect(),
+    };
+    info!("Expand vars: {:?}", expand_vars);
+
+    let scope = SystemdScope::from_env();
+    info!("Systemd scope: {}", scope);
+    let bus = system::SystemdBus::new(scope);
+
+    // Canal de eventos de estado y monitor de systemd que lo alimenta
+    let (events, _) = broadcast::channel(100);
+    let monitor_events = events.clone();
+    let monitor_bus = bus.clone();
+    tokio::spawn(system::supervise_systemd_events(monitor_bus, monitor_events));
+
+    // TTL de la caché de descubrimiento, en milisegundos
+    let discover_ttl = var("DISCOVER_CACHE_TTL_MS")
+        .ok()
+        .and_then(|ttl| ttl.parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_DISCOVER_TTL);
+    info!("Discover cache TTL: {:?}", discover_ttl);
+
+    // Build del frontend que se sirve fuera de /api/v1
+    let static_dir = var("STATIC_DIR").unwrap_or("static".to_string());
+    info!("Static dir: {}", static_dir);
+
+    let state = Arc::new(AppState {
+        pool: pool.clone(),
+        secret,
+        static_dir,
+        quadlet_generator,
+        access_log,
+        scope,
+        bus,
+        events,
+        discover_cache: DiscoverCache::new(discover_ttl),
+        file_locks: FileLocks::default(),
+        tls: tls_config.is_some(),
+        redact_patterns,
+        expand_vars,
+    });
+
+    // Cambios hechos en el directorio de quadlets fuera de la API
+    let watcher_state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = system::watch_quadlet_dir(
+            models::get_quadlet_dir(),
+            watcher_state.bus.clone(),
+            watcher_state.discover_cache.clone(),
+            watcher_state.events.clone(),
+        )
+        .await
+        {
+            error!("Quadlet dir watcher stopped: {}", e);
+        }
+    });
+
+    let static_files = api::static_files(&state.static_dir);
     let routes = Router::new()
         .nest("/health",api::health_router())
         .nest("/quadlets",api::quadlet_router())
         .nest("/auth",api::auth_router())
+        .nest("/users",api::user_router())
+        .nest("/audit",api::audit_router())
+        .route("/openapi.json", get(api::openapi_json))
         .fallback(api::fallback_404)
-        .with_state(Arc::new(AppState {
-            pool,
-            secret,
-            static_dir: "static".to_string(),
-        }));
+        .layer(middleware::from_fn_with_state(state.clone(), api::access_log))
+        .layer(middleware::from_fn_with_state(state.clone(), api::authenticate))
+        .with_state(state);
 
     // Definición de las rutas de Quadly
-    let app = Router::new().nest("/api/v1", routes).layer(cors);
+    let in_flight = system::InFlight::default();
+    let app = Router::new()
+        .nest("/api/v1", routes)
+        .fallback_service(static_files)
+        .layer(cors)
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(api::request_span)
+                .on_request(api::on_request)
+                .on_response(api::on_response),
+        )
+        .layer(middleware::from_fn_with_state(in_flight.clone(), api::track_in_flight));
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    println!("🚀 Quadly Backend arrancando en http://{}", addr);
+    match tls_config {
+        Some(tls_config) => {
+            println!("🚀 Quadly Backend arrancando en https://{}", addr);
+            // axum-server no acepta un futuro de apagado, se le avisa con el handle
+            let handle = axum_server::Handle::new();
+            let shutdown_handle = handle.clone();
+            tokio::spawn(async move {
+                system::shutdown_signal(in_flight).await;
+                shutdown_handle.graceful_shutdown(None);
+            });
+            axum_server::bind_rustls(addr, tls_config)
+                .handle(handle)
+                .serve(app.into_make_service())
+                .await?;
+        }
+        None => {
+            println!("🚀 Quadly Backend arrancando en http://{}", addr);
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            axum::serve(listener, app)
+                .with_graceful_shutdown(system::shutdown_signal(in_flight))
+                .await?;
+        }
+    }
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    // Ya no queda ninguna petición que pueda usar la base de datos
+    info!("Cerrando conexiones de base de datos...");
+    pool.close().await;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_addr() {
+        assert_eq!(bind_addr(None, 3000).unwrap().to_string(), "0.0.0.0:3000");
+        assert_eq!(
+            bind_addr(Some("127.0.0.1"), 8080).unwrap().to_string(),
+            "127.0.0.1:8080"
+        );
+        assert_eq!(bind_addr(Some("::1"), 8080).unwrap().to_string(), "[::1]:8080");
+        assert!(bind_addr(Some("localhost"), 8080).is_err());
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Registro de una petición autenticada
+#[derive(Debug, FromRow, Serialize, Deserialize)]
+pub struct AccessLogEntry {
+    pub id: i64,
+    pub timestamp: String,
+    pub username: String,
+    pub method: String,
+    pub path: String,
+    pub status: i64,
+}
+
+impl AccessLogEntry {
+    pub async fn create(
+        pool: &sqlx::SqlitePool,
+        username: &str,
+        method: &str,
+        path: &str,
+        status: u16,
+    ) -> sqlx::Result<()> {
+        sqlx::query("INSERT INTO access_log (username, method, path, status) VALUES (?, ?, ?, ?)")
+            .bind(username)
+            .bind(method)
+            .bind(path)
+            .bind(status)
+            .execute(pool)
+            .await
+            .map(|_| ())
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use ts_rs::TS;
+
+/// Operación que modificó un quadlet o actuó sobre su unidad. `result` es "ok"
+/// o el motivo del fallo
+#[derive(Debug, FromRow, Serialize, Deserialize, TS)]
+#[ts(export_to = "../../frontend/src/bindings/AuditEntry.ts")]
+pub struct AuditEntry {
+    pub id: i64,
+    pub timestamp: String,
+    pub username: String,
+    pub action: String,
+    pub target: String,
+    pub result: String,
+}
+
+impl AuditEntry {
+    pub async fn create(
+        pool: &sqlx::SqlitePool,
+        username: &str,
+        action: &str,
+        target: &str,
+        result: &str,
+    ) -> sqlx::Result<()> {
+        sqlx::query("INSERT INTO audit_log (username, action, target, result) VALUES (?, ?, ?, ?)")
+            .bind(username)
+            .bind(action)
+            .bind(target)
+            .bind(result)
+            .execute(pool)
+            .await
+            .map(|_| ())
+    }
+
+    /// Página de entradas, de la más reciente a la más antigua, y el total
+    pub async fn read_page(
+        pool: &sqlx::SqlitePool,
+        limit: i64,
+        offset: i64,
+    ) -> sqlx::Result<(Vec<AuditEntry>, i64)> {
+        let entries = sqlx::query_as::<_, AuditEntry>(
+            "SELECT * FROM audit_log ORDER BY id DESC LIMIT ? OFFSET ?",
+        )
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+        let total = sqlx::query_scalar("SELECT COUNT(*) FROM audit_log")
+            .fetch_one(pool)
+            .await?;
+        Ok((entries, total))
+    }
+}
@@ -0,0 +1,93 @@
+//! Exportación de los tipos de TypeScript del frontend. Es el único sitio que genera
+//! `frontend/src/bindings`: un tipo nuevo que llegue al frontend hay que añadirlo aquí
+use super::*;
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use ts_rs::{Config, TypeVisitor, TS};
+
+/// Destino de los bindings, relativo al directorio de exportación de ts-rs
+const BINDINGS_DIR: &str = "../../frontend/src/bindings";
+
+/// Recorre las dependencias de un tipo y anota las que no se exportan a `BINDINGS_DIR`
+#[derive(Default)]
+struct MissingBindings {
+    seen: HashSet<&'static str>,
+    missing: Vec<String>,
+}
+
+impl TypeVisitor for MissingBindings {
+    fn visit<T: TS + 'static + ?Sized>(&mut self) {
+        if !self.seen.insert(std::any::type_name::<T>()) {
+            return;
+        }
+        if let Some(path) = T::output_path() {
+            if !path.starts_with(BINDINGS_DIR) {
+                self.missing.push(std::any::type_name::<T>().to_string());
+            }
+        }
+        T::visit_dependencies(self);
+        T::visit_generics(self);
+    }
+}
+
+/// Resuelve los `..` sin tocar el disco: el directorio de exportación puede no existir
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn export<T: TS + 'static>(cfg: &Config, check: &mut MissingBindings) {
+    check.visit::<T>();
+    T::export_all(cfg).unwrap();
+    let path = normalize(&cfg.out_dir().join(T::output_path().unwrap()));
+    assert!(path.exists(), "{} no se ha generado", path.display());
+}
+
+#[test]
+fn export_bindings() {
+    let cfg = Config::from_env();
+    let mut check = MissingBindings::default();
+    export::<ApiResponse<()>>(&cfg, &mut check);
+    export::<ErrorResponse>(&cfg, &mut check);
+    export::<TokenClaims>(&cfg, &mut check);
+    export::<Role>(&cfg, &mut check);
+    export::<CurrentUser>(&cfg, &mut check);
+    export::<UserPass>(&cfg, &mut check);
+    export::<ServerConfig>(&cfg, &mut check);
+    export::<server_config::ServerFeatures>(&cfg, &mut check);
+    export::<SystemdScope>(&cfg, &mut check);
+    export::<Quadlet>(&cfg, &mut check);
+    export::<QuadletInfo>(&cfg, &mut check);
+    export::<QuadletSummary>(&cfg, &mut check);
+    export::<QuadletDiff>(&cfg, &mut check);
+    export::<QuadletGraph>(&cfg, &mut check);
+    export::<QuadletLint>(&cfg, &mut check);
+    export::<QuadletMeta>(&cfg, &mut check);
+    export::<QuadletTemplate>(&cfg, &mut check);
+    export::<ActionPreview>(&cfg, &mut check);
+    export::<ApplyResult>(&cfg, &mut check);
+    export::<BulkActionResult>(&cfg, &mut check);
+    export::<ImagePull>(&cfg, &mut check);
+    export::<UnitAction>(&cfg, &mut check);
+    export::<UnitResources>(&cfg, &mut check);
+    export::<StatusDetail>(&cfg, &mut check);
+    export::<OrphanUnit>(&cfg, &mut check);
+    export::<LogEntry>(&cfg, &mut check);
+    export::<UnitErrors>(&cfg, &mut check);
+    export::<AuditEntry>(&cfg, &mut check);
+    export::<Versions>(&cfg, &mut check);
+    assert!(
+        check.missing.is_empty(),
+        "Tipos sin export_to a {}: {:?}",
+        BINDINGS_DIR,
+        check.missing
+    );
+}
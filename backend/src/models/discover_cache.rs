@@ -0,0 +1,127 @@
+use super::QuadletInfo;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// TTL por defecto de la caché de descubrimiento
+pub const DEFAULT_DISCOVER_TTL: Duration = Duration::from_secs(2);
+
+/// Resultado guardado junto al momento en el que se obtuvo
+type CachedDiscovery = (Instant, Vec<QuadletInfo>);
+
+/// Caché en memoria del último descubrimiento de quadlets, para que un dashboard que
+/// consulta cada pocos segundos no relea el directorio ni llame a D-Bus en cada petición
+#[derive(Clone)]
+pub struct DiscoverCache {
+    ttl: Duration,
+    entry: Arc<Mutex<Option<CachedDiscovery>>>,
+}
+
+impl DiscoverCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Devuelve el resultado en caché si no ha caducado; si no, lo recalcula con `refresh`.
+    /// El cerrojo se mantiene durante el refresco para no escanear dos veces a la vez.
+    /// Los errores no se guardan en caché
+    pub async fn get_or_refresh<F, Fut, E>(&self, refresh: F) -> Result<Vec<QuadletInfo>, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Vec<QuadletInfo>, E>>,
+    {
+        let mut entry = self.entry.lock().await;
+        if let Some((cached_at, quadlets)) = entry.as_ref() {
+            if cached_at.elapsed() < self.ttl {
+                return Ok(quadlets.clone());
+            }
+        }
+        let quadlets = refresh().await?;
+        *entry = Some((Instant::now(), quadlets.clone()));
+        Ok(quadlets)
+    }
+
+    /// Descarta el resultado en caché tras un cambio en los quadlets
+    pub async fn invalidate(&self) {
+        *self.entry.lock().await = None;
+    }
+}
+
+impl Default for DiscoverCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_DISCOVER_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::QuadletType;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn scan(scans: &AtomicUsize) -> Result<Vec<QuadletInfo>, String> {
+        scans.fetch_add(1, Ordering::SeqCst);
+        Ok(vec![QuadletInfo {
+            name: "web".to_string(),
+            kind: QuadletType::Container,
+            status: None,
+            modified: None,
+            health: None,
+            enablement: None,
+            meta: None,
+        }])
+    }
+
+    #[tokio::test]
+    async fn test_second_call_within_ttl_is_cached() {
+        let cache = DiscoverCache::new(Duration::from_secs(60));
+        let scans = AtomicUsize::new(0);
+
+        let first = cache.get_or_refresh(|| scan(&scans)).await.unwrap();
+        let second = cache.get_or_refresh(|| scan(&scans)).await.unwrap();
+
+        assert_eq!(scans.load(Ordering::SeqCst), 1);
+        assert_eq!(first[0].name, second[0].name);
+    }
+
+    #[tokio::test]
+    async fn test_expired_entry_is_refreshed() {
+        let cache = DiscoverCache::new(Duration::ZERO);
+        let scans = AtomicUsize::new(0);
+
+        cache.get_or_refresh(|| scan(&scans)).await.unwrap();
+        cache.get_or_refresh(|| scan(&scans)).await.unwrap();
+
+        assert_eq!(scans.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_invalidate_busts_cache() {
+        let cache = DiscoverCache::new(Duration::from_secs(60));
+        let scans = AtomicUsize::new(0);
+
+        cache.get_or_refresh(|| scan(&scans)).await.unwrap();
+        cache.invalidate().await;
+        cache.get_or_refresh(|| scan(&scans)).await.unwrap();
+
+        assert_eq!(scans.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_not_cached() {
+        let cache = DiscoverCache::new(Duration::from_secs(60));
+        let scans = AtomicUsize::new(0);
+
+        let failed = cache
+            .get_or_refresh(|| async { Err::<Vec<QuadletInfo>, _>("boom".to_string()) })
+            .await;
+        assert!(failed.is_err());
+        cache.get_or_refresh(|| scan(&scans)).await.unwrap();
+
+        assert_eq!(scans.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,14 +1,15 @@
 use axum::{
-    Json,
     http::StatusCode,
     response::{IntoResponse, Response},
+    Json,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use ts_rs::TS;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize, TS)]
-#[ts(export, export_to = "../../frontend/src/bindings/AppError.ts")]
+#[derive(Debug, Serialize, Deserialize, TS, ToSchema)]
+#[ts(export_to = "../../frontend/src/bindings/AppError.ts")]
 pub struct ErrorResponse {
     pub error: String,
     pub message: String,
@@ -19,14 +20,21 @@ pub struct ErrorResponse {
 pub enum AppError {
     // Errores de sistema
     SystemdError(String),
+    /// systemd no respondió a tiempo
+    Timeout(String),
     StorageError(String),
     ParseError(String),
 
     // Errores de API
     NotFound(String),
     BadRequest(String),
+    /// El recurso cambió desde que el cliente lo leyó
+    Conflict(String),
+    /// El cuerpo de la petición supera `MAX_BODY_BYTES`
+    PayloadTooLarge(String),
     InternalServerError(String),
     Unauthorized,
+    Forbidden,
 
     // Errores de validación
     ValidationError(String),
@@ -39,12 +47,16 @@ impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             AppError::SystemdError(msg) => write!(f, "Error de systemd: {}", msg),
+            AppError::Timeout(msg) => write!(f, "Tiempo de espera agotado: {}", msg),
             AppError::StorageError(msg) => write!(f, "Error de almacenamiento: {}", msg),
             AppError::ParseError(msg) => write!(f, "Error de parseo: {}", msg),
             AppError::NotFound(msg) => write!(f, "No encontrado: {}", msg),
             AppError::BadRequest(msg) => write!(f, "Solicitud incorrecta: {}", msg),
+            AppError::Conflict(msg) => write!(f, "Conflicto: {}", msg),
+            AppError::PayloadTooLarge(msg) => write!(f, "Petición demasiado grande: {}", msg),
             AppError::InternalServerError(msg) => write!(f, "Error interno: {}", msg),
             AppError::Unauthorized => write!(f, "No autorizado"),
+            AppError::Forbidden => write!(f, "Prohibido"),
             AppError::ValidationError(msg) => write!(f, "Error de validación: {}", msg),
             AppError::Generic(err) => write!(f, "{}", err),
         }
@@ -57,12 +69,17 @@ impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_type, message) = match self {
             AppError::SystemdError(msg) => (StatusCode::SERVICE_UNAVAILABLE, "systemd_error", msg),
+            AppError::Timeout(msg) => (StatusCode::GATEWAY_TIMEOUT, "timeout", msg),
             AppError::StorageError(msg) => {
                 (StatusCode::INTERNAL_SERVER_ERROR, "storage_error", msg)
             }
             AppError::ParseError(msg) => (StatusCode::BAD_REQUEST, "parse_error", msg),
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, "not_found", msg),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "bad_request", msg),
+            AppError::Conflict(msg) => (StatusCode::CONFLICT, "conflict", msg),
+            AppError::PayloadTooLarge(msg) => {
+                (StatusCode::PAYLOAD_TOO_LARGE, "payload_too_large", msg)
+            }
             AppError::InternalServerError(msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 "internal_server_error",
@@ -73,6 +90,11 @@ impl IntoResponse for AppError {
                 "unauthorized",
                 "No autorizado".to_string(),
             ),
+            AppError::Forbidden => (
+                StatusCode::FORBIDDEN,
+                "forbidden",
+                "Se necesita el rol de administrador".to_string(),
+            ),
             AppError::ValidationError(msg) => {
                 (StatusCode::UNPROCESSABLE_ENTITY, "validation_error", msg)
             }
@@ -124,6 +146,15 @@ impl AppError {
         AppError::NotFound(format!("Recurso '{}' no encontrado", resource))
     }
 
+    /// Convierte un error de E/S sobre `resource`, distinguiendo los ficheros que no existen
+    pub fn from_io(err: std::io::Error, resource: &str) -> Self {
+        match err.kind() {
+            std::io::ErrorKind::NotFound => AppError::not_found(resource),
+            std::io::ErrorKind::InvalidInput => AppError::BadRequest(err.to_string()),
+            _ => AppError::StorageError(err.to_string()),
+        }
+    }
+
     pub fn bad_request(msg: &str) -> Self {
         AppError::BadRequest(msg.to_string())
     }
@@ -132,13 +163,6 @@ impl AppError {
         AppError::ValidationError(format!("Campo '{}': {}", field, reason))
     }
 
-    pub fn systemd_action_failed(action: &str, unit: &str) -> Self {
-        AppError::SystemdError(format!(
-            "Error ejecutando '{}' en unidad '{}'",
-            action, unit
-        ))
-    }
-
     pub fn quadlet_parse_error(filename: &str, details: &str) -> Self {
         AppError::ParseError(format!(
             "Error parseando quadlet '{}': {}",
@@ -146,3 +170,21 @@ impl AppError {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timeout_is_gateway_timeout() {
+        let response = AppError::Timeout("start en web.service".to_string()).into_response();
+        assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: ErrorResponse = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(error.error, "timeout");
+        assert_eq!(error.message, "start en web.service");
+        assert_eq!(error.status, 504);
+    }
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// Cerrojos por fichero de quadlet, para que dos peticiones sobre el mismo fichero
+/// (guardar, borrar, mover...) no se intercalen. Solo se guardan referencias débiles:
+/// la entrada de un fichero se descarta cuando nadie lo tiene bloqueado ni espera por él
+#[derive(Clone, Default)]
+pub struct FileLocks {
+    locks: Arc<Mutex<HashMap<String, Weak<AsyncMutex<()>>>>>,
+}
+
+impl FileLocks {
+    /// Espera a tener en exclusiva el fichero `file`. Se libera al soltar el guard
+    pub async fn lock(&self, file: &str) -> OwnedMutexGuard<()> {
+        let lock = {
+            let mut locks = self.locks.lock().unwrap();
+            locks.retain(|_, lock| lock.strong_count() > 0);
+            match locks.get(file).and_then(Weak::upgrade) {
+                Some(lock) => lock,
+                None => {
+                    let lock = Arc::new(AsyncMutex::new(()));
+                    locks.insert(file.to_string(), Arc::downgrade(&lock));
+                    lock
+                }
+            }
+        };
+        lock.lock_owned().await
+    }
+
+    /// Ficheros con un cerrojo vivo
+    #[cfg(test)]
+    fn live(&self) -> usize {
+        let locks = self.locks.lock().unwrap();
+        locks
+            .values()
+            .filter(|lock| lock.strong_count() > 0)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_same_file_waits_for_the_guard() {
+        let locks = FileLocks::default();
+        let guard = locks.lock("web.container").await;
+
+        let waiting = locks.clone();
+        let second = tokio::spawn(async move { waiting.lock("web.container").await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!second.is_finished());
+
+        drop(guard);
+        tokio::time::timeout(Duration::from_secs(1), second)
+            .await
+            .expect("el segundo cerrojo no se concedió")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_other_files_do_not_wait() {
+        let locks = FileLocks::default();
+        let _web = locks.lock("web.container").await;
+        tokio::time::timeout(Duration::from_secs(1), locks.lock("db.container"))
+            .await
+            .expect("un fichero distinto no debe esperar");
+    }
+
+    #[tokio::test]
+    async fn test_released_locks_are_not_kept() {
+        let locks = FileLocks::default();
+        let web = locks.lock("web.container").await;
+        let db = locks.lock("db.container").await;
+        assert_eq!(locks.live(), 2);
+        drop(web);
+        drop(db);
+        assert_eq!(locks.live(), 0);
+
+        // La siguiente petición limpia las entradas muertas
+        let _data = locks.lock("data.volume").await;
+        assert_eq!(locks.locks.lock().unwrap().len(), 1);
+    }
+}
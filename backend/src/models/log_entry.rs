@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Entrada de log de journald
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export_to = "../../frontend/src/bindings/LogEntry.ts")]
+pub struct LogEntry {
+    /// Momento en el que se registró la entrada (RFC 3339)
+    #[ts(type = "string")]
+    pub timestamp: DateTime<Utc>,
+    /// Prioridad syslog (0 = emerg ... 7 = debug)
+    pub priority: u8,
+    /// Mensaje
+    pub message: String,
+    /// Unidad systemd que generó la entrada
+    pub unit: String,
+}
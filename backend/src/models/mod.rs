@@ -1,20 +1,126 @@
+use crate::system::SystemdBus;
 use sqlx::SqlitePool;
+use std::path::PathBuf;
+use tokio::sync::broadcast;
+mod access_log;
+mod audit_entry;
+#[cfg(test)]
+mod bindings;
+mod discover_cache;
 mod error;
+mod file_locks;
+mod log_entry;
+mod orphan_unit;
 mod quadlet;
+mod quadlet_graph;
+mod quadlet_lint;
+mod quadlet_meta;
+mod quadlet_template;
 mod quadlet_type;
 mod response;
+mod role;
+mod scope;
+mod server_config;
+mod status_detail;
 mod token_claims;
+mod unit_action;
+mod unit_errors;
+mod unit_resources;
 mod user;
+mod validation_error;
+mod versions;
 
-pub use quadlet::{get_quadlet_dir, Quadlet, QuadletInfo, QuadletStatus};
+pub use access_log::AccessLogEntry;
+pub use audit_entry::AuditEntry;
+pub use discover_cache::{DiscoverCache, DEFAULT_DISCOVER_TTL};
+pub use error::{AppError, ErrorResponse};
+pub use file_locks::FileLocks;
+pub use log_entry::LogEntry;
+pub use orphan_unit::OrphanUnit;
+pub use quadlet::{
+    content_etag, dir_writable, file_modified, get_quadlet_dir, get_unit_dir, validate_quadlet_name, write_quadlet_file,
+    ActionPreview, ApplyResult, ApplyStep, BulkActionResult, HealthState, ImagePull, Quadlet, QuadletDiff, QuadletInfo, QuadletStatus,
+    QuadletSummary,
+};
+pub use quadlet_graph::{GraphEdge, GraphNode, QuadletGraph};
+pub use quadlet_lint::QuadletLint;
+pub use quadlet_meta::QuadletMeta;
+pub use quadlet_template::QuadletTemplate;
 pub use quadlet_type::QuadletType;
 pub use response::CustomResponse;
+pub(crate) use response::ApiResponse;
+pub use role::Role;
+pub use scope::SystemdScope;
+pub use server_config::ServerConfig;
+pub use status_detail::StatusDetail;
 pub use token_claims::TokenClaims;
-pub use user::{NewUser, User, UserPass};
+pub use unit_action::UnitAction;
+pub use unit_errors::UnitErrors;
+pub use unit_resources::UnitResources;
+pub use user::{CurrentUser, NewUser, User, UserPass};
+pub use validation_error::ValidationError;
+pub use versions::Versions;
 
 #[derive(Clone)]
 pub struct AppState {
     pub pool: SqlitePool,
     pub secret: String,
     pub static_dir: String,
+    /// Ruta del generador de quadlets de podman, si está instalado
+    pub quadlet_generator: Option<PathBuf>,
+    /// Registrar en base de datos las peticiones autenticadas
+    pub access_log: bool,
+    /// Ámbito de systemd (usuario o sistema)
+    pub scope: SystemdScope,
+    /// Conexión compartida al bus de systemd del ámbito configurado
+    pub bus: SystemdBus,
+    /// Canal por el que se difunden los cambios de estado de los quadlets
+    pub events: broadcast::Sender<QuadletInfo>,
+    /// Caché de corta duración del descubrimiento de quadlets
+    pub discover_cache: DiscoverCache,
+    /// Cerrojos por fichero para no intercalar escrituras sobre el mismo quadlet
+    pub file_locks: FileLocks,
+    /// El servidor sirve HTTPS, así que las cookies llevan `Secure`
+    pub tls: bool,
+    /// Patrones de las claves que se ocultan al leer un quadlet con `?redact=true`
+    pub redact_patterns: Vec<String>,
+    /// Variables de entorno que se pueden expandir al guardar con `?expand=true`
+    pub expand_vars: Vec<String>,
+}
+
+#[cfg(test)]
+impl AppState {
+    /// Estado para tests con una base de datos en memoria ya migrada
+    pub async fn for_tests() -> Self {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .expect("Failed to create test database");
+        sqlx::migrate!()
+            .run(&pool)
+            .await
+            .expect("Failed to run test migrations");
+        Self {
+            pool,
+            secret: "test-secret".to_string(),
+            static_dir: "static".to_string(),
+            quadlet_generator: None,
+            access_log: false,
+            tls: false,
+            redact_patterns: crate::core::DEFAULT_REDACT_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            expand_vars: crate::core::DEFAULT_EXPAND_VARS
+                .iter()
+                .map(|v| v.to_string())
+                .collect(),
+            scope: SystemdScope::User,
+            bus: SystemdBus::new(SystemdScope::User),
+            events: broadcast::channel(16).0,
+            discover_cache: DiscoverCache::default(),
+            file_locks: FileLocks::default(),
+        }
+    }
 }
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Unidad generada por podman cuyo quadlet ya no está en el directorio de quadlets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export_to = "../../frontend/src/bindings/OrphanUnit.ts")]
+pub struct OrphanUnit {
+    /// Nombre de la unidad systemd (`web.service`)
+    pub unit: String,
+    /// Fichero de quadlet del que se generó la unidad
+    pub source_path: String,
+    /// Estado de activación según systemd (active, inactive, failed...)
+    pub active_state: String,
+}
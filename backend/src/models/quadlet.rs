@@ -1,21 +1,46 @@
+use super::error::AppError;
+use super::quadlet_meta::QuadletMeta;
 use super::quadlet_type::QuadletType;
+use super::scope::SystemdScope;
+use super::unit_action::UnitAction;
+use super::validation_error::ValidationError;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
 use ts_rs::TS;
+use utoipa::ToSchema;
 
-#[derive(Serialize, Deserialize, TS, Debug, PartialEq, Clone, Copy)]
-#[ts(export, export_to = "../../frontend/src/bindings/QuadletStatus.ts")]
+#[derive(
+    Serialize, Deserialize, TS, ToSchema, Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy,
+)]
+#[ts(export_to = "../../frontend/src/bindings/QuadletStatus.ts")]
 pub enum QuadletStatus {
     Active,
     Inactive,
     Failed,
     Activating,
     Deactivating,
+    /// systemd no conoce la unidad (`not-found`), por ejemplo si falta un daemon-reload
+    NotLoaded,
     Unknown,
 }
 
-#[derive(Serialize, Deserialize, TS)]
-#[ts(export, export_to = "../../frontend/src/bindings/QuadletInfo.ts")]
+/// Resultado del healthcheck de podman de un contenedor
+#[derive(Serialize, Deserialize, TS, ToSchema, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+#[ts(export_to = "../../frontend/src/bindings/HealthState.ts")]
+pub enum HealthState {
+    Healthy,
+    Unhealthy,
+    /// El healthcheck aún no ha dado un resultado
+    Starting,
+    /// El contenedor no define healthcheck
+    None,
+}
+
+#[derive(Serialize, Deserialize, TS, ToSchema, Debug, Clone)]
+#[ts(export_to = "../../frontend/src/bindings/QuadletInfo.ts")]
 pub struct QuadletInfo {
     /// Nombre del quadlet (sin extensión)
     pub name: String,
@@ -23,10 +48,123 @@ pub struct QuadletInfo {
     pub kind: QuadletType,
     /// Status actual del quadlet
     pub status: Option<QuadletStatus>,
+    /// Última modificación del fichero, si el sistema de ficheros la conoce
+    #[serde(default)]
+    #[ts(type = "string | null")]
+    pub modified: Option<DateTime<Utc>>,
+    /// Healthcheck de podman. Solo para contenedores en ejecución; una unidad
+    /// activa puede tener el contenedor unhealthy
+    #[serde(default)]
+    pub health: Option<HealthState>,
+    /// Estado de habilitación de la unidad (`enabled`, `disabled`, `static`,
+    /// `generated`...). Solo para los tipos con unidad systemd
+    #[serde(default)]
+    pub enablement: Option<String>,
+    /// Etiquetas y descripción guardadas en la base de datos
+    #[serde(default)]
+    pub meta: Option<QuadletMeta>,
+}
+
+/// Resumen de un quadlet `.container` con sus claves principales ya interpretadas
+#[derive(Serialize, Deserialize, TS, Debug, Clone)]
+#[ts(export_to = "../../frontend/src/bindings/QuadletSummary.ts")]
+pub struct QuadletSummary {
+    pub name: String,
+    pub kind: QuadletType,
+    pub status: Option<QuadletStatus>,
+    pub image: Option<String>,
+    pub ports: Vec<String>,
+    /// El archivo no se pudo interpretar; `image` y `ports` vienen vacíos
+    pub parse_error: bool,
+}
+
+/// Resultado de descargar la imagen de un `.container`
+#[derive(Serialize, Deserialize, TS, Debug, Clone)]
+#[ts(export_to = "../../frontend/src/bindings/ImagePull.ts")]
+pub struct ImagePull {
+    pub image: String,
+    /// Id de la imagen descargada, si podman lo devolvió
+    pub id: Option<String>,
+    /// Progreso que mostró podman durante la descarga
+    pub output: String,
+}
+
+/// Resultado de una acción en modo dry-run: lo que pasaría sin ejecutarla
+#[derive(Serialize, Deserialize, TS, Debug, Clone)]
+#[ts(export_to = "../../frontend/src/bindings/ActionPreview.ts")]
+pub struct ActionPreview {
+    pub action: UnitAction,
+    /// Estado actual de la unidad
+    pub current: QuadletStatus,
+    /// Estado esperado tras la acción
+    pub expected: QuadletStatus,
+}
+
+/// Diferencias entre el contenido en disco de un quadlet y una edición propuesta
+#[derive(Serialize, Deserialize, TS, Debug, Clone)]
+#[ts(export_to = "../../frontend/src/bindings/QuadletDiff.ts")]
+pub struct QuadletDiff {
+    /// Diff en formato unificado, vacío si no hay cambios
+    pub diff: String,
+    pub has_changes: bool,
+}
+
+impl QuadletDiff {
+    pub fn new(file_name: &str, current: &str, proposed: &str) -> Self {
+        let diff = similar::TextDiff::from_lines(current, proposed)
+            .unified_diff()
+            .header(&format!("a/{}", file_name), &format!("b/{}", file_name))
+            .to_string();
+        Self {
+            has_changes: current != proposed,
+            diff,
+        }
+    }
+}
+
+/// Resultado de una acción en lote para una unidad
+#[derive(Serialize, Deserialize, TS, Debug, Clone)]
+#[ts(export_to = "../../frontend/src/bindings/BulkActionResult.ts")]
+pub struct BulkActionResult {
+    pub name: String,
+    pub ok: bool,
+    /// Motivo del fallo, si lo hay
+    pub error: Option<String>,
+}
+
+/// Paso de `apply` (guardar, daemon-reload y arrancar) en el que algo falló
+#[derive(Serialize, Deserialize, TS, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export_to = "../../frontend/src/bindings/ApplyStep.ts")]
+pub enum ApplyStep {
+    DaemonReload,
+    Start,
+}
+
+impl ApplyStep {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApplyStep::DaemonReload => "daemon-reload",
+            ApplyStep::Start => "start",
+        }
+    }
 }
 
+/// Resultado de guardar un quadlet y arrancar su unidad en una sola llamada.
+/// Lo ya hecho antes de un fallo no se deshace
 #[derive(Serialize, Deserialize, TS)]
-#[ts(export, export_to = "../../frontend/src/bindings/Quadlet.ts")]
+#[ts(export_to = "../../frontend/src/bindings/ApplyResult.ts")]
+pub struct ApplyResult {
+    pub quadlet: Quadlet,
+    /// Estado de la unidad tras aplicar
+    pub status: QuadletStatus,
+    /// Paso que falló después de guardar, si alguno
+    pub failed_step: Option<ApplyStep>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, TS, ToSchema)]
+#[ts(export_to = "../../frontend/src/bindings/Quadlet.ts")]
 pub struct Quadlet {
     /// Nombre del archivo (sin extensión)
     pub name: String,
@@ -38,30 +176,311 @@ pub struct Quadlet {
     pub content: Option<String>,
     /// Status actual del Quadlet
     pub status: Option<QuadletStatus>,
+    /// Hash del contenido leído de disco, para detectar ediciones concurrentes
+    pub etag: Option<String>,
+    /// Última modificación del fichero, si el sistema de ficheros la conoce
+    #[serde(default)]
+    #[ts(type = "string | null")]
+    pub modified: Option<DateTime<Utc>>,
+    /// Avisos de validación detectados al guardar que no impiden el guardado
+    #[serde(default)]
+    pub warnings: Vec<ValidationError>,
 }
 
+/// Etag de un contenido: su sha256 en hexadecimal
+pub fn content_etag(content: &str) -> String {
+    hex::encode(Sha256::digest(content.as_bytes()))
+}
+
+/// Fecha de modificación de un fichero. `None` si no existe o el sistema de ficheros
+/// no guarda mtime
+pub async fn file_modified(path: &Path) -> Option<DateTime<Utc>> {
+    let metadata = tokio::fs::metadata(path).await.ok()?;
+    metadata.modified().ok().map(DateTime::<Utc>::from)
+}
+
+/// Permisos de los quadlets si no se indica `QUADLET_FILE_MODE`. Pueden llevar
+/// secretos, así que por defecto solo los lee su dueño
+pub const DEFAULT_QUADLET_FILE_MODE: u32 = 0o600;
+
+/// Permisos con los que se guardan los quadlets, de `QUADLET_FILE_MODE` en octal
+pub fn quadlet_file_mode() -> u32 {
+    parse_file_mode(std::env::var("QUADLET_FILE_MODE").ok().as_deref())
+}
+
+fn parse_file_mode(value: Option<&str>) -> u32 {
+    let Some(value) = value else {
+        return DEFAULT_QUADLET_FILE_MODE;
+    };
+    u32::from_str_radix(value.trim_start_matches("0o"), 8)
+        .ok()
+        .filter(|mode| *mode <= 0o777)
+        .unwrap_or_else(|| {
+            tracing::warn!(
+                "QUADLET_FILE_MODE '{}' no es un modo octal válido, usando {:o}",
+                value,
+                DEFAULT_QUADLET_FILE_MODE
+            );
+            DEFAULT_QUADLET_FILE_MODE
+        })
+}
+
+/// Escribe un fichero de quadlet o de unidad de forma atómica con los permisos de
+/// `quadlet_file_mode()`. Lo usan todos los que escriben en esos directorios
+pub async fn write_quadlet_file(path: &Path, content: impl AsRef<[u8]>) -> std::io::Result<()> {
+    write_file(path, content.as_ref(), quadlet_file_mode()).await
+}
+
+/// Escribe `path` de forma atómica con los permisos `mode`. El temporal se crea ya
+/// con `mode` y la umask solo puede quitar permisos, así que nunca tiene más que
+/// esos; antes del rename se ajustan a `mode` exacto. Si falta el directorio se
+/// crea solo para el dueño (0700)
+async fn write_file(path: &Path, content: &[u8], mode: u32) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::io::AsyncWriteExt;
+
+    if let Some(dir) = path.parent() {
+        if !tokio::fs::try_exists(dir).await? {
+            tokio::fs::DirBuilder::new()
+                .recursive(true)
+                .mode(0o700)
+                .create(dir)
+                .await?;
+        }
+    }
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name));
+    // Un temporal que quedó de un guardado a medias conservaría sus permisos
+    let _ = tokio::fs::remove_file(&tmp_path).await;
+    let result = async {
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(mode)
+            .open(&tmp_path)
+            .await?;
+        file.write_all(content).await?;
+        file.sync_all().await?;
+        drop(file);
+        tokio::fs::set_permissions(&tmp_path, std::fs::Permissions::from_mode(mode)).await?;
+        tokio::fs::rename(&tmp_path, path).await
+    }
+    .await;
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+    }
+    result
+}
+
+/// Indica si este proceso puede crear ficheros en `dir`. Si aún no existe se prueba
+/// en el ascendiente más cercano que sí exista, que es donde se crearía
+pub async fn dir_writable(dir: &Path) -> bool {
+    let Some(existing) = dir.ancestors().find(|ancestor| ancestor.is_dir()) else {
+        return false;
+    };
+    let probe = existing.join(format!(".quadly-write-test-{}", std::process::id()));
+    let created = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&probe)
+        .await
+        .is_ok();
+    if created {
+        let _ = tokio::fs::remove_file(&probe).await;
+    }
+    created
+}
+
+/// Directorio de quadlets según el ámbito configurado en `QUADLY_SCOPE`
+#[cfg(not(test))]
 pub fn get_quadlet_dir() -> PathBuf {
-    // Para modo --user: ~/.config/containers/systemd/
-    let home = std::env::var("HOME").expect("No se pudo encontrar la variable HOME");
-    PathBuf::from(home).join(".config/containers/systemd")
+    super::SystemdScope::from_env().quadlet_dir()
+}
+
+/// En los tests todos los quadlets se escriben en un directorio temporal compartido
+#[cfg(test)]
+pub fn get_quadlet_dir() -> PathBuf {
+    static DIR: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+    DIR.get_or_init(|| {
+        tempfile::Builder::new()
+            .prefix("quadly-tests-")
+            .tempdir()
+            .expect("No se pudo crear el directorio temporal de tests")
+            .keep()
+    })
+    .clone()
+}
+
+/// Ruta de la plantilla del quadlet que está en `path`
+fn template_of(path: &Path) -> PathBuf {
+    let mut template = path.as_os_str().to_owned();
+    template.push(".tmpl");
+    PathBuf::from(template)
+}
+
+/// Nombres (sin extensión) de los ficheros `.{extension}` de `dir`. Un directorio
+/// que no existe no tiene quadlets; cualquier otro error al leerlo, como la falta
+/// de permisos, se propaga para no confundirlo con un directorio vacío
+async fn list_names(dir: &Path, extension: &str) -> std::io::Result<Vec<String>> {
+    let mut entries = match tokio::fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let suffix = format!(".{}", extension);
+    let mut names = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        if !entry.file_type().await?.is_file() {
+            continue;
+        }
+        if let Some(name) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.strip_suffix(&suffix))
+        {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// Directorio de unidades de systemd según el ámbito configurado en `QUADLY_SCOPE`
+#[cfg(not(test))]
+pub fn get_unit_dir() -> PathBuf {
+    super::SystemdScope::from_env().unit_dir()
+}
+
+/// En los tests los drop-ins se escriben en un directorio temporal compartido
+#[cfg(test)]
+pub fn get_unit_dir() -> PathBuf {
+    static DIR: std::sync::OnceLock<PathBuf> = std::sync::OnceLock::new();
+    DIR.get_or_init(|| {
+        tempfile::Builder::new()
+            .prefix("quadly-units-")
+            .tempdir()
+            .expect("No se pudo crear el directorio temporal de tests")
+            .keep()
+    })
+    .clone()
+}
+
+/// Valida que el nombre de un quadlet no pueda escapar del directorio de quadlets
+pub fn validate_quadlet_name(name: &str) -> Result<(), AppError> {
+    if name.is_empty() {
+        return Err(AppError::validation_error("name", "no puede estar vacío"));
+    }
+    if name.contains(['/', '\\', '\0']) || name.contains("..") {
+        return Err(AppError::validation_error(
+            "name",
+            "no puede contener '/', '\\', '..' ni caracteres nulos",
+        ));
+    }
+    Ok(())
+}
+
+/// Sección propia de Quadly. Las secciones `X-` las ignoran systemd y el generador
+const QUADLY_SECTION: &str = "[X-Quadly]";
+
+/// Descripción amigable de un quadlet: la clave `Description=` de la sección
+/// `[X-Quadly]` o, si no existe, un comentario `# Description: ...`
+fn parse_description(content: &str) -> Option<String> {
+    let mut section = "";
+    let mut comment = None;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            section = line;
+        } else if let Some(text) = line.strip_prefix(['#', ';']) {
+            if comment.is_none() {
+                comment = text.trim().strip_prefix("Description:").map(str::trim);
+            }
+        } else if section == QUADLY_SECTION {
+            if let Some(value) = line.strip_prefix("Description=") {
+                return Some(value.trim().to_string());
+            }
+        }
+    }
+    comment.filter(|c| !c.is_empty()).map(str::to_string)
+}
+
+/// Devuelve el contenido con la descripción indicada, sustituyendo la existente
+/// o añadiendo una sección `[X-Quadly]` al final
+fn with_description(content: &str, description: &str) -> String {
+    if parse_description(content).as_deref() == Some(description) {
+        return content.to_string();
+    }
+    let mut section = "";
+    let mut replaced = false;
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            section = trimmed;
+        }
+        let is_description = if section == QUADLY_SECTION {
+            trimmed.starts_with("Description=")
+        } else {
+            trimmed
+                .strip_prefix(['#', ';'])
+                .is_some_and(|text| text.trim().starts_with("Description:"))
+        };
+        if is_description && !replaced {
+            lines.push(if section == QUADLY_SECTION {
+                format!("Description={}", description)
+            } else {
+                format!("# Description: {}", description)
+            });
+            replaced = true;
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    let mut content = lines.join("\n");
+    if !replaced {
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str(&format!("{}\nDescription={}", QUADLY_SECTION, description));
+    }
+    content.push('\n');
+    content
+}
+
+/// Comprueba que la ruta final sigue estando dentro del directorio de quadlets
+/// una vez resueltos los enlaces simbólicos
+fn ensure_inside_quadlet_dir(path: &Path) -> Result<(), AppError> {
+    let dir = get_quadlet_dir();
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let inside = match (dir.canonicalize(), parent.canonicalize()) {
+        (Ok(dir), Ok(parent)) => parent == dir,
+        // Si el directorio aún no existe, comparamos las rutas sin resolver
+        _ => parent == dir,
+    };
+    if inside {
+        Ok(())
+    } else {
+        Err(AppError::validation_error(
+            "name",
+            "la ruta resultante está fuera del directorio de quadlets",
+        ))
+    }
 }
 
 impl Quadlet {
     /// Crea una nueva instancia de Quadlet
-    pub fn new(
-        name: &str,
-        extension: &str,
-        content: Option<String>,
-    ) -> Result<Self, std::io::Error> {
-        let kind = QuadletType::from_extension(&extension).ok_or_else(|| {
-            std::io::Error::new(std::io::ErrorKind::InvalidData, "Unsupported Quadlet type")
-        })?;
+    pub fn new(name: &str, extension: &str, content: Option<String>) -> Result<Self, AppError> {
+        let kind = QuadletType::from_extension(extension)
+            .ok_or_else(|| AppError::bad_request("Unsupported Quadlet type"))?;
+        validate_quadlet_name(name)?;
+        ensure_inside_quadlet_dir(&get_quadlet_dir().join(format!("{}.{}", name, kind.as_str())))?;
         Ok(Self {
             name: name.to_string(),
             kind,
             description: None,
             content,
             status: None,
+            etag: None,
+            modified: None,
+            warnings: Vec::new(),
         })
     }
     /// Devuelve el nombre completo del archivo (con extensión)
@@ -75,24 +494,76 @@ impl Quadlet {
     }
 
     /// Salva el contenido del Quadlet en el sistema de archivos. Si el Quadlet no tiene contenido, devuelve un error.
+    ///
+    /// La escritura es atómica: se escribe en un fichero temporal oculto y se renombra,
+    /// de forma que nunca queda un quadlet a medio escribir en el directorio.
     pub async fn save(&self) -> std::io::Result<()> {
-        if self.content.is_none() {
+        let Some(content) = &self.content else {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 "Quadlet can not be saved without content",
             ));
-        }
-        tokio::fs::write(self.path(), &self.content.clone().unwrap()).await
+        };
+        // La descripción se guarda dentro del propio fichero
+        let content = match &self.description {
+            Some(description) => with_description(content, description),
+            None => content.clone(),
+        };
+        write_quadlet_file(&self.path(), content).await
+    }
+
+    /// Copia con las variables `${VAR}` sin expandir. Va junto al quadlet con el
+    /// sufijo `.tmpl`, que el generador de podman no reconoce como quadlet
+    pub fn template_path(&self) -> PathBuf {
+        template_of(&self.path())
     }
 
-    /// Reads the content of the Quadlet from the file system and updates the `content` field. If the file does not exist or cannot be read, returns an error.
+    /// Guarda el contenido original de un quadlet que se expandió al guardarlo
+    pub async fn save_template(&self, template: &str) -> std::io::Result<()> {
+        write_quadlet_file(&self.template_path(), template).await
+    }
+
+    /// Ruta que tendría el fichero en el directorio de quadlets de `scope`
+    pub fn path_in(&self, scope: SystemdScope) -> PathBuf {
+        scope.quadlet_dir().join(self.full_name())
+    }
+
+    /// Mueve el fichero del Quadlet a `dest`: lo escribe allí de forma atómica y
+    /// solo entonces borra el original. La plantilla, si la hay, va con él
+    pub async fn move_to(&self, dest: &Path) -> std::io::Result<()> {
+        let content = tokio::fs::read_to_string(self.path()).await?;
+        write_quadlet_file(dest, content).await?;
+        tokio::fs::remove_file(self.path()).await?;
+        let template = match tokio::fs::read_to_string(self.template_path()).await {
+            Ok(template) => template,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        write_quadlet_file(&template_of(dest), template).await?;
+        tokio::fs::remove_file(self.template_path()).await
+    }
+
+    /// Indica si el fichero del Quadlet existe en disco
+    pub async fn exists(&self) -> std::io::Result<bool> {
+        tokio::fs::try_exists(self.path()).await
+    }
+
+    /// Reads the content of the Quadlet from the file system and updates the `content` and `description` fields. If the file does not exist or cannot be read, returns an error.
     pub async fn read(&mut self) -> std::io::Result<()> {
-        self.content = Some(tokio::fs::read_to_string(self.path()).await?);
+        let content = tokio::fs::read_to_string(self.path()).await?;
+        self.description = parse_description(&content);
+        self.etag = Some(content_etag(&content));
+        self.content = Some(content);
+        self.modified = file_modified(&self.path()).await;
         Ok(())
     }
 
     pub async fn delete(&self) -> std::io::Result<()> {
-        tokio::fs::remove_file(self.path()).await
+        tokio::fs::remove_file(self.path()).await?;
+        match tokio::fs::remove_file(self.template_path()).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
     }
 
     pub async fn read_by_extension_and_name(
@@ -104,42 +575,266 @@ impl Quadlet {
     }
 
     pub async fn read_by_extension(extension: &str) -> std::io::Result<Vec<Self>> {
-        if QuadletType::allowed_extensions()
-            .iter()
-            .find(|&&ext| ext == extension)
-            .is_none()
-        {
+        let Some(kind) = QuadletType::from_extension(extension) else {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 "Unsupported Quadlet type",
             ));
-        }
-        let dir = get_quadlet_dir();
+        };
         let mut quadlets = Vec::new();
-        if let Ok(entries) = tokio::fs::read_dir(&dir).await {
-            let mut entries = entries;
-            while let Some(entry) = entries.next_entry().await? {
-                if let Ok(file_type) = entry.file_type().await {
-                    if file_type.is_file() {
-                        if let Some(name_with_extension) = entry.file_name().to_str() {
-                            if name_with_extension.ends_with(extension) {
-                                let mut quadlet = Quadlet::new(
-                                    &name_with_extension
-                                        .trim_end_matches(extension)
-                                        .trim_end_matches('.')
-                                        .to_string(),
-                                    &extension.trim_start_matches('.').to_string(),
-                                    None,
-                                )
-                                .unwrap();
-                                quadlet.read().await?;
-                                quadlets.push(quadlet);
-                            }
-                        }
-                    }
-                }
-            }
+        for name in list_names(&get_quadlet_dir(), kind.as_str()).await? {
+            // Un fichero con un nombre no válido no es un quadlet que podamos gestionar
+            let Ok(mut quadlet) = Quadlet::new(&name, kind.as_str(), None) else {
+                continue;
+            };
+            quadlet.read().await?;
+            quadlets.push(quadlet);
         }
         Ok(quadlets)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_names_missing_dir_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let names = list_names(&dir.path().join("no-existe"), "container")
+            .await
+            .unwrap();
+        assert!(names.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_list_names_propagates_unreadable_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("web.container"), "[Container]\n").unwrap();
+        assert_eq!(
+            list_names(dir.path(), "container").await.unwrap(),
+            vec!["web"]
+        );
+
+        // Una ruta que no es un directorio también es un error, no una lista vacía
+        let file = dir.path().join("web.container");
+        assert!(list_names(&file, "container").await.is_err());
+
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o000)).unwrap();
+        // root ignora los permisos: en ese caso no hay nada que comprobar
+        if std::fs::read_dir(dir.path()).is_err() {
+            let error = list_names(dir.path(), "container").await.unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+            let error = AppError::from_io(error, "container");
+            assert!(matches!(error, AppError::StorageError(_)));
+        }
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_info_enablement_serde_shape() {
+        let info = QuadletInfo {
+            name: "web".to_string(),
+            kind: QuadletType::Container,
+            status: Some(QuadletStatus::Active),
+            modified: None,
+            health: None,
+            enablement: Some("generated".to_string()),
+            meta: None,
+        };
+        let value = serde_json::to_value(&info).unwrap();
+        assert_eq!(value["enablement"], "generated");
+
+        // Los eventos antiguos no traen el campo
+        let info: QuadletInfo =
+            serde_json::from_str(r#"{"name":"data","kind":"volume","status":"Unknown"}"#).unwrap();
+        assert_eq!(info.enablement, None);
+        assert_eq!(
+            serde_json::to_value(&info).unwrap()["enablement"],
+            serde_json::Value::Null
+        );
+    }
+
+    #[test]
+    fn test_path_in_per_scope() {
+        let home = std::env::var("HOME").unwrap();
+        let quadlet = Quadlet::new("web", "container", None).unwrap();
+        assert_eq!(
+            quadlet.path_in(SystemdScope::User),
+            PathBuf::from(home).join(".config/containers/systemd/web.container")
+        );
+        assert_eq!(
+            quadlet.path_in(SystemdScope::System),
+            PathBuf::from("/etc/containers/systemd/web.container")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_move_to_writes_destination_and_removes_source() {
+        let content = "[Volume]\nLabel=app=move\n";
+        let quadlet = Quadlet::new("move-data", "volume", Some(content.into())).unwrap();
+        quadlet.save().await.unwrap();
+        let dest_dir = tempfile::tempdir().unwrap();
+        // Un directorio que todavía no existe se comprueba en su padre
+        let dest = dest_dir.path().join("systemd/move-data.volume");
+        assert!(dir_writable(dest.parent().unwrap()).await);
+
+        quadlet
+            .save_template("[Volume]\nLabel=app=${APP}\n")
+            .await
+            .unwrap();
+
+        quadlet.move_to(&dest).await.unwrap();
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), content);
+        assert!(!quadlet.path().exists());
+        assert_eq!(
+            std::fs::read_to_string(dest_dir.path().join("systemd/move-data.volume.tmpl")).unwrap(),
+            "[Volume]\nLabel=app=${APP}\n"
+        );
+        assert!(!quadlet.template_path().exists());
+        assert!(std::fs::read_dir(dest_dir.path())
+            .unwrap()
+            .all(|entry| !entry
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .contains("write-test")));
+    }
+
+    #[test]
+    fn test_parse_file_mode() {
+        assert_eq!(parse_file_mode(None), 0o600);
+        assert_eq!(parse_file_mode(Some("0640")), 0o640);
+        assert_eq!(parse_file_mode(Some("0o644")), 0o644);
+        assert_eq!(parse_file_mode(Some("rw-r--r--")), 0o600);
+        assert_eq!(parse_file_mode(Some("1777")), 0o600);
+    }
+
+    #[tokio::test]
+    async fn test_save_sets_file_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let quadlet = Quadlet::new(
+            "mode-web",
+            "container",
+            Some("[Container]\nSecret=token\n".into()),
+        )
+        .unwrap();
+        quadlet.save().await.unwrap();
+        let mode = std::fs::metadata(quadlet.path())
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, DEFAULT_QUADLET_FILE_MODE);
+
+        // El directorio que falta se crea solo para el dueño
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("containers/systemd/web.container");
+        write_file(&path, b"[Container]\n", 0o640).await.unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o640);
+        assert_eq!(mode(path.parent().unwrap()), 0o700);
+    }
+
+    #[test]
+    fn test_rejects_malicious_names() {
+        for name in [
+            "../../.bashrc",
+            "..",
+            "foo/bar",
+            "/etc/passwd",
+            "..\\windows",
+            "foo\\bar",
+            "nul\0byte",
+            "",
+        ] {
+            assert!(
+                matches!(
+                    Quadlet::new(name, "container", None),
+                    Err(AppError::ValidationError(_))
+                ),
+                "el nombre {:?} debería rechazarse",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_description_from_comment() {
+        let content = "# Description: Servidor web de pruebas\n[Container]\nImage=nginx\n";
+        assert_eq!(
+            parse_description(content).as_deref(),
+            Some("Servidor web de pruebas")
+        );
+        let updated = with_description(content, "Nginx");
+        assert!(updated.starts_with("# Description: Nginx\n[Container]"));
+        assert_eq!(parse_description(&updated).as_deref(), Some("Nginx"));
+    }
+
+    #[test]
+    fn test_description_from_section() {
+        let content = "[Container]\nImage=nginx\n\n[X-Quadly]\nDescription=Servidor web\n";
+        assert_eq!(parse_description(content).as_deref(), Some("Servidor web"));
+        // Description= fuera de [X-Quadly] es la de systemd, no la nuestra
+        assert_eq!(parse_description("[Unit]\nDescription=web\n"), None);
+    }
+
+    #[test]
+    fn test_description_section_is_added() {
+        let content = "[Container]\nImage=nginx\n";
+        let updated = with_description(content, "Nginx");
+        assert_eq!(
+            updated,
+            "[Container]\nImage=nginx\n\n[X-Quadly]\nDescription=Nginx\n"
+        );
+        assert_eq!(with_description(&updated, "Nginx"), updated);
+    }
+
+    #[tokio::test]
+    async fn test_description_survives_save_and_read() {
+        let mut quadlet = Quadlet::new(
+            "described",
+            "container",
+            Some("[Container]\nImage=nginx\n".into()),
+        )
+        .unwrap();
+        quadlet.description = Some("Servidor web".to_string());
+        quadlet.save().await.unwrap();
+
+        let mut read = Quadlet::new("described", "container", None).unwrap();
+        read.read().await.unwrap();
+        assert_eq!(read.description.as_deref(), Some("Servidor web"));
+    }
+
+    #[test]
+    fn test_diff_added_and_removed_lines() {
+        let current = "[Container]\nImage=nginx\nPublishPort=80:80\n";
+        let proposed = "[Container]\nImage=nginx\nEnvironment=DEBUG=1\n";
+        let diff = QuadletDiff::new("web.container", current, proposed);
+        assert!(diff.has_changes);
+        assert_eq!(
+            diff.diff,
+            "--- a/web.container\n\
+             +++ b/web.container\n\
+             @@ -1,3 +1,3 @@\n \
+             [Container]\n \
+             Image=nginx\n\
+             -PublishPort=80:80\n\
+             +Environment=DEBUG=1\n"
+        );
+
+        let unchanged = QuadletDiff::new("web.container", current, current);
+        assert!(!unchanged.has_changes);
+        assert!(unchanged.diff.is_empty());
+    }
+
+    #[test]
+    fn test_accepts_regular_names() {
+        for name in ["web", "my-app", "db_1", "nginx.v2"] {
+            let quadlet = Quadlet::new(name, "container", None).unwrap();
+            assert_eq!(quadlet.path().parent(), Some(get_quadlet_dir().as_path()));
+        }
+    }
+}
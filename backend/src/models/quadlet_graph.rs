@@ -0,0 +1,35 @@
+use super::quadlet_type::QuadletType;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Quadlet del grafo de dependencias
+#[derive(Serialize, Deserialize, TS, Debug, Clone, PartialEq)]
+#[ts(export_to = "../../frontend/src/bindings/GraphNode.ts")]
+pub struct GraphNode {
+    /// Nombre del fichero, p. ej. "web.container"
+    pub id: String,
+    pub name: String,
+    pub kind: QuadletType,
+    /// Referenciado por otro quadlet pero sin fichero en disco
+    pub missing: bool,
+}
+
+/// Referencia de un quadlet a otro a través de `Pod=`, `Network=` o `Volume=`
+#[derive(Serialize, Deserialize, TS, Debug, Clone, PartialEq)]
+#[ts(export_to = "../../frontend/src/bindings/GraphEdge.ts")]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// Clave que crea la referencia
+    pub key: String,
+}
+
+/// Grafo de dependencias entre quadlets
+#[derive(Serialize, Deserialize, TS, Debug, Clone, Default)]
+#[ts(export_to = "../../frontend/src/bindings/QuadletGraph.ts")]
+pub struct QuadletGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// Ciclos encontrados, cada uno como la lista de ids que lo forman
+    pub cycles: Vec<Vec<String>>,
+}
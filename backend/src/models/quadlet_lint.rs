@@ -0,0 +1,13 @@
+use super::{QuadletType, ValidationError};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Resultado de revisar un fichero del directorio de quadlets
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export_to = "../../frontend/src/bindings/QuadletLint.ts")]
+pub struct QuadletLint {
+    pub name: String,
+    pub kind: QuadletType,
+    /// Errores de sintaxis y de validación; vacío si el quadlet es correcto
+    pub errors: Vec<ValidationError>,
+}
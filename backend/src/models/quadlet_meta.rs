@@ -0,0 +1,161 @@
+use super::AppError;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use std::collections::HashMap;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// Etiquetas y descripción de un quadlet. Se guardan en la base de datos, por
+/// fichero, para no tocar el quadlet
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+#[ts(export_to = "../../frontend/src/bindings/QuadletMeta.ts")]
+pub struct QuadletMeta {
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Fila de `quadlet_meta`. Las etiquetas van separadas por comas
+#[derive(FromRow)]
+struct MetaRow {
+    file: String,
+    tags: String,
+    description: Option<String>,
+}
+
+impl From<MetaRow> for QuadletMeta {
+    fn from(row: MetaRow) -> Self {
+        QuadletMeta {
+            tags: row
+                .tags
+                .split(',')
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+            description: row.description,
+        }
+    }
+}
+
+impl QuadletMeta {
+    /// Quita espacios y etiquetas repetidas y ordena el resto. Una etiqueta no puede
+    /// estar vacía ni llevar comas; una descripción en blanco es como no tenerla
+    pub fn normalized(self) -> Result<Self, AppError> {
+        let mut tags = Vec::with_capacity(self.tags.len());
+        for tag in &self.tags {
+            let tag = tag.trim();
+            if tag.is_empty() || tag.contains(',') {
+                return Err(AppError::bad_request(&format!(
+                    "Etiqueta no válida: '{}'",
+                    tag
+                )));
+            }
+            tags.push(tag.to_string());
+        }
+        tags.sort();
+        tags.dedup();
+        let description = self
+            .description
+            .map(|description| description.trim().to_string())
+            .filter(|description| !description.is_empty());
+        Ok(QuadletMeta { tags, description })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.description.is_none()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Metadatos de todos los quadlets, por fichero (`web.container`)
+    pub async fn read_all(pool: &SqlitePool) -> sqlx::Result<HashMap<String, QuadletMeta>> {
+        let rows = sqlx::query_as::<_, MetaRow>("SELECT * FROM quadlet_meta")
+            .fetch_all(pool)
+            .await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.file.clone(), row.into()))
+            .collect())
+    }
+
+    /// Guarda los metadatos de un fichero, sustituyendo los anteriores. Sin etiquetas
+    /// ni descripción no queda nada que guardar, así que se borra la fila
+    pub async fn save(&self, pool: &SqlitePool, file: &str) -> sqlx::Result<()> {
+        if self.is_empty() {
+            return Self::delete(pool, file).await;
+        }
+        sqlx::query(
+            "INSERT INTO quadlet_meta (file, tags, description) VALUES (?, ?, ?) \
+             ON CONFLICT(file) DO UPDATE SET tags = excluded.tags, \
+             description = excluded.description",
+        )
+        .bind(file)
+        .bind(self.tags.join(","))
+        .bind(&self.description)
+        .execute(pool)
+        .await
+        .map(|_| ())
+    }
+
+    /// Borra los metadatos de un fichero, por ejemplo al borrar el quadlet
+    pub async fn delete(pool: &SqlitePool, file: &str) -> sqlx::Result<()> {
+        sqlx::query("DELETE FROM quadlet_meta WHERE file = ?")
+            .bind(file)
+            .execute(pool)
+            .await
+            .map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AppState;
+
+    fn meta(tags: &[&str], description: Option<&str>) -> QuadletMeta {
+        QuadletMeta {
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            description: description.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_normalized_sorts_and_dedups_tags() {
+        let normalized = meta(&[" prod", "monitoring", "prod "], Some("  "))
+            .normalized()
+            .unwrap();
+        assert_eq!(normalized, meta(&["monitoring", "prod"], None));
+        assert!(matches!(
+            meta(&["a,b"], None).normalized(),
+            Err(AppError::BadRequest(_))
+        ));
+        assert!(meta(&[""], None).normalized().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_save_read_and_delete() {
+        let pool = AppState::for_tests().await.pool;
+        let web = meta(&["monitoring", "prod"], Some("Web pública"));
+        web.save(&pool, "web.container").await.unwrap();
+        meta(&["dev"], None)
+            .save(&pool, "db.container")
+            .await
+            .unwrap();
+
+        let all = QuadletMeta::read_all(&pool).await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all["web.container"], web);
+        assert!(all["db.container"].has_tag("dev"));
+
+        // Guardar sin nada equivale a borrar
+        QuadletMeta::default()
+            .save(&pool, "db.container")
+            .await
+            .unwrap();
+        QuadletMeta::delete(&pool, "web.container").await.unwrap();
+        assert!(QuadletMeta::read_all(&pool).await.unwrap().is_empty());
+    }
+}
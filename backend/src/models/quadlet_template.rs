@@ -0,0 +1,18 @@
+use super::quadlet_type::QuadletType;
+use serde::Serialize;
+use ts_rs::TS;
+
+/// Plantilla de quadlet incluida en Quadly
+#[derive(Serialize, TS, Debug, Clone)]
+#[ts(export_to = "../../frontend/src/bindings/QuadletTemplate.ts")]
+pub struct QuadletTemplate {
+    /// Identificador de la plantilla, p. ej. "basic-container"
+    pub id: String,
+    /// Tipo de quadlet que genera
+    pub kind: QuadletType,
+    pub description: String,
+    /// Variables `{{...}}` que hay que rellenar
+    pub placeholders: Vec<String>,
+    /// Contenido con las marcas `{{...}}` sin sustituir
+    pub content: String,
+}
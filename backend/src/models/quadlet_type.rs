@@ -1,10 +1,13 @@
+use super::error::AppError;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use ts_rs::TS;
+use utoipa::ToSchema;
 
 /// Tipo de archivo Quadlet soportado
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, ToSchema)]
 #[serde(rename_all = "lowercase")]
-#[ts(export, export_to = "../../frontend/src/bindings/QuadletType.ts")]
+#[ts(export_to = "../../frontend/src/bindings/QuadletType.ts")]
 pub enum QuadletType {
     Container,
     Network,
@@ -12,26 +15,16 @@ pub enum QuadletType {
     Kube,
     Pod,
     Image,
+    /// Imagen construida a partir de un Containerfile (podman 5)
+    Build,
     Any,
 }
 
 impl QuadletType {
-    /// Intenta determinar el tipo de Quadlet desde una extensión de archivo
+    /// Intenta determinar el tipo de Quadlet desde una extensión de archivo.
+    /// No distingue mayúsculas y admite el punto inicial (`.container`)
     pub fn from_extension(ext: &str) -> Option<Self> {
-        match ext {
-            "container" => Some(QuadletType::Container),
-            "network" => Some(QuadletType::Network),
-            "pod" => Some(QuadletType::Pod),
-            "image" => Some(QuadletType::Image),
-            "volume" => Some(QuadletType::Volume),
-            "kube" => Some(QuadletType::Kube),
-            "any" => Some(QuadletType::Any),
-            _ => None,
-        }
-    }
-
-    pub fn allowed_extensions() -> Vec<&'static str> {
-        vec!["container", "network", "pod", "image", "volume", "kube", "any"]
+        ext.strip_prefix('.').unwrap_or(ext).parse().ok()
     }
 
     /// Devuelve una representación en string del tipo
@@ -41,9 +34,103 @@ impl QuadletType {
             QuadletType::Network => "network",
             QuadletType::Pod => "pod",
             QuadletType::Image => "image",
+            QuadletType::Build => "build",
             QuadletType::Volume => "volume",
             QuadletType::Kube => "kube",
             QuadletType::Any => "any",
         }
     }
+
+    /// Unidad que genera podman para el quadlet `name` de este tipo: `{name}.service`
+    /// para contenedores y kube y `{name}-{tipo}.service` para el resto, incluidas
+    /// las imágenes y los builds. `Any` no corresponde a ningún fichero, así que no tiene unidad
+    pub fn unit_name(&self, name: &str) -> Option<String> {
+        match self {
+            QuadletType::Container | QuadletType::Kube => Some(format!("{}.service", name)),
+            QuadletType::Any => None,
+            other => Some(format!("{}-{}.service", name, other.as_str())),
+        }
+    }
+}
+
+impl FromStr for QuadletType {
+    type Err = AppError;
+
+    /// Tipo a partir de su nombre (`container`, `Pod`, ...), sin distinguir mayúsculas
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "container" => Ok(QuadletType::Container),
+            "network" => Ok(QuadletType::Network),
+            "pod" => Ok(QuadletType::Pod),
+            "image" => Ok(QuadletType::Image),
+            "build" => Ok(QuadletType::Build),
+            "volume" => Ok(QuadletType::Volume),
+            "kube" => Ok(QuadletType::Kube),
+            "any" => Ok(QuadletType::Any),
+            _ => Err(AppError::bad_request(&format!(
+                "Invalid quadlet type: {}",
+                name
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unit_name_per_type() {
+        let cases = [
+            (QuadletType::Container, Some("web.service")),
+            (QuadletType::Kube, Some("web.service")),
+            (QuadletType::Pod, Some("web-pod.service")),
+            (QuadletType::Volume, Some("web-volume.service")),
+            (QuadletType::Network, Some("web-network.service")),
+            (QuadletType::Image, Some("web-image.service")),
+            (QuadletType::Build, Some("web-build.service")),
+            (QuadletType::Any, None),
+        ];
+        for (kind, expected) in cases {
+            assert_eq!(kind.unit_name("web").as_deref(), expected, "{:?}", kind);
+        }
+    }
+
+    #[test]
+    fn test_from_extension_is_lenient() {
+        for ext in ["CONTAINER", "container", ".container", "Container"] {
+            assert_eq!(
+                QuadletType::from_extension(ext),
+                Some(QuadletType::Container),
+                "{}",
+                ext
+            );
+        }
+        assert_eq!(QuadletType::from_extension("containers"), None);
+        assert_eq!(QuadletType::from_extension("..container"), None);
+    }
+
+    #[test]
+    fn test_build_round_trip() {
+        let kind = QuadletType::from_extension(".build").unwrap();
+        assert_eq!(kind, QuadletType::Build);
+        assert_eq!(kind.as_str(), "build");
+        assert_eq!(kind.as_str().parse::<QuadletType>().unwrap(), kind);
+        let json = serde_json::to_string(&kind).unwrap();
+        assert_eq!(json, "\"build\"");
+        assert_eq!(serde_json::from_str::<QuadletType>(&json).unwrap(), kind);
+    }
+
+    #[test]
+    fn test_from_str_bare_name() {
+        assert_eq!(
+            "Volume".parse::<QuadletType>().unwrap(),
+            QuadletType::Volume
+        );
+        assert!(matches!(
+            ".volume".parse::<QuadletType>(),
+            Err(AppError::BadRequest(_))
+        ));
+        assert!("volumes".parse::<QuadletType>().is_err());
+    }
 }
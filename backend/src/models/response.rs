@@ -5,6 +5,8 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
 
 #[derive(Debug, Clone)]
 pub enum CustomResponse<T> {
@@ -12,7 +14,6 @@ pub enum CustomResponse<T> {
     Empty(EmptyResponse),
 }
 
-
 impl<T> CustomResponse<T>
 where
     T: Serialize,
@@ -26,13 +27,27 @@ where
     pub fn api(status: StatusCode, message: &str, data: T) -> Self {
         CustomResponse::Api(ApiResponse::new(status, message, data))
     }
+    /// Respuesta con una página de un listado y el total de elementos sin paginar
+    pub fn paginated(status: StatusCode, message: &str, data: T, total: usize) -> Self {
+        let mut response = ApiResponse::new(status, message, data);
+        response.total = Some(total);
+        CustomResponse::Api(response)
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-struct ApiResponse<T> {
+/// Envoltorio de las respuestas con datos. En OpenAPI aparece como `CustomResponse_<T>`
+/// y en TypeScript como `ApiResponse<T>`. Las respuestas vacías son texto plano
+#[derive(Debug, Serialize, Deserialize, Clone, ToSchema, TS)]
+#[schema(as = CustomResponse)]
+#[ts(export_to = "../../frontend/src/bindings/ApiResponse.ts")]
+pub(crate) struct ApiResponse<T> {
     pub status: u16,
     pub message: String,
     pub data: T,
+    /// Total de elementos sin paginar, solo en los listados paginados
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub total: Option<usize>,
 }
 
 impl<T> ApiResponse<T>
@@ -44,6 +59,7 @@ where
             status: status.as_u16(),
             message: message.to_string(),
             data,
+            total: None,
         }
     }
 }
@@ -89,7 +105,7 @@ where
 }
 
 #[derive(Debug, Clone)]
-struct EmptyResponse {
+pub struct EmptyResponse {
     pub status: StatusCode,
     pub message: String,
 }
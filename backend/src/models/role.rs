@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// Rol de un usuario. Se guarda en la base de datos y en el token en minúsculas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, ToSchema, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(rename_all = "lowercase")]
+#[ts(export_to = "../../frontend/src/bindings/Role.ts")]
+pub enum Role {
+    /// Puede modificar quadlets, controlar unidades y gestionar usuarios
+    Admin,
+    /// Solo lectura
+    Viewer,
+}
+
+impl Role {
+    pub fn is_admin(&self) -> bool {
+        match self {
+            Role::Admin => true,
+            Role::Viewer => false,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Viewer => "viewer",
+        }
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "admin" => Ok(Role::Admin),
+            "viewer" => Ok(Role::Viewer),
+            other => Err(format!("Invalid role '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_role_round_trip() {
+        for role in [Role::Admin, Role::Viewer] {
+            assert_eq!(role.to_string().parse::<Role>().unwrap(), role);
+            let json = serde_json::to_string(&role).unwrap();
+            assert_eq!(json, format!("\"{}\"", role));
+            assert_eq!(serde_json::from_str::<Role>(&json).unwrap(), role);
+        }
+        assert!("superuser".parse::<Role>().is_err());
+        assert!(serde_json::from_str::<Role>("\"superuser\"").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_role_is_stored_as_text() {
+        let pool = sqlx::SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let stored: String = sqlx::query_scalar("SELECT ?")
+            .bind(Role::Viewer)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, "viewer");
+        let role: Role = sqlx::query_scalar("SELECT 'admin'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(role, Role::Admin);
+    }
+}
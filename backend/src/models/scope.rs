@@ -0,0 +1,115 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::PathBuf, str::FromStr};
+use tracing::warn;
+use ts_rs::TS;
+
+/// Ámbito de systemd sobre el que trabaja Quadly
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export_to = "../../frontend/src/bindings/SystemdScope.ts")]
+pub enum SystemdScope {
+    /// Quadlets rootless en `~/.config/containers/systemd` (bus de sesión)
+    #[default]
+    User,
+    /// Quadlets del sistema en `/etc/containers/systemd` (bus del sistema)
+    System,
+}
+
+impl SystemdScope {
+    /// Lee el ámbito de la variable de entorno `QUADLY_SCOPE` (por defecto `user`)
+    pub fn from_env() -> Self {
+        match std::env::var("QUADLY_SCOPE") {
+            Ok(value) => value.parse().unwrap_or_else(|e| {
+                warn!("{}, using user scope", e);
+                SystemdScope::User
+            }),
+            Err(_) => SystemdScope::User,
+        }
+    }
+
+    /// Directorio donde se guardan los quadlets de este ámbito
+    pub fn quadlet_dir(&self) -> PathBuf {
+        match self {
+            SystemdScope::User => {
+                // Para modo --user: ~/.config/containers/systemd/
+                let home = std::env::var("HOME").expect("No se pudo encontrar la variable HOME");
+                PathBuf::from(home).join(".config/containers/systemd")
+            }
+            SystemdScope::System => PathBuf::from("/etc/containers/systemd"),
+        }
+    }
+
+    /// Directorio de unidades del administrador, donde van los drop-ins `{unidad}.d/`
+    pub fn unit_dir(&self) -> PathBuf {
+        match self {
+            SystemdScope::User => {
+                let home = std::env::var("HOME").expect("No se pudo encontrar la variable HOME");
+                PathBuf::from(home).join(".config/systemd/user")
+            }
+            SystemdScope::System => PathBuf::from("/etc/systemd/system"),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SystemdScope::User => "user",
+            SystemdScope::System => "system",
+        }
+    }
+}
+
+impl FromStr for SystemdScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "user" => Ok(SystemdScope::User),
+            "system" => Ok(SystemdScope::System),
+            other => Err(format!("Invalid systemd scope '{}'", other)),
+        }
+    }
+}
+
+impl fmt::Display for SystemdScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quadlet_dir_per_scope() {
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(
+            SystemdScope::User.quadlet_dir(),
+            PathBuf::from(home).join(".config/containers/systemd")
+        );
+        assert_eq!(
+            SystemdScope::System.quadlet_dir(),
+            PathBuf::from("/etc/containers/systemd")
+        );
+    }
+
+    #[test]
+    fn test_unit_dir_per_scope() {
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(
+            SystemdScope::User.unit_dir(),
+            PathBuf::from(home).join(".config/systemd/user")
+        );
+        assert_eq!(
+            SystemdScope::System.unit_dir(),
+            PathBuf::from("/etc/systemd/system")
+        );
+    }
+
+    #[test]
+    fn test_parse_scope() {
+        assert_eq!("system".parse(), Ok(SystemdScope::System));
+        assert_eq!(" User ".parse(), Ok(SystemdScope::User));
+        assert!("root".parse::<SystemdScope>().is_err());
+    }
+}
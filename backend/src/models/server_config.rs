@@ -0,0 +1,40 @@
+use super::{get_quadlet_dir, AppState, SystemdScope};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Funcionalidades opcionales que el frontend puede mostrar u ocultar
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export_to = "../../frontend/src/bindings/ServerFeatures.ts")]
+pub struct ServerFeatures {
+    /// Eventos de estado en vivo por Server-Sent Events
+    pub sse: bool,
+    /// Seguimiento de logs en vivo por WebSocket (requiere journalctl)
+    pub log_stream: bool,
+    /// El servidor trabaja con el bus del sistema en lugar del de sesión
+    pub system_bus: bool,
+}
+
+/// Capacidades del servidor expuestas al frontend
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export_to = "../../frontend/src/bindings/ServerConfig.ts")]
+pub struct ServerConfig {
+    pub scope: SystemdScope,
+    pub quadlet_dir: String,
+    pub features: ServerFeatures,
+    pub version: String,
+}
+
+impl ServerConfig {
+    pub fn from_state(app_state: &AppState, log_stream: bool) -> Self {
+        Self {
+            scope: app_state.scope,
+            quadlet_dir: get_quadlet_dir().display().to_string(),
+            features: ServerFeatures {
+                sse: true,
+                log_stream,
+                system_bus: app_state.scope == SystemdScope::System,
+            },
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
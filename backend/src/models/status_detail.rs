@@ -0,0 +1,53 @@
+use super::QuadletStatus;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// Estado de la unidad de un quadlet junto con cómo terminó su proceso principal
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+#[ts(export_to = "../../frontend/src/bindings/StatusDetail.ts")]
+pub struct StatusDetail {
+    pub status: QuadletStatus,
+    /// Código de salida del proceso principal (`ExecMainStatus`)
+    pub exit_code: Option<i32>,
+    /// Motivo del último fin de la unidad según systemd: `success`, `exit-code`,
+    /// `oom-kill`, `timeout`...
+    pub result: Option<String>,
+}
+
+impl StatusDetail {
+    /// Detalle sin datos del proceso, para unidades sin servicio o sin bus
+    pub fn without_exit(status: QuadletStatus) -> Self {
+        Self {
+            status,
+            exit_code: None,
+            result: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_shape() {
+        let detail = StatusDetail {
+            status: QuadletStatus::Failed,
+            exit_code: Some(137),
+            result: Some("oom-kill".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_value(&detail).unwrap(),
+            serde_json::json!({
+                "status": "Failed",
+                "exit_code": 137,
+                "result": "oom-kill"
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(StatusDetail::without_exit(QuadletStatus::Unknown)).unwrap(),
+            serde_json::json!({"status": "Unknown", "exit_code": null, "result": null})
+        );
+    }
+}
@@ -1,9 +1,33 @@
+use jsonwebtoken::{decode, DecodingKey, Validation};
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize)]
+use super::error::AppError;
+use super::role::Role;
+
+/// Contenido del JWT de sesión. `iat` y `exp` son segundos desde epoch
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export_to = "../../frontend/src/bindings/TokenClaims.ts")]
 pub struct TokenClaims {
     pub sub: String,
-    pub role: String,
+    pub role: Role,
     pub iat: usize,
     pub exp: usize,
 }
+
+impl TokenClaims {
+    /// Valida un JWT y devuelve sus claims
+    pub fn decode(token: &str, secret: &str) -> Result<Self, AppError> {
+        decode::<Self>(
+            token,
+            &DecodingKey::from_secret(secret.as_bytes()),
+            &Validation::default(),
+        )
+        .map(|data| data.claims)
+        .map_err(|_| AppError::Unauthorized)
+    }
+
+    pub fn is_admin(&self) -> bool {
+        self.role.is_admin()
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// Acción de control sobre la unidad systemd de un quadlet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+#[ts(export_to = "../../frontend/src/bindings/UnitAction.ts")]
+pub enum UnitAction {
+    Start,
+    Stop,
+    Restart,
+    /// Reinicia la unidad solo si está activa; si está parada no la arranca
+    RestartIfActive,
+    DaemonReload,
+    ResetFailed,
+    Enable,
+    Disable,
+    /// Arranca la unidad si está parada o ha fallado y la para si está activa
+    Toggle,
+}
+
+impl UnitAction {
+    #[cfg(test)]
+    pub const ALL: [UnitAction; 9] = [
+        UnitAction::Start,
+        UnitAction::Stop,
+        UnitAction::Restart,
+        UnitAction::RestartIfActive,
+        UnitAction::DaemonReload,
+        UnitAction::ResetFailed,
+        UnitAction::Enable,
+        UnitAction::Disable,
+        UnitAction::Toggle,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnitAction::Start => "start",
+            UnitAction::Stop => "stop",
+            UnitAction::Restart => "restart",
+            UnitAction::RestartIfActive => "restart-if-active",
+            UnitAction::DaemonReload => "daemon-reload",
+            UnitAction::ResetFailed => "reset-failed",
+            UnitAction::Enable => "enable",
+            UnitAction::Disable => "disable",
+            UnitAction::Toggle => "toggle",
+        }
+    }
+}
+
+impl fmt::Display for UnitAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_actions_use_kebab_case() {
+        for action in UnitAction::ALL {
+            let json = serde_json::to_string(&action).unwrap();
+            assert_eq!(json, format!("\"{}\"", action.as_str()));
+            assert_eq!(serde_json::from_str::<UnitAction>(&json).unwrap(), action);
+        }
+    }
+
+    #[test]
+    fn test_unknown_action_is_rejected() {
+        let error = serde_json::from_str::<UnitAction>("\"explode\"").unwrap_err();
+        assert!(error.to_string().contains("unknown variant `explode`"));
+    }
+}
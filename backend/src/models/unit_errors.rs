@@ -0,0 +1,20 @@
+use super::QuadletType;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Errores recientes del journal de la unidad de un quadlet
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export_to = "../../frontend/src/bindings/UnitErrors.ts")]
+pub struct UnitErrors {
+    /// Unidad systemd (`web.service`)
+    pub unit: String,
+    /// Quadlet que genera la unidad
+    pub name: String,
+    pub kind: QuadletType,
+    /// Entradas con prioridad de error o más grave entre las leídas
+    pub count: usize,
+    pub last_message: String,
+    #[ts(type = "string")]
+    pub last_timestamp: DateTime<Utc>,
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Consumo de recursos de la unidad systemd de un quadlet.
+/// Los valores son `None` si la unidad no está activa o systemd no lleva la cuenta
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export_to = "../../frontend/src/bindings/UnitResources.ts")]
+pub struct UnitResources {
+    /// La unidad está activa
+    pub active: bool,
+    /// Memoria en uso, en bytes
+    #[ts(type = "number | null")]
+    pub memory_bytes: Option<u64>,
+    /// Tiempo de CPU consumido, en nanosegundos
+    #[ts(type = "number | null")]
+    pub cpu_usage_nsec: Option<u64>,
+    /// Número de tareas (procesos e hilos)
+    #[ts(type = "number | null")]
+    pub tasks: Option<u64>,
+}
+
+impl UnitResources {
+    /// systemd devuelve `u64::MAX` cuando no lleva la cuenta de un recurso
+    pub fn accounted(value: u64) -> Option<u64> {
+        (value != u64::MAX).then_some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_shape() {
+        let resources = UnitResources {
+            active: true,
+            memory_bytes: Some(1024),
+            cpu_usage_nsec: Some(5_000_000),
+            tasks: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&resources).unwrap(),
+            serde_json::json!({
+                "active": true,
+                "memory_bytes": 1024,
+                "cpu_usage_nsec": 5_000_000,
+                "tasks": null
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(UnitResources::default()).unwrap(),
+            serde_json::json!({
+                "active": false,
+                "memory_bytes": null,
+                "cpu_usage_nsec": null,
+                "tasks": null
+            })
+        );
+    }
+
+    #[test]
+    fn test_unaccounted_values() {
+        assert_eq!(UnitResources::accounted(u64::MAX), None);
+        assert_eq!(UnitResources::accounted(42), Some(42));
+    }
+}
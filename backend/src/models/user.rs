@@ -1,32 +1,74 @@
+use super::role::Role;
+use super::AppError;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::fmt;
+use ts_rs::TS;
+use utoipa::ToSchema;
 
 #[derive(Debug, FromRow, Serialize, Deserialize)]
 pub struct User {
     pub id: i32,
     pub username: String,
+    /// Nunca se envía al cliente
+    #[serde(skip_serializing)]
+    #[sqlx(rename = "password_hash")]
     pub hashed_password: String,
-    pub role: String, // "admin" o "viewer"
+    pub role: Role,
 }
 
-#[derive(Debug, FromRow, Serialize, Deserialize)]
+/// Coste de bcrypt para las contraseñas nuevas. En los tests se baja al mínimo
+#[cfg(not(test))]
+const HASH_COST: u32 = bcrypt::DEFAULT_COST;
+#[cfg(test)]
+const HASH_COST: u32 = 4;
+
+// La contraseña de `NewUser` y `UserPass` nunca debe acabar en los logs:
+// su `Debug` la sustituye por "***"
+/// Usuario a crear. `password` va en claro: el servidor guarda solo su hash
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct NewUser {
     pub username: String,
-    pub hashed_password: String,
-    pub role: String, // "admin" o "viewer"
+    pub password: String,
+    pub role: Role,
 }
 
-#[derive(Debug, FromRow, Serialize, Deserialize)]
+impl fmt::Debug for NewUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NewUser")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .field("role", &self.role)
+            .finish()
+    }
+}
+
+/// Credenciales enviadas en el login. `password` va en claro: el hash solo se guarda en `User`
+#[derive(FromRow, Serialize, Deserialize, TS, ToSchema)]
+#[ts(export_to = "../../frontend/src/bindings/UserPass.ts")]
 pub struct UserPass {
     pub username: String,
-    pub hashed_password: String,
+    pub password: String,
 }
 
-impl User {
-    pub fn is_admin(&self) -> bool {
-        self.role == "admin"
+impl fmt::Debug for UserPass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UserPass")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
     }
+}
 
+/// Identidad del usuario autenticado, sacada de su token
+#[derive(Debug, Serialize, Deserialize, TS, ToSchema)]
+#[ts(export_to = "../../frontend/src/bindings/CurrentUser.ts")]
+pub struct CurrentUser {
+    pub username: String,
+    pub role: Role,
+}
+
+impl User {
     pub async fn read_by_username(
         pool: &sqlx::SqlitePool,
         username: &str,
@@ -37,19 +79,116 @@ impl User {
             .await
     }
 
-    pub async fn read_all(pool: &sqlx::SqlitePool) -> sqlx::Result<Vec<Self>> {
-        sqlx::query_as::<_, Self>("SELECT * FROM users")
+    /// Una página de usuarios ordenados por id y el total de usuarios
+    pub async fn read_page(
+        pool: &sqlx::SqlitePool,
+        limit: i64,
+        offset: i64,
+    ) -> sqlx::Result<(Vec<Self>, i64)> {
+        let users = sqlx::query_as::<_, Self>("SELECT * FROM users ORDER BY id LIMIT ? OFFSET ?")
+            .bind(limit)
+            .bind(offset)
             .fetch_all(pool)
-            .await
+            .await?;
+        let total = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(pool)
+            .await?;
+        Ok((users, total))
     }
 
-    pub async fn create(pool: &sqlx::SqlitePool, new_user: NewUser) -> Result<Self, sqlx::Error>{
-        let sql = "INSERT INTO users (username, hashed_password, role) VALUES (?, ?, ?) RETURNING *";
-        sqlx::query_as::<_, Self>(sql)
-            .bind(&new_user.username)
-            .bind(&new_user.hashed_password)
-            .bind(&new_user.role)
-            .fetch_one(pool)
+    /// Crea el usuario con el hash bcrypt de su contraseña. El hash se calcula
+    /// aparte para no bloquear el runtime
+    pub async fn create(pool: &sqlx::SqlitePool, new_user: NewUser) -> Result<Self, AppError> {
+        let NewUser {
+            username,
+            password,
+            role,
+        } = new_user;
+        if password.is_empty() {
+            return Err(AppError::bad_request("La contraseña no puede estar vacía"));
+        }
+        let password_hash = tokio::task::spawn_blocking(move || bcrypt::hash(password, HASH_COST))
             .await
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?
+            .map_err(|e| AppError::InternalServerError(format!("Error hashing password: {}", e)))?;
+        let sql = "INSERT INTO users (username, password_hash, role) VALUES (?, ?, ?) RETURNING *";
+        let user = sqlx::query_as::<_, Self>(sql)
+            .bind(&username)
+            .bind(&password_hash)
+            .bind(role)
+            .fetch_one(pool)
+            .await?;
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_pass_debug_redacts_password() {
+        let user_pass = UserPass {
+            username: "alice".to_string(),
+            password: "s3cr3t".to_string(),
+        };
+        let output = format!("{:?}", user_pass);
+        assert!(output.contains("alice"));
+        assert!(!output.contains("s3cr3t"));
+    }
+
+    #[test]
+    fn test_user_json_has_no_password_hash() {
+        let user = User {
+            id: 1,
+            username: "alice".to_string(),
+            hashed_password: "$2b$04$hash".to_string(),
+            role: Role::Admin,
+        };
+        assert_eq!(
+            serde_json::to_value(&user).unwrap(),
+            serde_json::json!({"id": 1, "username": "alice", "role": "admin"})
+        );
+    }
+
+    #[test]
+    fn test_new_user_debug_redacts_password() {
+        let new_user = NewUser {
+            username: "bob".to_string(),
+            password: "hunter2".to_string(),
+            role: Role::Viewer,
+        };
+        let output = format!("{:?}", new_user);
+        assert_eq!(
+            output,
+            r#"NewUser { username: "bob", password: "***", role: Viewer }"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_stores_only_the_hash() {
+        let pool = crate::models::AppState::for_tests().await.pool;
+        let user = User::create(
+            &pool,
+            NewUser {
+                username: "carol".to_string(),
+                password: "s3cr3t".to_string(),
+                role: Role::Viewer,
+            },
+        )
+        .await
+        .unwrap();
+        assert_ne!(user.hashed_password, "s3cr3t");
+        assert!(bcrypt::verify("s3cr3t", &user.hashed_password).unwrap());
+
+        let empty = NewUser {
+            username: "dave".to_string(),
+            password: String::new(),
+            role: Role::Viewer,
+        };
+        assert!(matches!(
+            User::create(&pool, empty).await,
+            Err(AppError::BadRequest(_))
+        ));
     }
 }
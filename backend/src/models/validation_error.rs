@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use utoipa::ToSchema;
+
+/// Problema encontrado al validar un quadlet. Según el caso se devuelve como
+/// error o como aviso junto al quadlet guardado
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, ToSchema)]
+#[ts(export_to = "../../frontend/src/bindings/ValidationError.ts")]
+pub struct ValidationError {
+    pub field: String,
+    pub message: String,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Versiones de Quadly y de las herramientas de las que depende. `None` si la
+/// herramienta no está instalada o no se pudo consultar
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export_to = "../../frontend/src/bindings/Versions.ts")]
+pub struct Versions {
+    pub quadly: String,
+    pub podman: Option<String>,
+    pub systemd: Option<String>,
+}
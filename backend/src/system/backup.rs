@@ -0,0 +1,298 @@
+use super::systemd::split_quadlet_file_name;
+use crate::models::{validate_quadlet_name, write_quadlet_file, AppError};
+use axum::body::Bytes;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use futures_util::Stream;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Tamaño máximo de cada fichero de una copia que se restaura
+const MAX_ENTRY_BYTES: u64 = 1024 * 1024;
+
+/// Tamaño máximo del contenido descomprimido de una copia, contando también las
+/// entradas que se omiten: un `.tar.gz` pequeño puede esconder gigas de ceros
+const MAX_ARCHIVE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Trozos del archivo en vuelo entre el hilo que comprime y la respuesta HTTP
+const ARCHIVE_CHANNEL_SIZE: usize = 16;
+
+/// `Write` que reenvía cada bloque escrito por un canal. Si el receptor se ha
+/// cerrado (el cliente se desconectó) la escritura falla y se deja de comprimir
+struct ChannelWriter(mpsc::Sender<std::io::Result<Bytes>>);
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .blocking_send(Ok(Bytes::copy_from_slice(buf)))
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Escribe en `writer` un `.tar.gz` con los ficheros de `dir`. Los ocultos (como
+/// los temporales de un guardado a medias) se omiten
+fn write_archive<W: Write>(dir: &Path, writer: W) -> std::io::Result<W> {
+    let mut builder = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+    if dir.exists() {
+        let mut entries = std::fs::read_dir(dir)?.collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let file_name = entry.file_name();
+            if file_name.to_string_lossy().starts_with('.') || !entry.file_type()?.is_file() {
+                continue;
+            }
+            builder.append_path_with_name(entry.path(), &file_name)?;
+        }
+    }
+    builder.into_inner()?.finish()
+}
+
+/// Empaqueta el directorio de quadlets en un `.tar.gz` que se va enviando según se
+/// comprime, sin tener el archivo entero en memoria
+pub fn export_archive(dir: PathBuf) -> impl Stream<Item = std::io::Result<Bytes>> {
+    let (tx, rx) = mpsc::channel(ARCHIVE_CHANNEL_SIZE);
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = write_archive(&dir, ChannelWriter(tx.clone())) {
+            let _ = tx.blocking_send(Err(e));
+        }
+    });
+    ReceiverStream::new(rx)
+}
+
+/// Nombre de fichero de una entrada del archivo. Las rutas absolutas o con `..`
+/// se rechazan para que nada se escriba fuera del directorio de quadlets; las
+/// que están en subdirectorios se ignoran (`None`)
+fn entry_file_name(path: &Path) -> Result<Option<String>, AppError> {
+    let mut names = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => names.push(name),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(AppError::BadRequest(format!(
+                    "Ruta no permitida en el archivo: '{}'",
+                    path.display()
+                )));
+            }
+        }
+    }
+    match names.as_slice() {
+        [name] => Ok(name.to_str().map(str::to_string)),
+        _ => Ok(None),
+    }
+}
+
+/// Quadlets contenidos en un `.tar.gz`, como pares (fichero, contenido). Se leen
+/// todos antes de escribir nada, así un archivo con una sola ruta maliciosa no
+/// deja una restauración a medias. Un fichero de más de `max_entry` bytes o un
+/// archivo de más de `max_total` bytes descomprimido se rechazan
+fn read_archive(
+    archive: &[u8],
+    max_entry: u64,
+    max_total: u64,
+) -> Result<Vec<(String, Vec<u8>)>, AppError> {
+    let invalid = |e: std::io::Error| AppError::BadRequest(format!("Archivo no válido: {}", e));
+    let mut archive = tar::Archive::new(GzDecoder::new(archive));
+    let mut files = Vec::new();
+    let mut total: u64 = 0;
+    for entry in archive.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        let path = entry.path().map_err(invalid)?.into_owned();
+        // El tamaño de la cabecera es lo que se va a descomprimir, se lea o no
+        let size = entry.size();
+        if size > max_entry {
+            return Err(AppError::PayloadTooLarge(format!(
+                "'{}' ocupa {} bytes, el máximo es {}",
+                path.display(),
+                size,
+                max_entry
+            )));
+        }
+        total = total.saturating_add(size);
+        if total > max_total {
+            return Err(AppError::PayloadTooLarge(format!(
+                "El archivo descomprimido supera los {} bytes",
+                max_total
+            )));
+        }
+        let Some(file_name) = entry_file_name(&path)? else {
+            continue;
+        };
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let Some((name, _)) = split_quadlet_file_name(&file_name) else {
+            continue;
+        };
+        validate_quadlet_name(&name)?;
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).map_err(invalid)?;
+        files.push((file_name, content));
+    }
+    Ok(files)
+}
+
+/// Restaura en `dir` los quadlets de un `.tar.gz` generado por `export_archive` y
+/// devuelve los ficheros escritos. Se escriben igual que al guardar, con los
+/// permisos de los quadlets. Sin `overwrite` los que ya existen se dejan como están
+pub async fn import_archive(
+    archive: Bytes,
+    dir: PathBuf,
+    overwrite: bool,
+) -> Result<Vec<String>, AppError> {
+    let files = tokio::task::spawn_blocking(move || {
+        read_archive(&archive, MAX_ENTRY_BYTES, MAX_ARCHIVE_BYTES)
+    })
+    .await
+    .map_err(|e| AppError::InternalServerError(e.to_string()))??;
+    let mut imported = Vec::new();
+    for (file_name, content) in files {
+        let path = dir.join(&file_name);
+        if !overwrite && tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            continue;
+        }
+        write_quadlet_file(&path, content)
+            .await
+            .map_err(|e| AppError::from_io(e, &file_name))?;
+        imported.push(file_name);
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::TryStreamExt;
+
+    #[tokio::test]
+    async fn test_export_contains_quadlets() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("web.container"),
+            "[Container]\nImage=nginx\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("data.volume"), "[Volume]\n").unwrap();
+        std::fs::write(dir.path().join(".web.container.tmp"), "a medias").unwrap();
+
+        let chunks: Vec<Bytes> = export_archive(dir.path().to_path_buf())
+            .try_collect()
+            .await
+            .unwrap();
+        let archive = chunks.concat();
+
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(archive.as_slice()));
+        let mut files = Vec::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            files.push((entry.path().unwrap().display().to_string(), content));
+        }
+        assert_eq!(
+            files,
+            vec![
+                ("data.volume".to_string(), "[Volume]\n".to_string()),
+                (
+                    "web.container".to_string(),
+                    "[Container]\nImage=nginx\n".to_string()
+                ),
+            ]
+        );
+    }
+
+    /// Construye un `.tar.gz` escribiendo las rutas tal cual, sin las comprobaciones
+    /// de `tar::Builder`, para poder simular archivos maliciosos
+    fn raw_archive(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (path, content) in files {
+            let mut header = tar::Header::new_old();
+            header.as_old_mut().name[..path.len()].copy_from_slice(path.as_bytes());
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_cksum();
+            builder.append(&header, content.as_bytes()).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_import_rejects_tar_slip() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("quadlets");
+        for path in ["../evil.container", "/tmp/evil.container"] {
+            let archive =
+                raw_archive(&[("ok.container", "[Container]\n"), (path, "[Container]\n")]);
+            let result = import_archive(archive.into(), target.clone(), true).await;
+            assert!(matches!(result, Err(AppError::BadRequest(_))), "{}", path);
+        }
+        // No se escribe nada, ni siquiera las entradas válidas
+        assert!(!target.join("ok.container").exists());
+        assert!(!dir.path().join("evil.container").exists());
+    }
+
+    #[test]
+    fn test_read_archive_size_limits() {
+        let archive = raw_archive(&[
+            ("web.container", "[Container]\n"),
+            ("notes.txt", "0123456789"),
+        ]);
+        assert_eq!(read_archive(&archive, 16, 32).unwrap().len(), 1);
+
+        // Un fichero grande, aunque no sea un quadlet
+        let result = read_archive(&archive, 8, 32);
+        assert!(matches!(result, Err(AppError::PayloadTooLarge(_))));
+        // Entre todos superan el total
+        let result = read_archive(&archive, 16, 16);
+        assert!(matches!(result, Err(AppError::PayloadTooLarge(_))));
+    }
+
+    #[tokio::test]
+    async fn test_import_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("web.container"), "original").unwrap();
+        let archive = raw_archive(&[
+            ("./web.container", "restaurado"),
+            ("data.volume", "[Volume]\n"),
+            ("notes.txt", "no es un quadlet"),
+        ]);
+
+        let imported = import_archive(archive.clone().into(), dir.path().to_path_buf(), false)
+            .await
+            .unwrap();
+        assert_eq!(imported, vec!["data.volume"]);
+        let web = std::fs::read_to_string(dir.path().join("web.container")).unwrap();
+        assert_eq!(web, "original");
+        assert!(!dir.path().join("notes.txt").exists());
+
+        let imported = import_archive(archive.into(), dir.path().to_path_buf(), true)
+            .await
+            .unwrap();
+        assert_eq!(imported, vec!["web.container", "data.volume"]);
+        let web = std::fs::read_to_string(dir.path().join("web.container")).unwrap();
+        assert_eq!(web, "restaurado");
+    }
+
+    #[tokio::test]
+    async fn test_import_uses_quadlet_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("containers/systemd");
+        let archive = raw_archive(&[("web.container", "[Container]\nImage=nginx\n")]);
+        import_archive(archive.into(), target.clone(), false)
+            .await
+            .unwrap();
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        // Sin QUADLET_FILE_MODE solo los lee su dueño
+        assert_eq!(mode(&target.join("web.container")), 0o600);
+        assert_eq!(mode(&target), 0o700);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::models::SystemdScope;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use zbus::Connection;
+
+/// Abre una conexión al bus de D-Bus del ámbito indicado
+pub async fn connect(scope: SystemdScope) -> zbus::Result<Connection> {
+    match scope {
+        // Bus de sesión (rootless)
+        SystemdScope::User => Connection::session().await,
+        SystemdScope::System => Connection::system().await,
+    }
+}
+
+/// Conexión al bus de systemd compartida por toda la aplicación.
+/// Se abre la primera vez que se necesita y se vuelve a abrir si se ha cerrado
+#[derive(Clone)]
+pub struct SystemdBus {
+    scope: SystemdScope,
+    cached: Arc<Mutex<Option<Connection>>>,
+}
+
+impl SystemdBus {
+    pub fn new(scope: SystemdScope) -> Self {
+        Self {
+            scope,
+            cached: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Devuelve la conexión compartida, abriéndola si todavía no existe o se ha caído
+    pub async fn connection(&self) -> zbus::Result<Connection> {
+        reuse_or_open(&self.cached, Connection::is_closed, || connect(self.scope)).await
+    }
+}
+
+/// Devuelve el valor guardado en `cache` si sigue abierto; si no, lo abre con `open`
+/// y lo guarda. El cerrojo se mantiene mientras se abre para no conectar dos veces
+async fn reuse_or_open<C, E, F, Fut>(
+    cache: &Mutex<Option<C>>,
+    is_closed: impl Fn(&C) -> bool,
+    open: F,
+) -> Result<C, E>
+where
+    C: Clone,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<C, E>>,
+{
+    let mut cached = cache.lock().await;
+    if let Some(conn) = cached.as_ref().filter(|conn| !is_closed(conn)) {
+        return Ok(conn.clone());
+    }
+    let conn = open().await?;
+    *cached = Some(conn.clone());
+    Ok(conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    /// Conexión falsa identificada por el número de apertura
+    #[derive(Clone)]
+    struct FakeConnection {
+        id: usize,
+        closed: Arc<AtomicBool>,
+    }
+
+    fn is_closed(conn: &FakeConnection) -> bool {
+        conn.closed.load(Ordering::SeqCst)
+    }
+
+    async fn open(opened: &AtomicUsize) -> Result<FakeConnection, String> {
+        Ok(FakeConnection {
+            id: opened.fetch_add(1, Ordering::SeqCst),
+            closed: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_sequential_calls_reuse_connection() {
+        let cache = Mutex::new(None);
+        let opened = AtomicUsize::new(0);
+
+        let first = reuse_or_open(&cache, is_closed, || open(&opened))
+            .await
+            .unwrap();
+        let second = reuse_or_open(&cache, is_closed, || open(&opened))
+            .await
+            .unwrap();
+
+        assert_eq!(first.id, second.id);
+        assert_eq!(opened.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_closed_connection_is_reopened() {
+        let cache = Mutex::new(None);
+        let opened = AtomicUsize::new(0);
+
+        let first = reuse_or_open(&cache, is_closed, || open(&opened))
+            .await
+            .unwrap();
+        first.closed.store(true, Ordering::SeqCst);
+        let second = reuse_or_open(&cache, is_closed, || open(&opened))
+            .await
+            .unwrap();
+
+        assert_ne!(first.id, second.id);
+        assert_eq!(opened.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_open_is_not_cached() {
+        let cache = Mutex::new(None);
+        let opened = AtomicUsize::new(0);
+
+        let failed: Result<FakeConnection, String> =
+            reuse_or_open(&cache, is_closed, || async { Err("sin bus".to_string()) }).await;
+        assert!(failed.is_err());
+
+        let conn = reuse_or_open(&cache, is_closed, || open(&opened))
+            .await
+            .unwrap();
+        assert_eq!(conn.id, 0);
+    }
+}
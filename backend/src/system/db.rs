@@ -1,7 +1,64 @@
-use anyhow::Result;
+use crate::models::Role;
+use anyhow::{anyhow, Context, Result};
+use sqlx::migrate::Migrator;
 use sqlx::SqlitePool;
+use std::path::{Path, PathBuf};
+
+/// Directorio de las migraciones. En producción (`RUST_ENV=production`) van junto al
+/// ejecutable; en desarrollo, en el crate
+pub fn migrations_dir() -> Result<PathBuf> {
+    resolve_migrations_dir(|name| std::env::var(name).ok(), std::env::current_exe)
+}
+
+/// `env` lee las variables y `current_exe` solo se consulta en producción. Fuera de
+/// `cargo run` no hay `CARGO_MANIFEST_DIR`, así que se usa el del momento de compilar
+fn resolve_migrations_dir<F, E>(env: F, current_exe: E) -> Result<PathBuf>
+where
+    F: Fn(&str) -> Option<String>,
+    E: FnOnce() -> std::io::Result<PathBuf>,
+{
+    if env("RUST_ENV").as_deref() == Some("production") {
+        let exe = current_exe().context("No se pudo localizar el ejecutable")?;
+        let dir = exe
+            .parent()
+            .ok_or_else(|| anyhow!("El ejecutable {} no tiene directorio", exe.display()))?;
+        return Ok(dir.join("migrations"));
+    }
+    let crate_dir =
+        env("CARGO_MANIFEST_DIR").unwrap_or_else(|| env!("CARGO_MANIFEST_DIR").to_string());
+    Ok(Path::new(&crate_dir).join("migrations"))
+}
+
+/// Aplica las migraciones de `dir`. El error indica el directorio usado y, si no
+/// existe, dónde se esperaba
+pub async fn run_migrations(pool: &SqlitePool, dir: &Path) -> Result<()> {
+    let result = async { Migrator::new(dir).await?.run(pool).await }.await;
+    result.with_context(|| migrations_context(dir, dir.exists()))
+}
+
+fn migrations_context(dir: &Path, exists: bool) -> String {
+    let mut context = format!("Migraciones de {}", dir.display());
+    if !exists {
+        context.push_str(
+            ": el directorio no existe. Con RUST_ENV=production se buscan en `migrations` \
+             junto al ejecutable; si no, en el directorio del crate",
+        );
+    }
+    context
+}
 
 pub async fn init_db(pool: &SqlitePool) -> Result<()> {
+    ensure_admin(pool, |name| std::env::var(name).ok()).await
+}
+
+/// Crea la tabla de usuarios y, si está vacía, el administrador inicial con
+/// `QUADLY_ADMIN_USER`/`QUADLY_ADMIN_PASS`. `env` lee esas variables; solo se
+/// consulta cuando hay que crear el administrador, así que con usuarios ya
+/// creados la contraseña no hace falta
+async fn ensure_admin<F>(pool: &SqlitePool, env: F) -> Result<()>
+where
+    F: Fn(&str) -> Option<String>,
+{
     // 1. Crear tabla si no existe
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS users (
@@ -20,15 +77,16 @@ pub async fn init_db(pool: &SqlitePool) -> Result<()> {
         .await?;
 
     if count == 0 {
-        let admin_user = std::env::var("QUADLY_ADMIN_USER").unwrap_or_else(|_| "admin".into());
-        let admin_pass =
-            std::env::var("QUADLY_ADMIN_PASS").expect("QUADLY_ADMIN_PASS es obligatoria");
+        let admin_user = env("QUADLY_ADMIN_USER").unwrap_or_else(|| "admin".into());
+        let admin_pass = env("QUADLY_ADMIN_PASS").ok_or_else(|| {
+            anyhow!("No hay usuarios: define QUADLY_ADMIN_PASS para crear el administrador inicial")
+        })?;
         let hash = bcrypt::hash(admin_pass, bcrypt::DEFAULT_COST)?;
 
         sqlx::query("INSERT INTO users (username, password_hash, role) VALUES (?, ?, ?)")
             .bind(admin_user)
             .bind(hash)
-            .bind("admin")
+            .bind(Role::Admin)
             .execute(pool)
             .await?;
 
@@ -37,9 +95,100 @@ pub async fn init_db(pool: &SqlitePool) -> Result<()> {
     Ok(())
 }
 
-// En el shutdown_signal de main.rs
-async fn shutdown_signal(pool: SqlitePool) {
-    // ... lógica de señales (Ctrl+C, SIGTERM) ...
-    println!("Cerrando conexiones de base de datos...");
-    pool.close().await;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn memory_pool() -> SqlitePool {
+        sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    async fn users(pool: &SqlitePool) -> i32 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn no_exe() -> std::io::Result<PathBuf> {
+        panic!("solo en producción se busca el ejecutable")
+    }
+
+    #[test]
+    fn test_migrations_dir_in_production_is_next_to_exe() {
+        let dir = resolve_migrations_dir(
+            |name| (name == "RUST_ENV").then(|| "production".to_string()),
+            || Ok(PathBuf::from("/opt/quadly/backend")),
+        )
+        .unwrap();
+        assert_eq!(dir, Path::new("/opt/quadly/migrations"));
+    }
+
+    #[test]
+    fn test_migrations_dir_in_development_is_the_crate() {
+        let dir = resolve_migrations_dir(
+            |name| match name {
+                "RUST_ENV" => Some("development".to_string()),
+                "CARGO_MANIFEST_DIR" => Some("/src/quadly/backend".to_string()),
+                _ => None,
+            },
+            no_exe,
+        )
+        .unwrap();
+        assert_eq!(dir, Path::new("/src/quadly/backend/migrations"));
+
+        // Sin cargo run se usa el crate con el que se compiló
+        let dir = resolve_migrations_dir(|_| None, no_exe).unwrap();
+        assert_eq!(
+            dir,
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations")
+        );
+        assert!(dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_missing_migrations_dir_explains_where() {
+        let pool = memory_pool().await;
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("migrations");
+        let error = run_migrations(&pool, &missing).await.unwrap_err();
+        let message = format!("{:#}", error);
+        assert!(message.contains(&missing.display().to_string()));
+        assert!(message.contains("el directorio no existe"));
+
+        let migrations = resolve_migrations_dir(|_| None, no_exe).unwrap();
+        run_migrations(&pool, &migrations).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_empty_table_without_password_fails() {
+        let pool = memory_pool().await;
+        let result = ensure_admin(&pool, |_| None).await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("QUADLY_ADMIN_PASS"));
+        assert_eq!(users(&pool).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_populated_table_does_not_need_password() {
+        let pool = memory_pool().await;
+        ensure_admin(&pool, |name| {
+            (name == "QUADLY_ADMIN_PASS").then(|| "s3cr3t".to_string())
+        })
+        .await
+        .unwrap();
+        assert_eq!(users(&pool).await, 1);
+
+        // Con usuarios ya creados las variables ni se leen
+        ensure_admin(&pool, |name| panic!("no debería leer {}", name))
+            .await
+            .unwrap();
+        assert_eq!(users(&pool).await, 1);
+    }
 }
@@ -0,0 +1,105 @@
+use crate::models::{write_quadlet_file, AppError};
+use std::path::Path;
+
+/// Comprueba que el nombre de un drop-in sea un `.conf` dentro de su directorio
+pub fn validate_dropin_name(file: &str) -> Result<(), AppError> {
+    let stem = file.strip_suffix(".conf").unwrap_or_default();
+    let valid = !stem.is_empty()
+        && !stem.starts_with('.')
+        && stem
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::validation_error(
+            "file",
+            "debe ser un nombre terminado en .conf con letras, números, '-', '_' o '.'",
+        ))
+    }
+}
+
+/// Drop-ins `.conf` de un directorio `{unidad}.d`, ordenados como los aplica systemd.
+/// Si el directorio no existe la lista está vacía
+pub async fn list_dropins(dir: &Path) -> Result<Vec<String>, AppError> {
+    let mut files = Vec::new();
+    if !tokio::fs::try_exists(dir).await.unwrap_or(false) {
+        return Ok(files);
+    }
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if let Some(file) = entry.file_name().to_str() {
+            if validate_dropin_name(file).is_ok() && entry.file_type().await?.is_file() {
+                files.push(file.to_string());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+pub async fn read_dropin(dir: &Path, file: &str) -> Result<String, AppError> {
+    validate_dropin_name(file)?;
+    tokio::fs::read_to_string(dir.join(file))
+        .await
+        .map_err(|e| AppError::from_io(e, file))
+}
+
+/// Escribe un drop-in de forma atómica y con los permisos de los quadlets, creando
+/// el directorio `{unidad}.d` si hace falta
+pub async fn write_dropin(dir: &Path, file: &str, content: &str) -> Result<(), AppError> {
+    validate_dropin_name(file)?;
+    write_quadlet_file(&dir.join(file), content)
+        .await
+        .map_err(|e| AppError::from_io(e, file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_write_and_list_dropins() {
+        let dir = tempfile::tempdir().unwrap();
+        let dropins = dir.path().join("web.service.d");
+        assert!(list_dropins(&dropins).await.unwrap().is_empty());
+
+        write_dropin(&dropins, "override.conf", "[Service]\nRestart=always\n")
+            .await
+            .unwrap();
+        write_dropin(&dropins, "10-limits.conf", "[Service]\nMemoryMax=1G\n")
+            .await
+            .unwrap();
+        std::fs::write(dropins.join("notes.txt"), "no es un drop-in").unwrap();
+
+        assert_eq!(
+            list_dropins(&dropins).await.unwrap(),
+            vec!["10-limits.conf", "override.conf"]
+        );
+        assert_eq!(
+            read_dropin(&dropins, "override.conf").await.unwrap(),
+            "[Service]\nRestart=always\n"
+        );
+
+        // Mismos permisos que los quadlets: pueden llevar secretos en Environment=
+        use std::os::unix::fs::PermissionsExt;
+        let mode = |path: &Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&dropins.join("override.conf")), 0o600);
+        assert_eq!(mode(&dropins), 0o700);
+    }
+
+    #[test]
+    fn test_invalid_dropin_names() {
+        for file in [
+            "../evil.conf",
+            "override",
+            ".conf",
+            ".hidden.conf",
+            "a/b.conf",
+            "",
+        ] {
+            assert!(validate_dropin_name(file).is_err(), "{}", file);
+        }
+        assert!(validate_dropin_name("override.conf").is_ok());
+    }
+}
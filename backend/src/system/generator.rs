@@ -0,0 +1,158 @@
+use crate::models::{AppError, SystemdScope};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tracing::warn;
+
+/// Rutas donde las distintas distribuciones instalan el generador de quadlets de podman
+const GENERATOR_CANDIDATES: [&str; 4] = [
+    "/usr/lib/systemd/user-generators/podman-user-generator",
+    "/usr/lib/systemd/system-generators/podman-system-generator",
+    "/usr/libexec/podman/quadlet",
+    "/usr/lib/podman/quadlet",
+];
+
+/// Busca el generador de quadlets usando `exists` para comprobar cada ruta candidata.
+/// Devuelve la primera ruta encontrada o `None` si el generador no está instalado.
+pub fn probe_quadlet_generator<F>(exists: F) -> Option<PathBuf>
+where
+    F: Fn(&Path) -> bool,
+{
+    GENERATOR_CANDIDATES
+        .iter()
+        .map(Path::new)
+        .find(|path| exists(path))
+        .map(Path::to_path_buf)
+}
+
+/// Directorio donde systemd deja las unidades generadas
+fn generator_output_dir(scope: SystemdScope) -> Option<PathBuf> {
+    match scope {
+        SystemdScope::User => std::env::var("XDG_RUNTIME_DIR")
+            .ok()
+            .map(|dir| PathBuf::from(dir).join("systemd/generator")),
+        SystemdScope::System => Some(PathBuf::from("/run/systemd/generator")),
+    }
+}
+
+/// Extrae una unidad de la salida de `quadlet -dryrun`, donde cada unidad
+/// va precedida de una cabecera `---nombre.service---`
+fn extract_dryrun_unit(output: &str, unit_name: &str) -> Option<String> {
+    let header = format!("---{}---", unit_name);
+    let mut lines = output.lines().skip_while(|line| line.trim() != header);
+    lines.next()?;
+    let unit: Vec<&str> = lines
+        .take_while(|line| !(line.starts_with("---") && line.ends_with("---")))
+        .collect();
+    Some(unit.join("\n").trim().to_string() + "\n")
+}
+
+/// Lee una unidad de un directorio de unidades generadas. `Ok(None)` si no existe
+async fn read_unit_from(dir: &Path, unit_name: &str) -> Result<Option<String>, AppError> {
+    match tokio::fs::read_to_string(dir.join(unit_name)).await {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Devuelve el contenido de la unidad que el generador de quadlets produjo para `unit_name`.
+/// Primero busca en el directorio de unidades generadas y, si no está, ejecuta el
+/// generador en modo dry-run
+pub async fn get_generated_unit(
+    scope: SystemdScope,
+    unit_name: &str,
+    generator: Option<&Path>,
+) -> Result<String, AppError> {
+    if let Some(dir) = generator_output_dir(scope) {
+        if let Some(content) = read_unit_from(&dir, unit_name).await? {
+            return Ok(content);
+        }
+    }
+
+    if let Some(generator) = generator {
+        let mut command = Command::new(generator);
+        if scope == SystemdScope::User {
+            command.arg("-user");
+        }
+        match command.arg("-dryrun").output().await {
+            Ok(output) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                if let Some(unit) = extract_dryrun_unit(&stdout, unit_name) {
+                    return Ok(unit);
+                }
+            }
+            Err(e) => warn!("Error running {} -dryrun: {}", generator.display(), e),
+        }
+    }
+
+    Err(AppError::NotFound(format!(
+        "No se encontró la unidad generada '{}'",
+        unit_name
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probe_detects_generator() {
+        let found =
+            probe_quadlet_generator(|path| path == Path::new("/usr/libexec/podman/quadlet"));
+        assert_eq!(found, Some(PathBuf::from("/usr/libexec/podman/quadlet")));
+    }
+
+    #[test]
+    fn test_probe_prefers_first_candidate() {
+        let found = probe_quadlet_generator(|_| true);
+        assert_eq!(found, Some(PathBuf::from(GENERATOR_CANDIDATES[0])));
+    }
+
+    #[test]
+    fn test_probe_reports_absent_generator() {
+        assert_eq!(probe_quadlet_generator(|_| false), None);
+    }
+
+    const DRYRUN_OUTPUT: &str = "quadlet-generator[1]: Loading source unit file web.container
+---db.service---
+[Unit]
+Description=db
+
+---web.service---
+[Unit]
+SourcePath=/home/user/.config/containers/systemd/web.container
+
+[Service]
+ExecStart=/usr/bin/podman run --name systemd-web nginx
+";
+
+    #[test]
+    fn test_extract_dryrun_unit() {
+        let unit = extract_dryrun_unit(DRYRUN_OUTPUT, "web.service").unwrap();
+        assert!(unit.starts_with("[Unit]\nSourcePath="));
+        assert!(unit.contains("ExecStart=/usr/bin/podman run"));
+        assert!(!unit.contains("Description=db"));
+
+        let unit = extract_dryrun_unit(DRYRUN_OUTPUT, "db.service").unwrap();
+        assert_eq!(unit, "[Unit]\nDescription=db\n");
+    }
+
+    #[test]
+    fn test_extract_dryrun_unit_missing() {
+        assert_eq!(extract_dryrun_unit(DRYRUN_OUTPUT, "cache.service"), None);
+    }
+
+    #[tokio::test]
+    async fn test_read_unit_from_generator_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("web.service"), "[Service]\n").unwrap();
+        assert_eq!(
+            read_unit_from(dir.path(), "web.service").await.unwrap(),
+            Some("[Service]\n".to_string())
+        );
+        assert_eq!(
+            read_unit_from(dir.path(), "db.service").await.unwrap(),
+            None
+        );
+    }
+}
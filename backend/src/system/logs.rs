@@ -1,24 +1,591 @@
-use std::process::Command;
-use anyhow::{Context, Result};
-
-pub fn get_service_logs(name: &str, lines: u32) -> Result<String> {
-    let unit_name = format!("{}.service", name);
-    
-    // Ejecutamos journalctl --user -u <nombre> -n <lineas> --no-pager
-    let output = Command::new("journalctl")
-        .arg("--user")
-        .arg("-u")
-        .arg(&unit_name)
-        .arg("-n")
-        .arg(lines.to_string())
-        .arg("--no-pager") // Importante para que no se quede bloqueado esperando input
-        .output()
-        .context("Falló al ejecutar journalctl")?;
+use anyhow::{anyhow, Result};
+use chrono::DateTime;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use super::systemd::{list_quadlets, quadlet_unit_name};
+use crate::models::{get_quadlet_dir, AppError, LogEntry, QuadletType, SystemdScope, UnitErrors};
+
+/// Ejecutable de journalctl: `JOURNALCTL_BIN` o `journalctl` buscado en el PATH
+fn journalctl_bin() -> PathBuf {
+    std::env::var_os("JOURNALCTL_BIN")
+        .filter(|bin| !bin.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("journalctl"))
+}
+
+/// Argumentos que seleccionan el journal del ámbito: el del usuario o el del sistema
+fn scope_args(scope: SystemdScope) -> Vec<String> {
+    match scope {
+        SystemdScope::User => vec!["--user".to_string()],
+        SystemdScope::System => Vec::new(),
+    }
+}
+
+/// Error al lanzar journalctl. Si no existe se dice claramente cómo arreglarlo
+fn spawn_error(bin: &Path, err: std::io::Error) -> AppError {
+    if err.kind() == std::io::ErrorKind::NotFound {
+        AppError::SystemdError(format!(
+            "No se encontró journalctl en '{}'. Instálalo o indica su ruta con JOURNALCTL_BIN",
+            bin.display()
+        ))
+    } else {
+        AppError::from(anyhow!("Falló al ejecutar journalctl: {}", err))
+    }
+}
+
+/// Ventana temporal opcional para filtrar los logs (`--since` / `--until`).
+/// Acepta cualquier formato que entienda journalctl: RFC 3339, "2 hours ago", "yesterday"...
+#[derive(Debug, Default, Clone)]
+pub struct LogWindow {
+    pub since: Option<String>,
+    pub until: Option<String>,
+}
+
+/// Comprueba que un valor de tiempo no pueda interpretarse como un flag de journalctl
+fn validate_time_spec(field: &str, value: &str) -> Result<(), AppError> {
+    if value.trim().is_empty() {
+        return Err(AppError::BadRequest(format!(
+            "'{}' no puede estar vacío",
+            field
+        )));
+    }
+    if value.trim_start().starts_with('-') {
+        return Err(AppError::BadRequest(format!(
+            "'{}' no puede empezar por '-'",
+            field
+        )));
+    }
+    if value.chars().any(char::is_control) {
+        return Err(AppError::BadRequest(format!(
+            "'{}' contiene caracteres no válidos",
+            field
+        )));
+    }
+    Ok(())
+}
+
+impl LogWindow {
+    pub fn validate(&self) -> Result<(), AppError> {
+        if let Some(since) = &self.since {
+            validate_time_spec("since", since)?;
+        }
+        if let Some(until) = &self.until {
+            validate_time_spec("until", until)?;
+        }
+        Ok(())
+    }
+}
+
+/// Construye los argumentos de journalctl [--user] -u <unidad> -n <lineas> -o <formato> --no-pager
+fn journalctl_args(
+    scope: SystemdScope,
+    unit_name: &str,
+    lines: u32,
+    output_format: &str,
+    window: &LogWindow,
+) -> Result<Vec<String>, AppError> {
+    window.validate()?;
+    let mut args = scope_args(scope);
+    args.extend([
+        "-u".to_string(),
+        unit_name.to_string(),
+        "-n".to_string(),
+        lines.to_string(),
+        "-o".to_string(),
+        output_format.to_string(),
+        "--no-pager".to_string(), // Importante para que no se quede bloqueado esperando input
+    ]);
+    if let Some(since) = &window.since {
+        args.push(format!("--since={}", since.trim()));
+    }
+    if let Some(until) = &window.until {
+        args.push(format!("--until={}", until.trim()));
+    }
+    Ok(args)
+}
+
+/// Tiempo máximo que esperamos a journalctl antes de abandonar la petición
+const JOURNALCTL_TIMEOUT: Duration = Duration::from_secs(10);
+
+async fn journalctl_output(
+    scope: SystemdScope,
+    unit_name: &str,
+    lines: u32,
+    output_format: &str,
+    window: &LogWindow,
+) -> Result<String, AppError> {
+    let args = journalctl_args(scope, unit_name, lines, output_format, window)?;
+    run_journalctl(&args).await
+}
+
+async fn run_journalctl(args: &[String]) -> Result<String, AppError> {
+    let bin = journalctl_bin();
+    let mut command = Command::new(&bin);
+    // Si vence el timeout el futuro se descarta y el proceso se mata
+    command.args(args).kill_on_drop(true);
+    let output = tokio::time::timeout(JOURNALCTL_TIMEOUT, command.output())
+        .await
+        .map_err(|_| {
+            AppError::SystemdError(format!(
+                "journalctl no respondió en {} segundos",
+                JOURNALCTL_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|e| spawn_error(&bin, e))?;
 
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     } else {
         let error = String::from_utf8_lossy(&output.stderr);
-        Err(anyhow::anyhow!("Error obteniendo logs: {}", error))
+        Err(anyhow::anyhow!("Error obteniendo logs: {}", error).into())
+    }
+}
+
+pub async fn get_service_logs(
+    scope: SystemdScope,
+    name: &str,
+    kind: QuadletType,
+    lines: u32,
+    window: &LogWindow,
+) -> Result<String, AppError> {
+    let unit_name = quadlet_unit_name(name, kind)?;
+    journalctl_output(scope, &unit_name, lines, "short", window).await
+}
+
+/// Devuelve los logs de un servicio como entradas estructuradas
+pub async fn get_service_logs_json(
+    scope: SystemdScope,
+    name: &str,
+    kind: QuadletType,
+    lines: u32,
+    window: &LogWindow,
+) -> Result<Vec<LogEntry>, AppError> {
+    let unit_name = quadlet_unit_name(name, kind)?;
+    let output = journalctl_output(scope, &unit_name, lines, "json", window).await?;
+    Ok(parse_log_entries(&output, &unit_name))
+}
+
+/// Argumentos para leer los últimos errores de todas las unidades del ámbito
+fn error_args(scope: SystemdScope, lines: u32) -> Vec<String> {
+    let mut args = scope_args(scope);
+    args.extend([
+        "-p".to_string(),
+        "err".to_string(),
+        "-n".to_string(),
+        lines.to_string(),
+        "-o".to_string(),
+        "json".to_string(),
+        "--no-pager".to_string(),
+    ]);
+    args
+}
+
+/// Agrupa las entradas por unidad, quedándose solo con las unidades de `quadlets`
+/// (unidad -> nombre y tipo). Las unidades con el error más reciente van primero
+fn group_errors(
+    entries: Vec<LogEntry>,
+    quadlets: &HashMap<String, (String, QuadletType)>,
+) -> Vec<UnitErrors> {
+    let mut groups: HashMap<String, UnitErrors> = HashMap::new();
+    for entry in entries {
+        let Some((name, kind)) = quadlets.get(&entry.unit) else {
+            continue;
+        };
+        let group = groups
+            .entry(entry.unit.clone())
+            .or_insert_with(|| UnitErrors {
+                unit: entry.unit.clone(),
+                name: name.clone(),
+                kind: *kind,
+                count: 0,
+                last_message: entry.message.clone(),
+                last_timestamp: entry.timestamp,
+            });
+        group.count += 1;
+        if entry.timestamp >= group.last_timestamp {
+            group.last_message = entry.message;
+            group.last_timestamp = entry.timestamp;
+        }
+    }
+    let mut groups: Vec<_> = groups.into_values().collect();
+    groups.sort_by(|a, b| {
+        b.last_timestamp
+            .cmp(&a.last_timestamp)
+            .then_with(|| a.unit.cmp(&b.unit))
+    });
+    groups
+}
+
+/// Resumen de los últimos `lines` errores del journal, agrupados por quadlet
+pub async fn get_error_summary(
+    scope: SystemdScope,
+    lines: u32,
+) -> Result<Vec<UnitErrors>, AppError> {
+    let quadlets: HashMap<_, _> = list_quadlets(&get_quadlet_dir())
+        .await?
+        .into_iter()
+        .filter_map(|(name, kind)| Some((kind.unit_name(&name)?, (name, kind))))
+        .collect();
+    let output = run_journalctl(&error_args(scope, lines)).await?;
+    Ok(group_errors(parse_log_entries(&output, ""), &quadlets))
+}
+
+/// Los campos de journald pueden venir como texto o, si no son UTF-8, como array de bytes
+fn journal_field(entry: &Value, field: &str) -> Option<String> {
+    match entry.get(field)? {
+        Value::String(value) => Some(value.clone()),
+        Value::Array(bytes) => {
+            let bytes: Vec<u8> = bytes
+                .iter()
+                .filter_map(|b| b.as_u64().map(|b| b as u8))
+                .collect();
+            Some(String::from_utf8_lossy(&bytes).to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Convierte una línea de `journalctl -o json` en un `LogEntry`
+fn parse_log_entry(line: &str, default_unit: &str) -> Result<LogEntry> {
+    let entry: Value = serde_json::from_str(line)?;
+    let micros: i64 = journal_field(&entry, "__REALTIME_TIMESTAMP")
+        .ok_or_else(|| anyhow!("Falta __REALTIME_TIMESTAMP"))?
+        .parse()?;
+    let timestamp =
+        DateTime::from_timestamp_micros(micros).ok_or_else(|| anyhow!("Timestamp inválido"))?;
+    let priority = journal_field(&entry, "PRIORITY")
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(6); // info
+    let message = journal_field(&entry, "MESSAGE").unwrap_or_default();
+    let unit = journal_field(&entry, "_SYSTEMD_USER_UNIT")
+        .or_else(|| journal_field(&entry, "_SYSTEMD_UNIT"))
+        .unwrap_or_else(|| default_unit.to_string());
+    Ok(LogEntry {
+        timestamp,
+        priority,
+        message,
+        unit,
+    })
+}
+
+/// Convierte la salida de `journalctl -o json` en entradas, descartando las líneas inválidas
+fn parse_log_entries(output: &str, default_unit: &str) -> Vec<LogEntry> {
+    output
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match parse_log_entry(line, default_unit) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                warn!("Skipping malformed journal line: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Indica si journalctl está disponible, necesario para leer y seguir logs. Una ruta
+/// en `JOURNALCTL_BIN` se comprueba tal cual; un nombre se busca en el PATH
+pub fn journalctl_available() -> bool {
+    let bin = journalctl_bin();
+    if bin.components().count() > 1 {
+        return bin.is_file();
+    }
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(&bin).is_file()))
+        .unwrap_or(false)
+}
+
+/// Argumentos de `journalctl -f` para seguir una unidad
+fn follow_args(scope: SystemdScope, unit_name: &str) -> Vec<String> {
+    let mut args = scope_args(scope);
+    args.extend(
+        ["-u", unit_name, "-f", "-o", "cat", "--no-pager"]
+            .into_iter()
+            .map(str::to_string),
+    );
+    args
+}
+
+/// Lanza `journalctl -f` para seguir en vivo los logs de un servicio.
+/// Solo se leen los logs de la unidad indicada y el proceso muere si se descarta el `Child`
+pub fn follow_service_logs(
+    scope: SystemdScope,
+    name: &str,
+    kind: QuadletType,
+) -> Result<Child, AppError> {
+    let unit_name = quadlet_unit_name(name, kind)?;
+    let bin = journalctl_bin();
+
+    Command::new(&bin)
+        .args(follow_args(scope, &unit_name))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| spawn_error(&bin, e))
+}
+
+/// Envía por `tx` cada línea de la salida de `child` hasta que el proceso termina
+/// o el receptor se cierra. Al terminar el proceso se mata y se recoge siempre,
+/// para no dejar procesos huérfanos cuando el cliente se desconecta
+pub async fn pipe_lines(mut child: Child, tx: mpsc::Sender<String>) -> std::io::Result<ExitStatus> {
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        loop {
+            tokio::select! {
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) => {
+                        if tx.send(line).await.is_err() {
+                            break;
+                        }
+                    }
+                    _ => break,
+                },
+                _ = tx.closed() => break,
+            }
+        }
+    }
+    // Si el proceso ya ha terminado no hay nada que matar
+    let _ = child.start_kill();
+    child.wait().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_logs_reject_unsafe_unit_names() {
+        let window = LogWindow::default();
+        for name in ["foo;rm -rf", "--user"] {
+            let kind = QuadletType::Container;
+            let result = get_service_logs(SystemdScope::User, name, kind, 10, &window).await;
+            assert!(matches!(result, Err(AppError::ValidationError(_))));
+            let result = get_service_logs_json(SystemdScope::User, name, kind, 10, &window).await;
+            assert!(matches!(result, Err(AppError::ValidationError(_))));
+            assert!(matches!(
+                follow_service_logs(SystemdScope::User, name, kind),
+                Err(AppError::ValidationError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_journalctl_args_without_window() {
+        let window = LogWindow::default();
+        let args = journalctl_args(SystemdScope::User, "web.service", 20, "short", &window);
+        assert_eq!(
+            args.unwrap(),
+            vec![
+                "--user",
+                "-u",
+                "web.service",
+                "-n",
+                "20",
+                "-o",
+                "short",
+                "--no-pager"
+            ]
+        );
+        let args = journalctl_args(SystemdScope::System, "web.service", 20, "short", &window);
+        assert_eq!(
+            args.unwrap(),
+            vec!["-u", "web.service", "-n", "20", "-o", "short", "--no-pager"]
+        );
+    }
+
+    #[test]
+    fn test_follow_args_per_scope() {
+        assert_eq!(
+            follow_args(SystemdScope::User, "web.service"),
+            vec![
+                "--user",
+                "-u",
+                "web.service",
+                "-f",
+                "-o",
+                "cat",
+                "--no-pager"
+            ]
+        );
+        assert_eq!(
+            follow_args(SystemdScope::System, "web.service"),
+            vec!["-u", "web.service", "-f", "-o", "cat", "--no-pager"]
+        );
+    }
+
+    #[test]
+    fn test_missing_binary_is_a_systemd_error() {
+        let bin = Path::new("/nonexistent/journalctl");
+        let error = std::io::Error::from(std::io::ErrorKind::NotFound);
+        match spawn_error(bin, error) {
+            AppError::SystemdError(msg) => {
+                assert!(msg.contains("/nonexistent/journalctl"));
+                assert!(msg.contains("JOURNALCTL_BIN"));
+            }
+            other => panic!("se esperaba SystemdError, obtenido {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_journalctl_args_with_window() {
+        let window = LogWindow {
+            since: Some("2 hours ago".to_string()),
+            until: Some("2024-01-01T10:00:00Z".to_string()),
+        };
+        let args = journalctl_args(SystemdScope::User, "web.service", 20, "json", &window).unwrap();
+        assert!(args.contains(&"--since=2 hours ago".to_string()));
+        assert!(args.contains(&"--until=2024-01-01T10:00:00Z".to_string()));
+    }
+
+    #[test]
+    fn test_journalctl_args_rejects_flags() {
+        for window in [
+            LogWindow {
+                since: Some("--output=export".to_string()),
+                until: None,
+            },
+            LogWindow {
+                since: None,
+                until: Some(" -f".to_string()),
+            },
+            LogWindow {
+                since: Some("today\n--flag".to_string()),
+                until: None,
+            },
+            LogWindow {
+                since: Some("".to_string()),
+                until: None,
+            },
+        ] {
+            let result = journalctl_args(SystemdScope::User, "web.service", 20, "short", &window);
+            assert!(
+                matches!(result, Err(AppError::BadRequest(_))),
+                "{:?}",
+                window
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_log_entries() {
+        let output = r#"{"__REALTIME_TIMESTAMP":"1700000000000000","PRIORITY":"3","MESSAGE":"boom","_SYSTEMD_USER_UNIT":"web.service"}
+not json at all
+{"__REALTIME_TIMESTAMP":"1700000001000000","MESSAGE":[104,105]}
+{"PRIORITY":"6","MESSAGE":"sin timestamp"}
+"#;
+        let entries = parse_log_entries(output, "web.service");
+        assert_eq!(entries.len(), 2);
+
+        assert_eq!(entries[0].timestamp.timestamp(), 1_700_000_000);
+        assert_eq!(entries[0].priority, 3);
+        assert_eq!(entries[0].message, "boom");
+        assert_eq!(entries[0].unit, "web.service");
+
+        // Mensaje binario, prioridad por defecto y unidad por defecto
+        assert_eq!(entries[1].message, "hi");
+        assert_eq!(entries[1].priority, 6);
+        assert_eq!(entries[1].unit, "web.service");
+    }
+
+    #[test]
+    fn test_error_args_have_no_unit_filter() {
+        assert_eq!(
+            error_args(SystemdScope::User, 100),
+            vec![
+                "--user",
+                "-p",
+                "err",
+                "-n",
+                "100",
+                "-o",
+                "json",
+                "--no-pager"
+            ]
+        );
+        assert!(!error_args(SystemdScope::System, 100).contains(&"-u".to_string()));
+    }
+
+    #[test]
+    fn test_group_errors_by_quadlet_unit() {
+        let output = r#"{"__REALTIME_TIMESTAMP":"1700000000000000","PRIORITY":"3","MESSAGE":"no such image","_SYSTEMD_USER_UNIT":"web.service"}
+{"__REALTIME_TIMESTAMP":"1700000001000000","PRIORITY":"3","MESSAGE":"dbus hiccup","_SYSTEMD_USER_UNIT":"dbus.service"}
+{"__REALTIME_TIMESTAMP":"1700000002000000","PRIORITY":"2","MESSAGE":"pod failed","_SYSTEMD_UNIT":"db-pod.service"}
+{"__REALTIME_TIMESTAMP":"1700000003000000","PRIORITY":"3","MESSAGE":"exited 1","_SYSTEMD_USER_UNIT":"web.service"}
+{"__REALTIME_TIMESTAMP":"1700000004000000","PRIORITY":"3","MESSAGE":"kernel says no"}
+"#;
+        let quadlets = HashMap::from([
+            (
+                "web.service".to_string(),
+                ("web".to_string(), QuadletType::Container),
+            ),
+            (
+                "db-pod.service".to_string(),
+                ("db".to_string(), QuadletType::Pod),
+            ),
+        ]);
+        let groups = group_errors(parse_log_entries(output, ""), &quadlets);
+        assert_eq!(groups.len(), 2);
+
+        assert_eq!(groups[0].unit, "web.service");
+        assert_eq!(groups[0].name, "web");
+        assert_eq!(groups[0].count, 2);
+        assert_eq!(groups[0].last_message, "exited 1");
+        assert_eq!(groups[0].last_timestamp.timestamp(), 1_700_000_003);
+
+        assert_eq!(groups[1].unit, "db-pod.service");
+        assert_eq!(groups[1].kind, QuadletType::Pod);
+        assert_eq!(groups[1].count, 1);
+        assert_eq!(groups[1].last_message, "pod failed");
+    }
+
+    #[tokio::test]
+    async fn test_pipe_lines_reaps_process_on_disconnect() {
+        let child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg("echo one; echo two; sleep 30")
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .unwrap();
+        let (tx, mut rx) = mpsc::channel(1);
+        let pipe = tokio::spawn(pipe_lines(child, tx));
+
+        assert_eq!(rx.recv().await.as_deref(), Some("one"));
+        // El cliente se desconecta
+        drop(rx);
+
+        let status = tokio::time::timeout(Duration::from_secs(5), pipe)
+            .await
+            .expect("el proceso no se ha recogido")
+            .unwrap()
+            .unwrap();
+        assert!(!status.success());
+    }
+
+    #[tokio::test]
+    async fn test_pipe_lines_forwards_until_exit() {
+        let child = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg("echo one; echo two")
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let (tx, mut rx) = mpsc::channel(8);
+        pipe_lines(child, tx).await.unwrap();
+
+        assert_eq!(rx.recv().await.as_deref(), Some("one"));
+        assert_eq!(rx.recv().await.as_deref(), Some("two"));
+        assert_eq!(rx.recv().await, None);
     }
 }
@@ -1,8 +1,35 @@
+mod backup;
+mod bus;
 mod db;
+mod dropins;
+mod generator;
 mod logs;
+mod podman;
 mod quadlet;
+mod retry;
+mod shutdown;
 mod systemd;
+mod tls;
+mod versions;
+mod watcher;
 
-pub use db::init_db;
-pub use logs::get_service_logs;
-pub use systemd::{discover_quadlets, get_status, run_unit_action};
+pub use backup::{export_archive, import_archive};
+pub use bus::SystemdBus;
+pub use db::{init_db, migrations_dir, run_migrations};
+pub use dropins::{list_dropins, read_dropin, write_dropin};
+pub use generator::{get_generated_unit, probe_quadlet_generator};
+pub use logs::{
+    follow_service_logs, get_error_summary, get_service_logs, get_service_logs_json,
+    journalctl_available, pipe_lines, LogWindow,
+};
+pub use podman::pull_image;
+pub use shutdown::{shutdown_signal, InFlight};
+pub use systemd::{
+    apply_unit, daemon_reload, discover_quadlets, discover_quadlets_stream, get_orphan_units, get_status, get_status_detail, get_statuses, get_unit_resources,
+    predict_status, run_bulk_action, run_unit_action, split_quadlet_file_name, stop_all, supervise_systemd_events,
+};
+pub use tls::{load_tls_config, tls_paths};
+#[cfg(test)]
+pub(crate) use systemd::{dispatch_apply, tests::FakeManager};
+pub use versions::get_versions;
+pub use watcher::watch_quadlet_dir;
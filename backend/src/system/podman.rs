@@ -0,0 +1,96 @@
+use crate::models::{AppError, HealthState, ImagePull};
+use anyhow::Context;
+use std::process::Stdio;
+use tokio::process::Command;
+
+/// Descarga `image` con `podman pull` y espera a que termine. Podman escribe el
+/// progreso en stderr y el id de la imagen en stdout
+pub async fn pull_image(image: &str) -> Result<ImagePull, AppError> {
+    // Una imagen que empieza por '-' se interpretaría como opción de podman
+    if image.is_empty() || image.starts_with('-') {
+        return Err(AppError::bad_request(&format!(
+            "Imagen no válida: '{}'",
+            image
+        )));
+    }
+    let output = Command::new("podman")
+        .arg("pull")
+        .arg(image)
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .context("Falló al ejecutar podman pull")
+        .map_err(AppError::from)?;
+    let progress = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if !output.status.success() {
+        return Err(AppError::InternalServerError(format!(
+            "podman pull {} falló: {}",
+            image, progress
+        )));
+    }
+    let id = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .last()
+        .map(|line| line.trim().to_string())
+        .filter(|id| !id.is_empty());
+    Ok(ImagePull {
+        image: image.to_string(),
+        id,
+        output: progress,
+    })
+}
+
+/// Interpreta la salida de `podman inspect --format '{{.State.Health.Status}}'`.
+/// Sin healthcheck podman devuelve una cadena vacía (o `<no value>` en versiones antiguas)
+fn parse_health(output: &str) -> Option<HealthState> {
+    match output.trim() {
+        "healthy" => Some(HealthState::Healthy),
+        "unhealthy" => Some(HealthState::Unhealthy),
+        "starting" => Some(HealthState::Starting),
+        "" | "<no value>" => Some(HealthState::None),
+        _ => None,
+    }
+}
+
+/// Estado del healthcheck del contenedor de un quadlet `.container`. Se asume el
+/// nombre por defecto que le da quadlet (`systemd-<nombre>`). `None` si no se puede
+/// consultar, por ejemplo porque el contenedor no existe o podman no está instalado
+pub async fn get_container_health(name: &str) -> Option<HealthState> {
+    let output = Command::new("podman")
+        .arg("inspect")
+        .arg("--type")
+        .arg("container")
+        .arg("--format")
+        .arg("{{.State.Health.Status}}")
+        .arg(format!("systemd-{}", name))
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_health(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_health() {
+        assert_eq!(parse_health("healthy\n"), Some(HealthState::Healthy));
+        assert_eq!(parse_health("unhealthy\n"), Some(HealthState::Unhealthy));
+        assert_eq!(parse_health("starting\n"), Some(HealthState::Starting));
+        assert_eq!(parse_health("\n"), Some(HealthState::None));
+        assert_eq!(parse_health("<no value>\n"), Some(HealthState::None));
+        assert_eq!(parse_health("Error: no such container"), None);
+    }
+
+    #[tokio::test]
+    async fn test_pull_rejects_option_like_images() {
+        let result = pull_image("--help").await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+}
@@ -0,0 +1,72 @@
+use std::future::Future;
+use std::time::Duration;
+
+/// Ejecuta `op` hasta `attempts` veces mientras falle con un error transitorio,
+/// doblando la espera entre intentos a partir de `delay`. Devuelve el último error
+pub async fn retry_with_backoff<T, E, F, Fut>(
+    attempts: u32,
+    mut delay: Duration,
+    is_transient: impl Fn(&E) -> bool,
+    mut op: F,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(e) if attempt < attempts && is_transient(&e) => {
+                tracing::debug!("Intento {} de {} fallido, reintentando", attempt, attempts);
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    async fn run(calls: &AtomicU32, fail_first: u32, transient: bool) -> Result<u32, &'static str> {
+        retry_with_backoff(
+            3,
+            Duration::from_millis(1),
+            |_| transient,
+            || async {
+                let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                if call <= fail_first {
+                    Err("bus caído")
+                } else {
+                    Ok(call)
+                }
+            },
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_retry_succeeds_on_second_attempt() {
+        let calls = AtomicU32::new(0);
+        assert_eq!(run(&calls, 1, true).await, Ok(2));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up_after_attempts() {
+        let calls = AtomicU32::new(0);
+        assert_eq!(run(&calls, 10, true).await, Err("bus caído"));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_skips_permanent_errors() {
+        let calls = AtomicU32::new(0);
+        assert_eq!(run(&calls, 1, false).await, Err("bus caído"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
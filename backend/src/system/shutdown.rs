@@ -0,0 +1,79 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::info;
+
+/// Contador de peticiones en curso, para saber cuántas quedan por terminar al apagar
+#[derive(Clone, Default)]
+pub struct InFlight(Arc<AtomicUsize>);
+
+/// Resta la petición del contador al soltarse, aunque el handler se cancele
+pub struct InFlightGuard(Arc<AtomicUsize>);
+
+impl InFlight {
+    pub fn enter(&self) -> InFlightGuard {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard(self.0.clone())
+    }
+
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Termina al recibir Ctrl+C o SIGTERM. Pensado para `with_graceful_shutdown`:
+/// axum deja de aceptar conexiones y espera a que acaben las peticiones en curso
+pub async fn shutdown_signal(in_flight: InFlight) {
+    let mut terminate =
+        signal(SignalKind::terminate()).expect("No se pudo instalar el manejador de SIGTERM");
+    let name = tokio::select! {
+        _ = tokio::signal::ctrl_c() => "Ctrl+C",
+        _ = terminate.recv() => "SIGTERM",
+    };
+    info!(
+        "{} recibido, esperando a {} peticiones en curso",
+        name,
+        in_flight.count()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_in_flight_guard() {
+        let in_flight = InFlight::default();
+        let first = in_flight.enter();
+        let second = in_flight.enter();
+        assert_eq!(in_flight.count(), 2);
+        drop(first);
+        drop(second);
+        assert_eq!(in_flight.count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_resolves_on_sigterm() {
+        let handle = tokio::spawn(shutdown_signal(InFlight::default()));
+        // Dar tiempo a que se instale el manejador antes de enviar la señal
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let status = std::process::Command::new("kill")
+            .args(["-TERM", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        tokio::time::timeout(Duration::from_secs(5), handle)
+            .await
+            .expect("shutdown_signal no terminó tras SIGTERM")
+            .unwrap();
+    }
+}
@@ -1,7 +1,39 @@
-use crate::models::{get_quadlet_dir, Quadlet, QuadletStatus};
+use super::bus::SystemdBus;
+use super::podman::get_container_health;
+use super::retry::retry_with_backoff;
+use crate::models::{
+    file_modified, get_quadlet_dir, AppError, ApplyStep, BulkActionResult, HealthState, OrphanUnit,
+    QuadletInfo, QuadletStatus, QuadletType, StatusDetail, UnitAction, UnitResources,
+};
 use anyhow::Result;
-use futures_util::StreamExt;
-use zbus::{fdo::PropertiesProxy, proxy, Connection};
+use async_trait::async_trait;
+use futures_util::{Stream, StreamExt};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    time::Duration,
+};
+use tokio::fs::{DirEntry, ReadDir};
+use zbus::{proxy, zvariant::OwnedObjectPath, Connection};
+
+/// Cambios devueltos por systemd al (des)habilitar ficheros de unidad: (tipo, enlace, destino)
+type UnitFileChanges = Vec<(String, String, String)>;
+
+/// Unidad devuelta por `ListUnits`: (nombre, descripción, estado de carga, estado activo,
+/// subestado, unidad a la que sigue, ruta del objeto, id del trabajo, tipo del trabajo,
+/// ruta del trabajo)
+type UnitListEntry = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    String,
+    OwnedObjectPath,
+    u32,
+    String,
+    OwnedObjectPath,
+);
 
 // Proxy para el Manager de systemd
 #[proxy(
@@ -12,28 +44,40 @@ use zbus::{fdo::PropertiesProxy, proxy, Connection};
 trait SystemdManager {
     /// Método para obtener la ruta del objeto de una unidad específica
     fn get_unit(&self, name: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
+    /// Como `get_unit`, pero carga la unidad si systemd la había descartado. Si no
+    /// existe su fichero también devuelve una ruta, con LoadState `not-found`
+    fn load_unit(&self, name: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
     fn start_unit(&self, name: &str, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
     fn stop_unit(&self, name: &str, mode: &str) -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
     fn restart_unit(&self, name: &str, mode: &str)
         -> zbus::Result<zbus::zvariant::OwnedObjectPath>;
     fn reload(&self) -> zbus::Result<()>;
-    /// Lista todas las unidades cargadas
-    fn list_units(
+    /// Limpia el estado `failed` de una unidad (`systemctl reset-failed`)
+    fn reset_failed_unit(&self, name: &str) -> zbus::Result<()>;
+    /// Activa la emisión de señales del Manager para este cliente
+    fn subscribe(&self) -> zbus::Result<()>;
+    /// Señal emitida cuando termina un trabajo sobre una unidad (start, stop, ...)
+    #[zbus(signal)]
+    fn job_removed(
         &self,
-    ) -> zbus::Result<
-        Vec<(
-            String,
-            String,
-            String,
-            String,
-            String,
-            String,
-            zbus::zvariant::OwnedObjectPath,
-            u32,
-            String,
-            zbus::zvariant::OwnedObjectPath,
-        )>,
-    >;
+        id: u32,
+        job: zbus::zvariant::ObjectPath<'_>,
+        unit: &str,
+        result: &str,
+    ) -> zbus::Result<()>;
+    /// Habilita ficheros de unidad. Devuelve si tienen sección [Install] y los cambios realizados
+    fn enable_unit_files(
+        &self,
+        files: &[&str],
+        runtime: bool,
+        force: bool,
+    ) -> zbus::Result<(bool, UnitFileChanges)>;
+    /// Deshabilita ficheros de unidad. Devuelve los cambios realizados
+    fn disable_unit_files(&self, files: &[&str], runtime: bool) -> zbus::Result<UnitFileChanges>;
+    /// Lista todas las unidades cargadas
+    fn list_units(&self) -> zbus::Result<Vec<UnitListEntry>>;
+    /// Estado de habilitación de un fichero de unidad (`systemctl is-enabled`)
+    fn get_unit_file_state(&self, file: &str) -> zbus::Result<String>;
 }
 
 // Proxy para la Unidad individual
@@ -49,161 +93,1776 @@ trait SystemdUnit {
     /// Propiedad que indica el estado de carga (loaded, not-found, etc.)
     #[zbus(property)]
     fn load_state(&self) -> zbus::Result<String>;
+
+    /// Fichero a partir del que se generó la unidad; en las de quadlet, el quadlet
+    #[zbus(property)]
+    fn source_path(&self) -> zbus::Result<String>;
+}
+
+// Proxy para el interfaz de servicio de una unidad, con la contabilidad de recursos
+#[proxy(
+    interface = "org.freedesktop.systemd1.Service",
+    default_service = "org.freedesktop.systemd1"
+)]
+trait SystemdService {
+    /// Memoria en uso, en bytes
+    #[zbus(property, name = "MemoryCurrent")]
+    fn memory_current(&self) -> zbus::Result<u64>;
+
+    /// Tiempo de CPU consumido, en nanosegundos
+    #[zbus(property, name = "CPUUsageNSec")]
+    fn cpu_usage_nsec(&self) -> zbus::Result<u64>;
+
+    /// Número de tareas
+    #[zbus(property, name = "TasksCurrent")]
+    fn tasks_current(&self) -> zbus::Result<u64>;
+
+    /// Código de salida del proceso principal
+    #[zbus(property, name = "ExecMainStatus")]
+    fn exec_main_status(&self) -> zbus::Result<i32>;
+
+    /// Motivo del último fin de la unidad (success, exit-code, oom-kill, timeout...)
+    #[zbus(property, name = "Result")]
+    fn result(&self) -> zbus::Result<String>;
+}
+
+/// Valida un nombre antes de usarlo para construir una unidad systemd o pasarlo a journalctl.
+/// Solo se permiten `[A-Za-z0-9_.@-]` y no puede empezar por `-` para que no se lea como un flag
+pub fn sanitize_unit_name(name: &str) -> Result<String, AppError> {
+    let valid_chars = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '@' | '-'));
+    if name.is_empty() || name.starts_with('-') || !valid_chars {
+        return Err(AppError::validation_error(
+            "name",
+            "solo se permiten letras, números y '_', '.', '@', '-'",
+        ));
+    }
+    Ok(name.to_string())
+}
+
+/// Unidad de un quadlet para actuar sobre ella o leer sus logs, comprobando antes
+/// que el nombre sea seguro
+pub fn quadlet_unit_name(name: &str, kind: QuadletType) -> Result<String, AppError> {
+    kind.unit_name(&sanitize_unit_name(name)?).ok_or_else(|| {
+        AppError::bad_request(&format!("'{}' no genera ninguna unidad", kind.as_str()))
+    })
+}
+
+/// Devuelve el nombre de la unidad systemd de la que se obtiene el estado de un quadlet,
+/// o `None` si el tipo no genera una unidad con estado. Las de volume, network, image
+/// y build son servicios de un solo uso que terminan al crear el recurso
+pub fn status_unit_name(name: &str, kind: QuadletType) -> Option<String> {
+    match kind {
+        QuadletType::Container | QuadletType::Kube | QuadletType::Pod => kind.unit_name(name),
+        QuadletType::Volume
+        | QuadletType::Network
+        | QuadletType::Image
+        | QuadletType::Build
+        | QuadletType::Any => None,
+    }
 }
 
 /// Función principal para obtener el estado de un Quadlet
-pub async fn get_status(name: &str) -> QuadletStatus {
-    // Los Quadlets generan servicios con el sufijo .service
-    let unit_name = format!("{}.service", name);
+pub async fn get_status(bus: &SystemdBus, name: &str, kind: QuadletType) -> QuadletStatus {
+    // Los tipos sin unidad no necesitan conexión al bus
+    let Some(unit_name) = status_unit_name(name, kind) else {
+        return QuadletStatus::Unknown;
+    };
+    // Un corte momentáneo de D-Bus no debe hacer pasar por parada una unidad en marcha.
+    // Los errores de método son respuestas de systemd: reintentar no las cambia
+    let result = retry_with_backoff(
+        STATUS_ATTEMPTS,
+        STATUS_BACKOFF,
+        |e| !matches!(e, zbus::Error::MethodError(..)),
+        || async {
+            let conn = bus.connection().await?;
+            let manager = SystemdManagerProxy::new(&conn).await?;
+            let unit = SystemdUnitProxy::builder(&conn)
+                .path(manager.load_unit(&unit_name).await?)?
+                .build()
+                .await?;
+            Ok(status_from_states(
+                &unit.load_state().await?,
+                &unit.active_state().await?,
+            ))
+        },
+    )
+    .await;
+    match result {
+        Ok(status) => status,
+        Err(e) => {
+            tracing::warn!("No se pudo consultar el estado de {}: {}", unit_name, e);
+            QuadletStatus::Unknown
+        }
+    }
+}
+
+/// Intentos de `get_status` ante errores de D-Bus y espera antes del primer reintento
+const STATUS_ATTEMPTS: u32 = 3;
+const STATUS_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Estado de habilitación de la unidad de un quadlet: `enabled`, `disabled`, `static`,
+/// `generated`... Las de quadlet suelen ser `generated`. `None` si el tipo no tiene
+/// unidad, no hay bus o systemd no la conoce
+async fn enablement_with(
+    conn: Option<&Connection>,
+    name: &str,
+    kind: QuadletType,
+) -> Option<String> {
+    let unit_name = status_unit_name(name, kind)?;
+    let manager = SystemdManagerProxy::new(conn?).await.ok()?;
+    manager
+        .get_unit_file_state(&unit_name)
+        .await
+        .map_err(|e| tracing::debug!("No se pudo consultar UnitFileState de {}: {}", unit_name, e))
+        .ok()
+}
+
+/// Estado a partir de LoadState y ActiveState. Una unidad `not-found` no es una
+/// unidad parada: systemd aún no la conoce, por ejemplo si falta un daemon-reload
+fn status_from_states(load_state: &str, active_state: &str) -> QuadletStatus {
+    match load_state {
+        "not-found" => QuadletStatus::NotLoaded,
+        _ => status_from_active_state(active_state),
+    }
+}
+
+fn status_from_active_state(state: &str) -> QuadletStatus {
+    match state {
+        "active" | "reloading" | "activating" => QuadletStatus::Active,
+        "inactive" | "deactivating" => QuadletStatus::Inactive,
+        "failed" => QuadletStatus::Failed,
+        _ => QuadletStatus::Unknown,
+    }
+}
+
+/// Estado de un quadlet con el código de salida y el motivo con el que terminó su
+/// servicio. Más caro que `get_status`, pensado para consultar una sola unidad
+pub async fn get_status_detail(bus: &SystemdBus, name: &str, kind: QuadletType) -> StatusDetail {
+    let Some(unit_name) = status_unit_name(name, kind) else {
+        return StatusDetail::without_exit(QuadletStatus::Unknown);
+    };
 
     let result = async {
-        // Conexión al bus de sesión (rootless)
-        let conn = Connection::session().await?;
+        let conn = bus.connection().await?;
         let manager = SystemdManagerProxy::new(&conn).await?;
-
-        // 1. Obtener la ruta de la unidad
         let unit_path = manager.get_unit(&unit_name).await?;
 
-        // 2. Crear un proxy para esa unidad específica
         let unit = SystemdUnitProxy::builder(&conn)
+            .path(unit_path.clone())?
+            .build()
+            .await?;
+        let status = status_from_active_state(&unit.active_state().await?);
+
+        let service = SystemdServiceProxy::builder(&conn)
             .path(unit_path)?
             .build()
             .await?;
+        Ok::<StatusDetail, zbus::Error>(StatusDetail {
+            status,
+            exit_code: service.exec_main_status().await.ok(),
+            result: service.result().await.ok(),
+        })
+    }
+    .await;
+
+    // Igual que en `get_status`: sin bus o sin unidad, inactiva
+    result.unwrap_or_else(|_| StatusDetail::without_exit(QuadletStatus::Inactive))
+}
+
+/// Obtiene el consumo de memoria, CPU y tareas de la unidad de un quadlet.
+/// Si la unidad no existe o no está activa se devuelven valores vacíos en lugar de un error
+pub async fn get_unit_resources(bus: &SystemdBus, name: &str, kind: QuadletType) -> UnitResources {
+    let Some(unit_name) = status_unit_name(name, kind) else {
+        return UnitResources::default();
+    };
+
+    let result = async {
+        let conn = bus.connection().await?;
+        let manager = SystemdManagerProxy::new(&conn).await?;
+        let unit_path = manager.get_unit(&unit_name).await?;
 
-        // 3. Consultar la propiedad ActiveState
-        let state = unit.active_state().await?;
+        let unit = SystemdUnitProxy::builder(&conn)
+            .path(unit_path.clone())?
+            .build()
+            .await?;
+        if unit.active_state().await? != "active" {
+            return Ok(UnitResources::default());
+        }
 
-        Ok::<QuadletStatus, zbus::Error>(match state.as_str() {
-            "active" | "reloading" | "activating" => QuadletStatus::Active,
-            "inactive" | "deactivating" => QuadletStatus::Inactive,
-            "failed" => QuadletStatus::Failed,
-            _ => QuadletStatus::Unknown,
+        let service = SystemdServiceProxy::builder(&conn)
+            .path(unit_path)?
+            .build()
+            .await?;
+        Ok::<UnitResources, zbus::Error>(UnitResources {
+            active: true,
+            memory_bytes: UnitResources::accounted(service.memory_current().await?),
+            cpu_usage_nsec: UnitResources::accounted(service.cpu_usage_nsec().await?),
+            tasks: UnitResources::accounted(service.tasks_current().await?),
         })
     }
     .await;
 
-    // Si hay un error (ej. la unidad no existe), devolvemos Inactive o Unknown
-    result.unwrap_or(QuadletStatus::Inactive)
+    result.unwrap_or_default()
 }
 
-pub async fn monitor_systemd_events(tx: tokio::sync::broadcast::Sender<Quadlet>) -> Result<()> {
-    let conn = Connection::session().await?;
+/// Tiempo durante el que se agrupan los eventos de una ráfaga (ej: un restart)
+const EVENT_DEBOUNCE: Duration = Duration::from_millis(500);
 
-    // Nos suscribimos a los cambios de propiedades del Manager de systemd
-    let proxy = PropertiesProxy::builder(&conn)
-        .destination("org.freedesktop.systemd1")?
-        .path("/org/freedesktop/systemd1")?
-        .build()
-        .await?;
+/// Recoge las unidades que llegan por `stream` durante `window`, agrupando
+/// ráfagas de eventos de la misma unidad en una sola
+async fn collect_burst<S>(first: String, stream: &mut S, window: Duration) -> HashSet<String>
+where
+    S: Stream<Item = String> + Unpin,
+{
+    let mut units = HashSet::from([first]);
+    let deadline = tokio::time::sleep(window);
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            _ = &mut deadline => break,
+            next = stream.next() => match next {
+                Some(unit) => {
+                    units.insert(unit);
+                }
+                None => break,
+            },
+        }
+    }
+    units
+}
 
-    let mut stream = proxy.receive_properties_changed().await?;
+/// Escucha los trabajos que systemd termina y emite el nuevo estado de los quadlets afectados
+pub async fn monitor_systemd_events(
+    bus: SystemdBus,
+    tx: tokio::sync::broadcast::Sender<QuadletInfo>,
+) -> Result<()> {
+    let conn = bus.connection().await?;
+    let manager = SystemdManagerProxy::new(&conn).await?;
 
-    while let Some(_change) = stream.next().await {
-        // Aquí filtramos si el cambio es de una unidad que nos interesa
-        // Por simplicidad, cuando algo cambia, re-escaneamos o enviamos el evento
-        // En una versión pro, extraeríamos qué unidad cambió del cuerpo de la señal
+    // Sin Subscribe systemd no emite las señales de trabajos
+    manager.subscribe().await?;
+    let mut stream = manager
+        .receive_job_removed()
+        .await?
+        .filter_map(|signal| async move { signal.args().ok().map(|args| args.unit.to_string()) })
+        .boxed();
 
-        // Enviamos una señal de "refresco" al canal
-        let _ = tx.send(Quadlet::new("any", "any", None).unwrap());
+    while let Some(unit) = stream.next().await {
+        for unit_name in collect_burst(unit, &mut stream, EVENT_DEBOUNCE).await {
+            // Solo nos interesan las unidades generadas por un quadlet
+            if let Some((name, kind)) = get_quadlet_type(&unit_name).await {
+                let status = get_status(&bus, &name, kind).await;
+                let path = get_quadlet_dir().join(format!("{}.{}", name, kind.as_str()));
+                let _ = tx.send(QuadletInfo {
+                    name,
+                    kind,
+                    status: Some(status),
+                    modified: file_modified(&path).await,
+                    health: None,
+                    enablement: None,
+                    meta: None,
+                });
+            }
+        }
     }
     Ok(())
 }
 
-/// Ejecuta una acción de control sobre un Quadlet
-pub async fn run_unit_action(name: &str, action: &str) -> Result<()> {
-    let unit_name = format!("{}.service", name);
-    let conn = Connection::session().await?;
-    let manager = SystemdManagerProxy::new(&conn).await?;
+/// Espera inicial antes de reiniciar el monitor tras perder la conexión
+const MONITOR_BACKOFF_MIN: Duration = Duration::from_secs(1);
+/// Espera máxima entre reinicios del monitor
+const MONITOR_BACKOFF_MAX: Duration = Duration::from_secs(60);
 
-    match action {
-        "start" => {
-            manager.start_unit(&unit_name, "replace").await?;
+/// Siguiente espera del backoff exponencial, limitada a `MONITOR_BACKOFF_MAX`
+fn next_backoff(current: Duration) -> Duration {
+    (current * 2).min(MONITOR_BACKOFF_MAX)
+}
+
+/// Mantiene vivo `monitor_systemd_events`: si la conexión con D-Bus se cae o no se
+/// puede abrir, lo vuelve a lanzar con backoff exponencial. No termina nunca
+pub async fn supervise_systemd_events(
+    bus: SystemdBus,
+    tx: tokio::sync::broadcast::Sender<QuadletInfo>,
+) {
+    let mut backoff = MONITOR_BACKOFF_MIN;
+    loop {
+        let started = tokio::time::Instant::now();
+        match monitor_systemd_events(bus.clone(), tx.clone()).await {
+            Ok(()) => tracing::warn!("Systemd monitor: conexión cerrada"),
+            Err(e) => tracing::error!("Systemd monitor stopped: {}", e),
         }
-        "stop" => {
-            manager.stop_unit(&unit_name, "replace").await?;
+        // Si ha estado funcionando un buen rato, el fallo no es persistente
+        if started.elapsed() > MONITOR_BACKOFF_MAX {
+            backoff = MONITOR_BACKOFF_MIN;
         }
-        "restart" => {
-            manager.restart_unit(&unit_name, "replace").await?;
+        tracing::info!("Reiniciando el monitor de systemd en {:?}", backoff);
+        tokio::time::sleep(backoff).await;
+        backoff = next_backoff(backoff);
+    }
+}
+
+/// Operaciones de control sobre unidades de systemd.
+/// Abstrae el proxy de D-Bus para poder sustituirlo en los tests
+#[async_trait]
+pub trait UnitManager: Sync {
+    async fn start(&self, unit_name: &str) -> zbus::Result<()>;
+    async fn stop(&self, unit_name: &str) -> zbus::Result<()>;
+    async fn restart(&self, unit_name: &str) -> zbus::Result<()>;
+    /// Estado actual de la unidad; si no existe, inactiva
+    async fn status(&self, unit_name: &str) -> QuadletStatus;
+    async fn daemon_reload(&self) -> zbus::Result<()>;
+    async fn reset_failed(&self, unit_name: &str) -> zbus::Result<()>;
+    /// Devuelve `false` si la unidad no tiene información de instalación
+    async fn enable(&self, unit_name: &str) -> zbus::Result<bool>;
+    async fn disable(&self, unit_name: &str) -> zbus::Result<()>;
+}
+
+#[async_trait]
+impl UnitManager for SystemdManagerProxy<'_> {
+    async fn start(&self, unit_name: &str) -> zbus::Result<()> {
+        self.start_unit(unit_name, "replace").await.map(|_| ())
+    }
+
+    async fn stop(&self, unit_name: &str) -> zbus::Result<()> {
+        self.stop_unit(unit_name, "replace").await.map(|_| ())
+    }
+
+    async fn restart(&self, unit_name: &str) -> zbus::Result<()> {
+        self.restart_unit(unit_name, "replace").await.map(|_| ())
+    }
+
+    async fn status(&self, unit_name: &str) -> QuadletStatus {
+        let result = async {
+            let unit_path = self.get_unit(unit_name).await?;
+            let unit = SystemdUnitProxy::builder(self.inner().connection())
+                .path(unit_path)?
+                .build()
+                .await?;
+            let state = unit.active_state().await?;
+            Ok::<QuadletStatus, zbus::Error>(status_from_active_state(&state))
         }
-        "daemon-reload" => {
-            manager.reload().await?;
+        .await;
+        result.unwrap_or(QuadletStatus::Inactive)
+    }
+
+    async fn daemon_reload(&self) -> zbus::Result<()> {
+        self.reload().await
+    }
+
+    async fn reset_failed(&self, unit_name: &str) -> zbus::Result<()> {
+        self.reset_failed_unit(unit_name).await
+    }
+
+    async fn enable(&self, unit_name: &str) -> zbus::Result<bool> {
+        let (carries_install_info, _changes) =
+            self.enable_unit_files(&[unit_name], false, false).await?;
+        Ok(carries_install_info)
+    }
+
+    async fn disable(&self, unit_name: &str) -> zbus::Result<()> {
+        self.disable_unit_files(&[unit_name], false)
+            .await
+            .map(|_| ())
+    }
+}
+
+/// Error explicativo para las unidades generadas por quadlet, que no se pueden
+/// habilitar o deshabilitar directamente con systemctl
+fn generated_unit_error(unit_name: &str) -> AppError {
+    AppError::SystemdError(format!(
+        "La unidad '{}' es generada por quadlet y no se puede habilitar o deshabilitar \
+         directamente. Añade una sección [Install] con WantedBy=default.target al quadlet",
+        unit_name
+    ))
+}
+
+fn is_generated_unit_error(err: &zbus::Error) -> bool {
+    let message = err.to_string().to_lowercase();
+    message.contains("generated") || message.contains("transient")
+}
+
+/// Estado esperado de una unidad tras aplicarle una acción, sin tocar systemd
+pub fn predict_status(current: QuadletStatus, action: UnitAction) -> QuadletStatus {
+    match action {
+        UnitAction::Start | UnitAction::Restart => QuadletStatus::Active,
+        UnitAction::RestartIfActive => current,
+        UnitAction::Stop => QuadletStatus::Inactive,
+        UnitAction::ResetFailed if current == QuadletStatus::Failed => QuadletStatus::Inactive,
+        UnitAction::Toggle if current == QuadletStatus::Active => QuadletStatus::Inactive,
+        UnitAction::Toggle => QuadletStatus::Active,
+        // daemon-reload, enable y disable no cambian el estado de ejecución
+        _ => current,
+    }
+}
+
+/// Ejecuta una acción sobre una unidad usando el `UnitManager` indicado.
+/// Devuelve `false` si no había nada que hacer (restart-if-active con la unidad parada)
+pub async fn dispatch_unit_action<M: UnitManager + ?Sized>(
+    manager: &M,
+    unit_name: &str,
+    action: UnitAction,
+) -> Result<bool, AppError> {
+    match action {
+        UnitAction::Start => manager.start(unit_name).await?,
+        UnitAction::Stop => manager.stop(unit_name).await?,
+        UnitAction::Restart => manager.restart(unit_name).await?,
+        UnitAction::RestartIfActive => {
+            // Una unidad parada a propósito debe seguir parada
+            if manager.status(unit_name).await != QuadletStatus::Active {
+                return Ok(false);
+            }
+            manager.restart(unit_name).await?
+        }
+        UnitAction::DaemonReload => manager.daemon_reload().await?,
+        UnitAction::ResetFailed => manager.reset_failed(unit_name).await?,
+        UnitAction::Toggle => match manager.status(unit_name).await {
+            QuadletStatus::Active => manager.stop(unit_name).await?,
+            // Una unidad fallida se arranca de nuevo, pero antes se limpia su estado
+            QuadletStatus::Failed => {
+                manager.reset_failed(unit_name).await?;
+                manager.start(unit_name).await?
+            }
+            _ => manager.start(unit_name).await?,
+        },
+        UnitAction::Enable => {
+            match manager.enable(unit_name).await {
+                Ok(true) => {}
+                Ok(false) => return Err(generated_unit_error(unit_name)),
+                Err(e) if is_generated_unit_error(&e) => {
+                    return Err(generated_unit_error(unit_name))
+                }
+                Err(e) => return Err(e.into()),
+            }
+            manager.daemon_reload().await?;
+        }
+        UnitAction::Disable => {
+            match manager.disable(unit_name).await {
+                Ok(()) => {}
+                Err(e) if is_generated_unit_error(&e) => {
+                    return Err(generated_unit_error(unit_name))
+                }
+                Err(e) => return Err(e.into()),
+            }
+            manager.daemon_reload().await?;
         }
-        _ => return Err(anyhow::anyhow!("Acción no soportada: {}", action)),
     }
+    Ok(true)
+}
+
+/// daemon-reload y arranque de una unidad recién guardada, o reinicio si ya estaba
+/// activa para que coja la nueva configuración. El error indica en qué paso se quedó
+pub async fn dispatch_apply<M: UnitManager + ?Sized>(
+    manager: &M,
+    unit_name: &str,
+) -> Result<(), (ApplyStep, AppError)> {
+    manager
+        .daemon_reload()
+        .await
+        .map_err(|e| (ApplyStep::DaemonReload, e.into()))?;
+    let started = if manager.status(unit_name).await == QuadletStatus::Active {
+        manager.restart(unit_name).await
+    } else {
+        manager.start(unit_name).await
+    };
+    started.map_err(|e| (ApplyStep::Start, e.into()))
+}
+
+/// Aplica en systemd un quadlet ya guardado: ver `dispatch_apply`
+pub async fn apply_unit(bus: &SystemdBus, unit_name: &str) -> Result<(), (ApplyStep, AppError)> {
+    let manager = async {
+        let conn = bus.connection().await?;
+        SystemdManagerProxy::new(&conn).await
+    }
+    .await
+    .map_err(|e| (ApplyStep::DaemonReload, e.into()))?;
+    dispatch_apply(&manager, unit_name).await
+}
+
+/// Pide a systemd que vuelva a leer las unidades, por ejemplo tras editar
+/// quadlets fuera de Quadly
+pub async fn daemon_reload(bus: &SystemdBus) -> Result<(), AppError> {
+    let conn = bus.connection().await?;
+    let manager = SystemdManagerProxy::new(&conn).await?;
+    UnitManager::daemon_reload(&manager).await?;
     Ok(())
 }
 
-/// Descubre todos los quadlets disponibles escaneando el directorio de quadlets
-pub async fn discover_quadlets() -> Result<Vec<crate::models::QuadletInfo>> {
-    let quadlet_dir = crate::models::get_quadlet_dir();
-    let mut quadlet_infos = Vec::new();
+/// Ejecuta la misma acción sobre varias unidades a la vez. Un fallo en una unidad
+/// no detiene al resto: cada resultado va en su posición
+async fn dispatch_bulk_action<M: UnitManager + ?Sized>(
+    manager: &M,
+    quadlets: &[(String, QuadletType)],
+    action: UnitAction,
+) -> Vec<BulkActionResult> {
+    let results = futures_util::future::join_all(quadlets.iter().map(|(name, kind)| async move {
+        let unit_name = quadlet_unit_name(name, *kind)?;
+        dispatch_unit_action(manager, &unit_name, action).await
+    }))
+    .await;
+    quadlets
+        .iter()
+        .zip(results)
+        .map(|((name, _), result)| BulkActionResult {
+            name: name.clone(),
+            ok: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+        })
+        .collect()
+}
 
-    // Si el directorio no existe, crear una lista vacía
-    if !quadlet_dir.exists() {
-        return Ok(quadlet_infos);
+/// Ejecuta una acción sobre varios quadlets compartiendo la conexión con systemd.
+/// El tipo de cada uno se busca por su fichero; si no existe se trata como contenedor
+pub async fn run_bulk_action(
+    bus: &SystemdBus,
+    names: &[String],
+    action: UnitAction,
+) -> Result<Vec<BulkActionResult>, AppError> {
+    let dir = get_quadlet_dir();
+    let mut quadlets = Vec::with_capacity(names.len());
+    for name in names {
+        let kind = find_quadlet_kind(&dir, name).await;
+        quadlets.push((name.clone(), kind.unwrap_or(QuadletType::Container)));
     }
+    let conn = bus.connection().await?;
+    let manager = SystemdManagerProxy::new(&conn).await?;
+    Ok(dispatch_bulk_action(&manager, &quadlets, action).await)
+}
+
+/// Unidad systemd en la que corre este proceso: la de `QUADLY_UNIT` o, si no se
+/// indica, la que aparece en `/proc/self/cgroup`. Dentro de un contenedor el cgroup
+/// no la muestra, así que entonces hay que indicarla
+fn own_unit() -> Option<String> {
+    if let Ok(unit) = std::env::var("QUADLY_UNIT") {
+        let unit = unit.trim();
+        if !unit.is_empty() {
+            return Some(unit.to_string());
+        }
+    }
+    let cgroup = std::fs::read_to_string("/proc/self/cgroup").ok()?;
+    unit_from_cgroup(&cgroup)
+}
 
-    // Leer todos los archivos en el directorio de quadlets
-    let mut entries = tokio::fs::read_dir(&quadlet_dir).await?;
+/// Último `.service` de la ruta del cgroup: `0::/user.slice/.../app.slice/quadly.service`
+fn unit_from_cgroup(cgroup: &str) -> Option<String> {
+    cgroup
+        .lines()
+        .filter_map(|line| line.rsplit_once(':').map(|(_, path)| path))
+        .flat_map(|path| path.rsplit('/'))
+        .find(|component| component.ends_with(".service"))
+        .map(str::to_string)
+}
+
+/// Contenedores y pods activos que se pueden parar, con `kind` para quedarse con
+/// uno de los dos. Nunca se incluye `own_unit`: parar Quadly dejaría al operador sin
+/// forma de volver a arrancar nada desde la interfaz
+fn stop_all_targets(
+    quadlets: Vec<(String, QuadletType)>,
+    states: &HashMap<String, QuadletStatus>,
+    kind: Option<QuadletType>,
+    own_unit: Option<&str>,
+) -> Vec<(String, QuadletType)> {
+    quadlets
+        .into_iter()
+        .filter(|(_, k)| matches!(k, QuadletType::Container | QuadletType::Pod))
+        .filter(|(_, k)| kind.is_none_or(|kind| kind == *k))
+        .filter(|(name, k)| {
+            let Some(unit_name) = status_unit_name(name, *k) else {
+                return false;
+            };
+            if own_unit == Some(unit_name.as_str()) {
+                tracing::warn!("stop-all no para {}: es la unidad de Quadly", unit_name);
+                return false;
+            }
+            states.get(&unit_name) == Some(&QuadletStatus::Active)
+        })
+        .collect()
+}
+
+/// Para a la vez todos los contenedores y pods activos (o solo los de `kind`),
+/// salvo el propio Quadly. Devuelve el resultado de cada uno, por orden de nombre
+pub async fn stop_all(
+    bus: &SystemdBus,
+    kind: Option<QuadletType>,
+) -> Result<Vec<BulkActionResult>, AppError> {
+    let mut quadlets = list_quadlets(&get_quadlet_dir()).await?;
+    quadlets.sort_by(|a, b| a.0.cmp(&b.0));
+    let conn = bus.connection().await?;
+    let states = unit_states(&conn).await?;
+    let targets = stop_all_targets(quadlets, &states, kind, own_unit().as_deref());
+    let manager = SystemdManagerProxy::new(&conn).await?;
+    Ok(dispatch_bulk_action(&manager, &targets, UnitAction::Stop).await)
+}
+
+/// Tiempo máximo de una acción sobre una unidad si no se indica `SYSTEMD_TIMEOUT_SECS`
+pub const DEFAULT_UNIT_ACTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Tiempo máximo que se espera a systemd en una acción, de `SYSTEMD_TIMEOUT_SECS`
+fn unit_action_timeout() -> Duration {
+    parse_timeout(std::env::var("SYSTEMD_TIMEOUT_SECS").ok().as_deref())
+}
 
+fn parse_timeout(value: Option<&str>) -> Duration {
+    let Some(value) = value else {
+        return DEFAULT_UNIT_ACTION_TIMEOUT;
+    };
+    value
+        .trim()
+        .parse()
+        .ok()
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs)
+        .unwrap_or_else(|| {
+            tracing::warn!(
+                "SYSTEMD_TIMEOUT_SECS '{}' no es un número de segundos válido, usando {:?}",
+                value,
+                DEFAULT_UNIT_ACTION_TIMEOUT
+            );
+            DEFAULT_UNIT_ACTION_TIMEOUT
+        })
+}
+
+/// Espera a `op` como mucho `limit`. Si systemd está ocupado las llamadas de D-Bus
+/// pueden bloquearse indefinidamente; así la petición termina con un 504
+async fn with_timeout<T>(
+    limit: Duration,
+    what: &str,
+    op: impl std::future::Future<Output = Result<T, AppError>>,
+) -> Result<T, AppError> {
+    tokio::time::timeout(limit, op).await.unwrap_or_else(|_| {
+        Err(AppError::Timeout(format!(
+            "{} no terminó en {:?}",
+            what, limit
+        )))
+    })
+}
+
+/// Ejecuta una acción de control sobre un Quadlet. Devuelve `false` si no se ha
+/// tocado la unidad
+pub async fn run_unit_action(
+    bus: &SystemdBus,
+    name: &str,
+    kind: QuadletType,
+    action: UnitAction,
+) -> Result<bool, AppError> {
+    let unit_name = quadlet_unit_name(name, kind)?;
+    let what = format!("{} en {}", action, unit_name);
+    with_timeout(unit_action_timeout(), &what, async {
+        let conn = bus.connection().await?;
+        let manager = SystemdManagerProxy::new(&conn).await?;
+
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            let current = get_status(bus, name, kind).await;
+            tracing::debug!(
+                "{}: {:?} -> {:?} esperado",
+                what,
+                current,
+                predict_status(current, action)
+            );
+        }
+        dispatch_unit_action(&manager, &unit_name, action).await
+    })
+    .await
+}
+
+/// Devuelve el nombre y el tipo de una entrada del directorio de quadlets, si es un quadlet
+async fn quadlet_from_entry(entry: &DirEntry) -> Option<(String, QuadletType)> {
+    let file_type = entry.file_type().await.ok()?;
+    if !file_type.is_file() {
+        return None;
+    }
+    split_quadlet_file_name(entry.file_name().to_str()?)
+}
+
+/// Separa el nombre de un fichero de quadlet (`web.container`) en nombre y tipo
+pub fn split_quadlet_file_name(file_name: &str) -> Option<(String, QuadletType)> {
+    // Verificar si el archivo tiene una extensión de quadlet válida
+    for ext in [
+        "container",
+        "network",
+        "volume",
+        "kube",
+        "pod",
+        "image",
+        "build",
+    ] {
+        if let Some(name) = file_name.strip_suffix(&format!(".{}", ext)) {
+            let quadlet_type = QuadletType::from_extension(ext)?;
+            return Some((name.to_string(), quadlet_type));
+        }
+    }
+    None
+}
+
+/// Convierte una entrada del directorio de quadlets en un `QuadletInfo`, si es un quadlet
+async fn quadlet_info_from_entry(bus: &SystemdBus, entry: DirEntry) -> Option<QuadletInfo> {
+    let (name, kind) = quadlet_from_entry(&entry).await?;
+    // Containers, pods y kubes generan un servicio systemd del que obtener el estado;
+    // volumes, networks, etc. devuelven Unknown
+    let status = Some(get_status(bus, &name, kind).await);
+    let health = health_of(&name, kind, status).await;
+    let enablement = match status_unit_name(&name, kind) {
+        Some(_) => enablement_with(bus.connection().await.ok().as_ref(), &name, kind).await,
+        None => None,
+    };
+    let modified = entry
+        .metadata()
+        .await
+        .ok()
+        .and_then(|metadata| metadata.modified().ok())
+        .map(Into::into);
+    Some(QuadletInfo {
+        name,
+        kind,
+        status,
+        modified,
+        health,
+        enablement,
+        meta: None,
+    })
+}
+
+/// Lista los quadlets de un directorio, sin estado. Si el directorio no existe la lista está vacía
+pub(super) async fn list_quadlets(dir: &std::path::Path) -> Result<Vec<(String, QuadletType)>> {
+    let mut quadlets = Vec::new();
+    if !dir.exists() {
+        return Ok(quadlets);
+    }
+    let mut entries = tokio::fs::read_dir(dir).await?;
     while let Some(entry) = entries.next_entry().await? {
-        if let Ok(file_type) = entry.file_type().await {
-            if file_type.is_file() {
-                if let Some(file_name) = entry.file_name().to_str() {
-                    // Verificar si el archivo tiene una extensión de quadlet válida
-                    for ext in ["container", "network", "volume", "kube", "pod", "image"] {
-                        if file_name.ends_with(&format!(".{}", ext)) {
-                            let name = file_name.trim_end_matches(&format!(".{}", ext)).to_string();
-
-                            if let Some(quadlet_type) =
-                                crate::models::QuadletType::from_extension(ext)
-                            {
-                                // Para containers, verificar el estado del servicio systemd
-                                let status = if ext == "container" {
-                                    Some(get_status(&name).await)
-                                } else {
-                                    // Para volumes, networks, etc., no tienen servicios systemd asociados
-                                    Some(crate::models::QuadletStatus::Unknown)
-                                };
-
-                                quadlet_infos.push(crate::models::QuadletInfo {
-                                    name,
-                                    kind: quadlet_type,
-                                    status,
-                                });
-                                break; // Salir del bucle de extensiones una vez que se encuentra una
-                            }
-                        }
-                    }
+        if let Some(quadlet) = quadlet_from_entry(&entry).await {
+            quadlets.push(quadlet);
+        }
+    }
+    Ok(quadlets)
+}
+
+/// Número máximo de consultas de estado simultáneas durante el descubrimiento
+const STATUS_CONCURRENCY: usize = 16;
+
+/// Consulta el estado de varios quadlets en paralelo conservando el orden de entrada
+async fn resolve_statuses<F, Fut>(
+    quadlets: Vec<(String, QuadletType)>,
+    lookup: F,
+) -> Vec<QuadletInfo>
+where
+    F: Fn(String, QuadletType) -> Fut,
+    Fut: std::future::Future<Output = QuadletStatus>,
+{
+    futures_util::stream::iter(quadlets)
+        .map(|(name, kind)| {
+            let status = lookup(name.clone(), kind);
+            async move {
+                QuadletInfo {
+                    name,
+                    kind,
+                    status: Some(status.await),
+                    modified: None,
+                    health: None,
+                    enablement: None,
+                    meta: None,
                 }
             }
+        })
+        .buffered(STATUS_CONCURRENCY)
+        .collect()
+        .await
+}
+
+/// Rellena la fecha de modificación de cada quadlet a partir de su fichero
+async fn fill_modified(dir: &std::path::Path, quadlets: &mut [QuadletInfo]) {
+    for quadlet in quadlets {
+        let path = dir.join(format!("{}.{}", quadlet.name, quadlet.kind.as_str()));
+        quadlet.modified = file_modified(&path).await;
+    }
+}
+
+/// Healthcheck de un quadlet: solo los contenedores en ejecución tienen uno que consultar
+async fn health_of(
+    name: &str,
+    kind: QuadletType,
+    status: Option<QuadletStatus>,
+) -> Option<HealthState> {
+    if kind != QuadletType::Container || status != Some(QuadletStatus::Active) {
+        return None;
+    }
+    get_container_health(name).await
+}
+
+/// Rellena el healthcheck de los contenedores en ejecución, en paralelo
+async fn fill_health(quadlets: &mut [QuadletInfo]) {
+    let targets: Vec<_> = quadlets
+        .iter()
+        .map(|q| (q.name.clone(), q.kind, q.status))
+        .collect();
+    let health: Vec<_> = futures_util::stream::iter(targets)
+        .map(|(name, kind, status)| async move { health_of(&name, kind, status).await })
+        .buffered(STATUS_CONCURRENCY)
+        .collect()
+        .await;
+    for (quadlet, health) in quadlets.iter_mut().zip(health) {
+        quadlet.health = health;
+    }
+}
+
+/// Rellena en paralelo el estado de habilitación de los quadlets que tienen unidad
+async fn fill_enablement(conn: Option<&Connection>, quadlets: &mut [QuadletInfo]) {
+    let targets: Vec<_> = quadlets.iter().map(|q| (q.name.clone(), q.kind)).collect();
+    let enablement: Vec<_> = futures_util::stream::iter(targets)
+        .map(|(name, kind)| async move { enablement_with(conn, &name, kind).await })
+        .buffered(STATUS_CONCURRENCY)
+        .collect()
+        .await;
+    for (quadlet, enablement) in quadlets.iter_mut().zip(enablement) {
+        quadlet.enablement = enablement;
+    }
+}
+
+/// Estado activo de todas las unidades cargadas, por nombre, en una sola llamada
+async fn unit_states(conn: &Connection) -> zbus::Result<HashMap<String, QuadletStatus>> {
+    let manager = SystemdManagerProxy::new(conn).await?;
+    Ok(manager
+        .list_units()
+        .await?
+        .into_iter()
+        .map(|(name, _, load_state, active_state, ..)| {
+            (name, status_from_states(&load_state, &active_state))
+        })
+        .collect())
+}
+
+/// Une los quadlets con los estados de `ListUnits` por nombre de unidad. Las unidades
+/// que systemd no tiene cargadas, o todas si no hay bus, se consideran inactivas
+fn join_statuses(
+    quadlets: Vec<(String, QuadletType)>,
+    states: Option<&HashMap<String, QuadletStatus>>,
+) -> Vec<QuadletInfo> {
+    quadlets
+        .into_iter()
+        .map(|(name, kind)| {
+            let status = match (status_unit_name(&name, kind), states) {
+                (None, _) => QuadletStatus::Unknown,
+                (Some(unit_name), Some(states)) => states
+                    .get(&unit_name)
+                    .copied()
+                    .unwrap_or(QuadletStatus::Inactive),
+                (Some(_), None) => QuadletStatus::Inactive,
+            };
+            QuadletInfo {
+                name,
+                kind,
+                status: Some(status),
+                modified: None,
+                health: None,
+                enablement: None,
+                meta: None,
+            }
+        })
+        .collect()
+}
+
+/// Descubre los quadlets de un directorio, obteniendo el estado de todas sus unidades
+/// con una única llamada a systemd. El resultado se ordena por nombre
+async fn discover_quadlets_in(bus: &SystemdBus, dir: &std::path::Path) -> Result<Vec<QuadletInfo>> {
+    let mut quadlets = list_quadlets(dir).await?;
+    quadlets.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Solo abrimos el bus si algún quadlet tiene una unidad de la que obtener el estado
+    let needs_bus = quadlets
+        .iter()
+        .any(|(name, kind)| status_unit_name(name, *kind).is_some());
+    let conn = if needs_bus {
+        bus.connection().await.ok()
+    } else {
+        None
+    };
+    let states = match &conn {
+        Some(conn) => unit_states(conn)
+            .await
+            .map_err(|e| tracing::warn!("Error listing systemd units: {}", e))
+            .ok(),
+        None => None,
+    };
+
+    let mut quadlets = join_statuses(quadlets, states.as_ref());
+    fill_modified(dir, &mut quadlets).await;
+    fill_health(&mut quadlets).await;
+    fill_enablement(conn.as_ref(), &mut quadlets).await;
+    Ok(quadlets)
+}
+
+/// Unidades `(nombre, estado activo, SourcePath)` generadas a partir de un quadlet
+/// cuyo fichero no está entre los `existing`. Quadlet apunta `SourcePath` al fichero
+/// original, así que las demás unidades se descartan
+fn find_orphans(
+    units: Vec<(String, String, String)>,
+    existing: &HashSet<String>,
+) -> Vec<OrphanUnit> {
+    let mut orphans: Vec<_> = units
+        .into_iter()
+        .filter(|(_, _, source_path)| {
+            let file_name = std::path::Path::new(source_path)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or_default();
+            split_quadlet_file_name(file_name).is_some() && !existing.contains(file_name)
+        })
+        .map(|(unit, active_state, source_path)| OrphanUnit {
+            unit,
+            source_path,
+            active_state,
+        })
+        .collect();
+    orphans.sort_by(|a, b| a.unit.cmp(&b.unit));
+    orphans
+}
+
+/// Unidades de podman que siguen cargadas en systemd aunque su quadlet ya no exista
+pub async fn get_orphan_units(bus: &SystemdBus) -> Result<Vec<OrphanUnit>, AppError> {
+    let conn = bus.connection().await?;
+    let manager = SystemdManagerProxy::new(&conn).await?;
+    let services: Vec<_> = manager
+        .list_units()
+        .await?
+        .into_iter()
+        .filter(|unit| unit.0.ends_with(".service"))
+        .collect();
+    let conn = &conn;
+    let units: Vec<_> = futures_util::stream::iter(services)
+        .map(|(name, _, _, active_state, _, _, path, ..)| async move {
+            let unit = SystemdUnitProxy::builder(conn)
+                .path(path)
+                .ok()?
+                .build()
+                .await
+                .ok()?;
+            let source_path = unit.source_path().await.ok()?;
+            Some((name, active_state, source_path))
+        })
+        .buffered(STATUS_CONCURRENCY)
+        .filter_map(|unit| async move { unit })
+        .collect()
+        .await;
+
+    let existing = list_quadlets(&get_quadlet_dir())
+        .await
+        .map_err(|e| AppError::StorageError(format!("Error listing quadlets: {}", e)))?
+        .into_iter()
+        .map(|(name, kind)| format!("{}.{}", name, kind.as_str()))
+        .collect();
+    Ok(find_orphans(units, &existing))
+}
+
+/// Tipos en los que se busca un quadlet cuyo estado se pide sin indicar el tipo
+const KIND_LOOKUP_ORDER: [QuadletType; 7] = [
+    QuadletType::Container,
+    QuadletType::Pod,
+    QuadletType::Kube,
+    QuadletType::Network,
+    QuadletType::Volume,
+    QuadletType::Image,
+    QuadletType::Build,
+];
+
+/// Averigua el tipo de un quadlet buscando su fichero en el directorio de quadlets
+async fn find_quadlet_kind(dir: &std::path::Path, name: &str) -> Option<QuadletType> {
+    for kind in KIND_LOOKUP_ORDER {
+        let path = dir.join(format!("{}.{}", name, kind.as_str()));
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Some(kind);
         }
     }
+    None
+}
+
+/// Obtiene en paralelo el estado de varios quadlets, conservando el orden pedido.
+/// Cada estado se consulta igual que en `get_status`. Si no se indica el tipo se
+/// busca su fichero; los que no existen se devuelven con tipo `Any` y estado `Unknown`
+pub async fn get_statuses(
+    bus: &SystemdBus,
+    targets: Vec<(String, Option<QuadletType>)>,
+) -> Vec<QuadletInfo> {
+    let dir = get_quadlet_dir();
+    let mut quadlets = Vec::with_capacity(targets.len());
+    for (name, kind) in targets {
+        let kind = match kind {
+            Some(kind) => kind,
+            None => find_quadlet_kind(&dir, &name)
+                .await
+                .unwrap_or(QuadletType::Any),
+        };
+        quadlets.push((name, kind));
+    }
+
+    let needs_bus = quadlets
+        .iter()
+        .any(|(name, kind)| status_unit_name(name, *kind).is_some());
+    let conn = if needs_bus {
+        bus.connection().await.ok()
+    } else {
+        None
+    };
 
-    Ok(quadlet_infos)
+    let mut quadlets = resolve_statuses(quadlets, |name, kind| async move {
+        get_status(bus, &name, kind).await
+    })
+    .await;
+    fill_modified(&dir, &mut quadlets).await;
+    fill_health(&mut quadlets).await;
+    fill_enablement(conn.as_ref(), &mut quadlets).await;
+    quadlets
 }
 
-/// Verifica si un servicio fue generado por un archivo quadlet y devuelve su tipo
-async fn get_quadlet_type(name: &str) -> Option<crate::models::QuadletType> {
-    let quadlet_dir = crate::models::get_quadlet_dir();
-    let extensions = ["container", "network", "volume", "kube", "pod", "image"];
+enum ScanState {
+    Pending(PathBuf),
+    Scanning(ReadDir),
+    Done,
+}
 
-    for ext in extensions {
-        let path = quadlet_dir.join(format!("{}.{}", name, ext));
-        if path.exists() {
-            return crate::models::QuadletType::from_extension(ext);
+/// Escanea un directorio de quadlets de forma incremental, emitiendo cada quadlet
+/// en cuanto se encuentra. Si el directorio no existe el stream está vacío
+pub fn scan_quadlets(
+    bus: SystemdBus,
+    quadlet_dir: PathBuf,
+) -> impl Stream<Item = Result<QuadletInfo>> + Send {
+    let initial = (bus, ScanState::Pending(quadlet_dir));
+    futures_util::stream::unfold(initial, |(bus, state)| async move {
+        let mut entries = match state {
+            ScanState::Pending(dir) => {
+                if !dir.exists() {
+                    return None;
+                }
+                match tokio::fs::read_dir(&dir).await {
+                    Ok(entries) => entries,
+                    Err(e) => return Some((Err(e.into()), (bus, ScanState::Done))),
+                }
+            }
+            ScanState::Scanning(entries) => entries,
+            ScanState::Done => return None,
+        };
+        loop {
+            match entries.next_entry().await {
+                Ok(Some(entry)) => {
+                    if let Some(info) = quadlet_info_from_entry(&bus, entry).await {
+                        return Some((Ok(info), (bus, ScanState::Scanning(entries))));
+                    }
+                }
+                Ok(None) => return None,
+                Err(e) => return Some((Err(e.into()), (bus, ScanState::Done))),
+            }
+        }
+    })
+}
+
+/// Descubre todos los quadlets disponibles como un stream, sin cargarlos todos en memoria
+pub fn discover_quadlets_stream(bus: SystemdBus) -> impl Stream<Item = Result<QuadletInfo>> + Send {
+    scan_quadlets(bus, get_quadlet_dir())
+}
+
+/// Descubre todos los quadlets disponibles escaneando el directorio de quadlets
+pub async fn discover_quadlets(bus: &SystemdBus) -> Result<Vec<QuadletInfo>> {
+    discover_quadlets_in(bus, &get_quadlet_dir()).await
+}
+
+/// Verifica si un servicio fue generado por un archivo quadlet y devuelve el nombre
+/// del quadlet y su tipo
+async fn get_quadlet_type(unit_name: &str) -> Option<(String, QuadletType)> {
+    let quadlet_dir = get_quadlet_dir();
+    let base = unit_name.strip_suffix(".service")?;
+
+    let mut candidates = vec![(base, QuadletType::Container), (base, QuadletType::Kube)];
+    if let Some(pod) = base.strip_suffix("-pod") {
+        candidates.push((pod, QuadletType::Pod));
+    }
+
+    for (name, kind) in candidates {
+        let path = quadlet_dir.join(format!("{}.{}", name, kind.as_str()));
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Some((name.to_string(), kind));
         }
     }
     None
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
+    use crate::models::SystemdScope;
+    use futures_util::TryStreamExt;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_sanitize_unit_name_accepts_valid_names() {
+        for name in ["web", "my-app_2", "app@instance", "nginx.v1"] {
+            assert_eq!(sanitize_unit_name(name).unwrap(), name);
+        }
+    }
+
+    #[test]
+    fn test_sanitize_unit_name_rejects_unsafe_names() {
+        for name in ["foo;rm -rf", "--user", "-f", "", "my app", "a/b", "x$(id)"] {
+            assert!(
+                matches!(sanitize_unit_name(name), Err(AppError::ValidationError(_))),
+                "{:?}",
+                name
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_unit_action_rejects_unsafe_name() {
+        let result = run_unit_action(
+            &SystemdBus::new(SystemdScope::User),
+            "foo;rm -rf",
+            QuadletType::Container,
+            UnitAction::Start,
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+
+    #[test]
+    fn test_quadlet_unit_name_per_type() {
+        let unit = |name: &str, kind| quadlet_unit_name(name, kind).ok();
+        assert_eq!(
+            unit("web", QuadletType::Container).as_deref(),
+            Some("web.service")
+        );
+        assert_eq!(
+            unit("data", QuadletType::Volume).as_deref(),
+            Some("data-volume.service")
+        );
+        assert_eq!(
+            unit("net", QuadletType::Network).as_deref(),
+            Some("net-network.service")
+        );
+        assert_eq!(
+            unit("app", QuadletType::Pod).as_deref(),
+            Some("app-pod.service")
+        );
+        assert_eq!(unit("web", QuadletType::Any), None);
+        assert!(matches!(
+            quadlet_unit_name("../etc", QuadletType::Volume),
+            Err(AppError::ValidationError(_))
+        ));
+    }
+
+    /// `UnitManager` falso que registra las llamadas recibidas. Lo usan también los
+    /// tests de los handlers que necesitan un systemd que falle a propósito
+    #[derive(Default)]
+    pub(crate) struct FakeManager {
+        pub(crate) calls: Mutex<Vec<String>>,
+        pub(crate) generated: bool,
+        pub(crate) active: bool,
+        pub(crate) failed: bool,
+        pub(crate) fail_start: bool,
+    }
+
+    impl FakeManager {
+        fn record(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+
+        fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl UnitManager for FakeManager {
+        async fn start(&self, unit_name: &str) -> zbus::Result<()> {
+            self.record(format!("start {}", unit_name));
+            if self.fail_start {
+                return Err(zbus::Error::Failure(format!(
+                    "Unit {} failed to start",
+                    unit_name
+                )));
+            }
+            Ok(())
+        }
+
+        async fn stop(&self, unit_name: &str) -> zbus::Result<()> {
+            self.record(format!("stop {}", unit_name));
+            Ok(())
+        }
+
+        async fn restart(&self, unit_name: &str) -> zbus::Result<()> {
+            self.record(format!("restart {}", unit_name));
+            Ok(())
+        }
+
+        async fn status(&self, unit_name: &str) -> QuadletStatus {
+            self.record(format!("status {}", unit_name));
+            if self.active {
+                QuadletStatus::Active
+            } else if self.failed {
+                QuadletStatus::Failed
+            } else {
+                QuadletStatus::Inactive
+            }
+        }
+
+        async fn daemon_reload(&self) -> zbus::Result<()> {
+            self.record("daemon-reload".to_string());
+            Ok(())
+        }
+
+        async fn reset_failed(&self, unit_name: &str) -> zbus::Result<()> {
+            self.record(format!("reset-failed {}", unit_name));
+            Ok(())
+        }
+
+        async fn enable(&self, unit_name: &str) -> zbus::Result<bool> {
+            self.record(format!("enable {}", unit_name));
+            if self.generated {
+                return Err(zbus::Error::Failure(format!(
+                    "Unit file {} is transient or generated.",
+                    unit_name
+                )));
+            }
+            Ok(true)
+        }
+
+        async fn disable(&self, unit_name: &str) -> zbus::Result<()> {
+            self.record(format!("disable {}", unit_name));
+            if self.generated {
+                return Err(zbus::Error::Failure(format!(
+                    "Unit file {} is transient or generated.",
+                    unit_name
+                )));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_enable_and_disable() {
+        let manager = FakeManager::default();
+        dispatch_unit_action(&manager, "web.service", UnitAction::Enable)
+            .await
+            .unwrap();
+        dispatch_unit_action(&manager, "web.service", UnitAction::Disable)
+            .await
+            .unwrap();
+        assert_eq!(
+            manager.calls(),
+            vec![
+                "enable web.service",
+                "daemon-reload",
+                "disable web.service",
+                "daemon-reload"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_enable_generated_unit() {
+        let manager = FakeManager {
+            generated: true,
+            ..Default::default()
+        };
+        for action in [UnitAction::Enable, UnitAction::Disable] {
+            match dispatch_unit_action(&manager, "web.service", action).await {
+                Err(AppError::SystemdError(msg)) => assert!(msg.contains("WantedBy=")),
+                other => panic!("se esperaba SystemdError, obtenido {:?}", other),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resources_for_types_without_unit() {
+        let bus = SystemdBus::new(SystemdScope::User);
+        assert_eq!(
+            get_unit_resources(&bus, "data", QuadletType::Volume).await,
+            UnitResources::default()
+        );
+    }
+
+    #[test]
+    fn test_next_backoff_is_capped() {
+        let mut backoff = MONITOR_BACKOFF_MIN;
+        let mut steps = Vec::new();
+        for _ in 0..8 {
+            steps.push(backoff.as_secs());
+            backoff = next_backoff(backoff);
+        }
+        assert_eq!(steps, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+    }
+
+    #[test]
+    fn test_predict_status() {
+        use QuadletStatus::*;
+        let statuses = [Active, Inactive, Failed, Activating, Deactivating, Unknown];
+        for current in statuses {
+            assert_eq!(predict_status(current, UnitAction::Start), Active);
+            assert_eq!(predict_status(current, UnitAction::Restart), Active);
+            assert_eq!(predict_status(current, UnitAction::Stop), Inactive);
+            for action in [
+                UnitAction::RestartIfActive,
+                UnitAction::DaemonReload,
+                UnitAction::Enable,
+                UnitAction::Disable,
+            ] {
+                assert_eq!(predict_status(current, action), current);
+            }
+            let expected = if current == Failed { Inactive } else { current };
+            assert_eq!(predict_status(current, UnitAction::ResetFailed), expected);
+            let expected = if current == Active { Inactive } else { Active };
+            assert_eq!(predict_status(current, UnitAction::Toggle), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_timeout() {
+        assert_eq!(parse_timeout(None), DEFAULT_UNIT_ACTION_TIMEOUT);
+        assert_eq!(parse_timeout(Some("5")), Duration::from_secs(5));
+        for invalid in ["", "0", "-3", "1m"] {
+            assert_eq!(parse_timeout(Some(invalid)), DEFAULT_UNIT_ACTION_TIMEOUT);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_action_times_out() {
+        let slow = async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(true)
+        };
+        match with_timeout(Duration::from_millis(10), "start en web.service", slow).await {
+            Err(AppError::Timeout(msg)) => assert!(msg.contains("start en web.service")),
+            other => panic!("se esperaba Timeout, obtenido {:?}", other),
+        }
+        let fast = async { Ok(true) };
+        assert!(with_timeout(Duration::from_secs(30), "stop", fast)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_toggle_active_unit() {
+        let manager = FakeManager {
+            active: true,
+            ..Default::default()
+        };
+        dispatch_unit_action(&manager, "web.service", UnitAction::Toggle)
+            .await
+            .unwrap();
+        assert_eq!(
+            manager.calls(),
+            vec!["status web.service", "stop web.service"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_toggle_inactive_unit() {
+        let manager = FakeManager::default();
+        dispatch_unit_action(&manager, "web.service", UnitAction::Toggle)
+            .await
+            .unwrap();
+        assert_eq!(
+            manager.calls(),
+            vec!["status web.service", "start web.service"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_toggle_failed_unit() {
+        let manager = FakeManager {
+            failed: true,
+            ..Default::default()
+        };
+        dispatch_unit_action(&manager, "web.service", UnitAction::Toggle)
+            .await
+            .unwrap();
+        assert_eq!(
+            manager.calls(),
+            vec![
+                "status web.service",
+                "reset-failed web.service",
+                "start web.service"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_reset_failed() {
+        let manager = FakeManager::default();
+        dispatch_unit_action(&manager, "web.service", UnitAction::ResetFailed)
+            .await
+            .unwrap();
+        assert_eq!(manager.calls(), vec!["reset-failed web.service"]);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_action_reports_each_unit() {
+        let manager = FakeManager::default();
+        let quadlets = vec![
+            ("web".to_string(), QuadletType::Container),
+            ("../etc".to_string(), QuadletType::Container),
+            ("data".to_string(), QuadletType::Volume),
+        ];
+        let results = dispatch_bulk_action(&manager, &quadlets, UnitAction::Restart).await;
+        assert_eq!(
+            manager.calls(),
+            vec!["restart web.service", "restart data-volume.service"]
+        );
+        assert_eq!(
+            serde_json::to_value(&results).unwrap(),
+            serde_json::json!([
+                {"name": "web", "ok": true, "error": null},
+                {
+                    "name": "../etc",
+                    "ok": false,
+                    "error": results[1].error.clone().unwrap()
+                },
+                {"name": "data", "ok": true, "error": null}
+            ])
+        );
+        assert!(results[1]
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("Error de validación"));
+    }
+
+    #[tokio::test]
+    async fn test_stop_all_stops_only_active_containers_and_pods() {
+        let quadlets = vec![
+            ("app".to_string(), QuadletType::Pod),
+            ("cache".to_string(), QuadletType::Container),
+            ("data".to_string(), QuadletType::Volume),
+            ("quadly".to_string(), QuadletType::Container),
+            ("stack".to_string(), QuadletType::Kube),
+            ("web".to_string(), QuadletType::Container),
+        ];
+        let states: HashMap<String, QuadletStatus> = [
+            ("app-pod.service", QuadletStatus::Active),
+            ("cache.service", QuadletStatus::Inactive),
+            ("data-volume.service", QuadletStatus::Active),
+            ("quadly.service", QuadletStatus::Active),
+            ("stack.service", QuadletStatus::Active),
+            ("web.service", QuadletStatus::Active),
+        ]
+        .into_iter()
+        .map(|(unit, status)| (unit.to_string(), status))
+        .collect();
+
+        let targets = stop_all_targets(quadlets.clone(), &states, None, Some("quadly.service"));
+        let manager = FakeManager::default();
+        let results = dispatch_bulk_action(&manager, &targets, UnitAction::Stop).await;
+        assert_eq!(
+            manager.calls(),
+            vec!["stop app-pod.service", "stop web.service"]
+        );
+        assert!(results.iter().all(|result| result.ok));
+
+        let pods = stop_all_targets(quadlets, &states, Some(QuadletType::Pod), None);
+        assert_eq!(pods, vec![("app".to_string(), QuadletType::Pod)]);
+    }
+
+    #[test]
+    fn test_unit_from_cgroup() {
+        assert_eq!(
+            unit_from_cgroup(
+                "0::/user.slice/user-1000.slice/user@1000.service/app.slice/quadly.service\n"
+            )
+            .as_deref(),
+            Some("quadly.service")
+        );
+        // Dentro de un contenedor solo se ve la raíz
+        assert_eq!(unit_from_cgroup("0::/\n"), None);
+    }
+
+    #[tokio::test]
+    async fn test_restart_if_active_restarts_running_unit() {
+        let manager = FakeManager {
+            active: true,
+            ..Default::default()
+        };
+        let applied = dispatch_unit_action(&manager, "web.service", UnitAction::RestartIfActive)
+            .await
+            .unwrap();
+        assert!(applied);
+        assert_eq!(
+            manager.calls(),
+            vec!["status web.service", "restart web.service"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restart_if_active_leaves_stopped_unit_alone() {
+        let manager = FakeManager::default();
+        let applied = dispatch_unit_action(&manager, "web.service", UnitAction::RestartIfActive)
+            .await
+            .unwrap();
+        assert!(!applied);
+        assert_eq!(manager.calls(), vec!["status web.service"]);
+    }
+
+    #[tokio::test]
+    async fn test_apply_starts_or_restarts() {
+        let manager = FakeManager::default();
+        dispatch_apply(&manager, "web.service").await.unwrap();
+        let running = FakeManager {
+            active: true,
+            ..Default::default()
+        };
+        dispatch_apply(&running, "web.service").await.unwrap();
+        assert_eq!(
+            manager.calls(),
+            vec!["daemon-reload", "status web.service", "start web.service"]
+        );
+        assert_eq!(
+            running.calls(),
+            vec!["daemon-reload", "status web.service", "restart web.service"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_apply_reports_failed_start() {
+        let manager = FakeManager {
+            fail_start: true,
+            ..Default::default()
+        };
+        let (step, error) = dispatch_apply(&manager, "web.service").await.unwrap_err();
+        assert_eq!(step, ApplyStep::Start);
+        assert!(error.to_string().contains("failed to start"));
+    }
+
+    #[test]
+    fn test_status_from_load_state() {
+        assert_eq!(
+            status_from_states("not-found", "inactive"),
+            QuadletStatus::NotLoaded
+        );
+        assert_eq!(
+            status_from_states("loaded", "inactive"),
+            QuadletStatus::Inactive
+        );
+        assert_eq!(
+            status_from_states("loaded", "active"),
+            QuadletStatus::Active
+        );
+        // Un error al cargar el fichero no oculta que la unidad haya fallado
+        assert_eq!(status_from_states("error", "failed"), QuadletStatus::Failed);
+        assert_eq!(
+            status_from_states("masked", "inactive"),
+            QuadletStatus::Inactive
+        );
+    }
+
+    #[test]
+    fn test_join_statuses_by_unit_name() {
+        let states = HashMap::from([
+            ("web.service".to_string(), QuadletStatus::Active),
+            ("db-pod.service".to_string(), QuadletStatus::Failed),
+            ("sshd.service".to_string(), QuadletStatus::Active),
+        ]);
+        let quadlets = vec![
+            ("web".to_string(), QuadletType::Container),
+            ("db".to_string(), QuadletType::Pod),
+            ("api".to_string(), QuadletType::Container),
+            ("data".to_string(), QuadletType::Volume),
+        ];
+        let statuses: Vec<_> = join_statuses(quadlets.clone(), Some(&states))
+            .into_iter()
+            .map(|q| (q.name, q.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("web".to_string(), Some(QuadletStatus::Active)),
+                ("db".to_string(), Some(QuadletStatus::Failed)),
+                ("api".to_string(), Some(QuadletStatus::Inactive)),
+                ("data".to_string(), Some(QuadletStatus::Unknown)),
+            ]
+        );
+
+        // Sin bus las unidades se consideran paradas
+        let without_bus = join_statuses(quadlets, None);
+        assert_eq!(without_bus[0].status, Some(QuadletStatus::Inactive));
+        assert_eq!(without_bus[3].status, Some(QuadletStatus::Unknown));
+    }
+
+    #[test]
+    fn test_find_orphans() {
+        let unit = |name: &str, state: &str, source: &str| {
+            (name.to_string(), state.to_string(), source.to_string())
+        };
+        let units = vec![
+            unit(
+                "web.service",
+                "active",
+                "/home/u/.config/containers/systemd/web.container",
+            ),
+            unit(
+                "old.service",
+                "active",
+                "/home/u/.config/containers/systemd/old.container",
+            ),
+            unit("db-pod.service", "failed", "/etc/containers/systemd/db.pod"),
+            unit("sshd.service", "active", ""),
+            unit("cron.service", "active", "/etc/cron.conf"),
+        ];
+        let existing = HashSet::from(["web.container".to_string()]);
+        assert_eq!(
+            find_orphans(units, &existing),
+            vec![
+                OrphanUnit {
+                    unit: "db-pod.service".to_string(),
+                    source_path: "/etc/containers/systemd/db.pod".to_string(),
+                    active_state: "failed".to_string(),
+                },
+                OrphanUnit {
+                    unit: "old.service".to_string(),
+                    source_path: "/home/u/.config/containers/systemd/old.container".to_string(),
+                    active_state: "active".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_each_action() {
+        let manager = FakeManager::default();
+        for action in UnitAction::ALL {
+            dispatch_unit_action(&manager, "web.service", action)
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            manager.calls(),
+            vec![
+                "start web.service",
+                "stop web.service",
+                "restart web.service",
+                "status web.service",
+                "daemon-reload",
+                "reset-failed web.service",
+                "enable web.service",
+                "daemon-reload",
+                "disable web.service",
+                "daemon-reload",
+                "status web.service",
+                "start web.service"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_status_unit_name_per_type() {
+        assert_eq!(
+            status_unit_name("web", QuadletType::Container).as_deref(),
+            Some("web.service")
+        );
+        assert_eq!(
+            status_unit_name("stack", QuadletType::Kube).as_deref(),
+            Some("stack.service")
+        );
+        assert_eq!(
+            status_unit_name("app", QuadletType::Pod).as_deref(),
+            Some("app-pod.service")
+        );
+        for kind in [
+            QuadletType::Volume,
+            QuadletType::Network,
+            QuadletType::Image,
+            QuadletType::Build,
+            QuadletType::Any,
+        ] {
+            assert_eq!(status_unit_name("data", kind), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_status_without_unit_is_unknown() {
+        assert_eq!(
+            get_status(
+                &SystemdBus::new(SystemdScope::User),
+                "data",
+                QuadletType::Volume
+            )
+            .await,
+            QuadletStatus::Unknown
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_status_matches_single_status() {
+        let bus = SystemdBus::new(SystemdScope::User);
+        // Sin D-Bus las dos dan Unknown; con D-Bus, NotLoaded. Nunca Inactive
+        let single = get_status(&bus, "batch-missing", QuadletType::Container).await;
+        let batch = get_statuses(
+            &bus,
+            vec![("batch-missing".to_string(), Some(QuadletType::Container))],
+        )
+        .await;
+        assert_eq!(batch[0].status, Some(single));
+        assert_ne!(single, QuadletStatus::Inactive);
+    }
+
+    #[tokio::test]
+    async fn test_get_quadlet_type_from_unit_name() {
+        for file in [
+            "mapped-web.container",
+            "mapped-app.pod",
+            "mapped-stack.kube",
+            "mapped-data.volume",
+        ] {
+            std::fs::write(get_quadlet_dir().join(file), "[Unit]\n").unwrap();
+        }
+
+        assert_eq!(
+            get_quadlet_type("mapped-web.service").await,
+            Some(("mapped-web".to_string(), QuadletType::Container))
+        );
+        assert_eq!(
+            get_quadlet_type("mapped-app-pod.service").await,
+            Some(("mapped-app".to_string(), QuadletType::Pod))
+        );
+        assert_eq!(
+            get_quadlet_type("mapped-stack.service").await,
+            Some(("mapped-stack".to_string(), QuadletType::Kube))
+        );
+        // Los volúmenes no tienen estado, y las unidades ajenas se ignoran
+        assert_eq!(get_quadlet_type("mapped-data.service").await, None);
+        assert_eq!(get_quadlet_type("sshd.service").await, None);
+        assert_eq!(get_quadlet_type("mapped-web.timer").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_collect_burst_deduplicates_units() {
+        let mut stream = futures_util::stream::iter(["web.service", "db.service", "web.service"])
+            .map(str::to_string);
+        let units = collect_burst(
+            "web.service".to_string(),
+            &mut stream,
+            Duration::from_millis(50),
+        )
+        .await;
+        assert_eq!(
+            units,
+            HashSet::from(["web.service".to_string(), "db.service".to_string()])
+        );
+    }
 
     #[tokio::test]
     async fn test_discover_quadlets() {
-        let result = discover_quadlets().await;
+        let dir = tempfile::tempdir().unwrap();
+        for file in [
+            "web.container",
+            "data.volume",
+            "backend.network",
+            "notes.txt",
+        ] {
+            std::fs::write(dir.path().join(file), "[Unit]\n").unwrap();
+        }
+        let result: Result<Vec<_>> = scan_quadlets(
+            SystemdBus::new(SystemdScope::User),
+            dir.path().to_path_buf(),
+        )
+        .try_collect()
+        .await;
 
         match result {
             Ok(quadlets) => {
@@ -215,9 +1874,10 @@ mod tests {
                     );
                 }
                 assert!(
-                    quadlets.len() > 0,
+                    !quadlets.is_empty(),
                     "Deberíamos encontrar al menos algunos quadlets"
                 );
+                assert_eq!(quadlets.len(), 3);
             }
             Err(e) => {
                 println!("Error: {}", e);
@@ -225,4 +1885,58 @@ mod tests {
             }
         }
     }
+
+    #[tokio::test]
+    async fn test_discover_quadlets_sorted_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in ["zeta.volume", "alpha.network", "mid.image", "notes.txt"] {
+            std::fs::write(dir.path().join(file), "[Unit]\n").unwrap();
+        }
+        let quadlets = discover_quadlets_in(&SystemdBus::new(SystemdScope::User), dir.path())
+            .await
+            .unwrap();
+        let names: Vec<_> = quadlets.iter().map(|q| q.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "mid", "zeta"]);
+        assert!(quadlets
+            .iter()
+            .all(|q| q.status == Some(QuadletStatus::Unknown)));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_statuses_runs_concurrently() {
+        let delay = Duration::from_millis(50);
+        let quadlets: Vec<_> = (0..20)
+            .map(|i| (format!("app{:02}", i), QuadletType::Container))
+            .collect();
+
+        let start = std::time::Instant::now();
+        let result = resolve_statuses(quadlets.clone(), |_, _| async move {
+            tokio::time::sleep(delay).await;
+            QuadletStatus::Active
+        })
+        .await;
+        let elapsed = start.elapsed();
+
+        // En serie tardaría 20 * 50ms = 1s
+        assert!(elapsed < delay * 10, "tardó {:?}", elapsed);
+        let names: Vec<_> = result.iter().map(|q| q.name.clone()).collect();
+        let expected: Vec<_> = quadlets.into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, expected);
+        assert!(result
+            .iter()
+            .all(|q| q.status == Some(QuadletStatus::Active)));
+    }
+
+    #[tokio::test]
+    async fn test_scan_missing_directory_is_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let result: Vec<_> = scan_quadlets(
+            SystemdBus::new(SystemdScope::User),
+            dir.path().join("missing"),
+        )
+        .try_collect()
+        .await
+        .unwrap();
+        assert!(result.is_empty());
+    }
 }
@@ -0,0 +1,71 @@
+use anyhow::{anyhow, Context};
+use axum_server::tls_rustls::RustlsConfig;
+use std::path::Path;
+use tracing::warn;
+
+/// Rutas del certificado y la clave de `TLS_CERT` y `TLS_KEY`. Solo se sirve HTTPS
+/// si están las dos; con una sola se avisa y se sigue en HTTP
+pub fn tls_paths(cert: Option<String>, key: Option<String>) -> Option<(String, String)> {
+    match (cert, key) {
+        (Some(cert), Some(key)) => Some((cert, key)),
+        (None, None) => None,
+        _ => {
+            warn!("TLS_CERT and TLS_KEY must both be set to enable TLS");
+            None
+        }
+    }
+}
+
+/// Carga un certificado y su clave en PEM. Los errores indican qué fichero falla
+pub async fn load_tls_config(cert: &Path, key: &Path) -> anyhow::Result<RustlsConfig> {
+    let cert_pem = tokio::fs::read(cert)
+        .await
+        .with_context(|| format!("No se pudo leer el certificado TLS '{}'", cert.display()))?;
+    let key_pem = tokio::fs::read(key)
+        .await
+        .with_context(|| format!("No se pudo leer la clave TLS '{}'", key.display()))?;
+    RustlsConfig::from_pem(cert_pem, key_pem)
+        .await
+        .map_err(|e| {
+            anyhow!(
+                "Certificado '{}' o clave '{}' no válidos: {}",
+                cert.display(),
+                key.display(),
+                e
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tls_paths_need_both() {
+        let path = |p: &str| Some(p.to_string());
+        assert_eq!(
+            tls_paths(path("cert.pem"), path("key.pem")),
+            Some(("cert.pem".to_string(), "key.pem".to_string()))
+        );
+        assert_eq!(tls_paths(path("cert.pem"), None), None);
+        assert_eq!(tls_paths(None, None), None);
+    }
+
+    #[tokio::test]
+    async fn test_load_tls_config_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let cert = dir.path().join("cert.pem");
+        let key = dir.path().join("key.pem");
+
+        let error = load_tls_config(&cert, &key).await.unwrap_err().to_string();
+        assert!(error.contains("certificado"), "{}", error);
+        assert!(error.contains(&cert.display().to_string()), "{}", error);
+
+        std::fs::write(&cert, "no es un certificado").unwrap();
+        let error = load_tls_config(&cert, &key).await.unwrap_err().to_string();
+        assert!(error.contains(&key.display().to_string()), "{}", error);
+
+        std::fs::write(&key, "tampoco es una clave").unwrap();
+        assert!(load_tls_config(&cert, &key).await.is_err());
+    }
+}
@@ -0,0 +1,65 @@
+use crate::models::Versions;
+use std::process::Stdio;
+use tokio::process::Command;
+use tokio::sync::OnceCell;
+
+/// Las versiones no cambian mientras corre el servidor: se consultan una sola vez
+static VERSIONS: OnceCell<Versions> = OnceCell::const_new();
+
+/// Versiones de Quadly, podman y systemd
+pub async fn get_versions() -> Versions {
+    VERSIONS
+        .get_or_init(|| async {
+            let (podman, systemd) = tokio::join!(tool_version("podman"), tool_version("systemctl"));
+            Versions {
+                quadly: env!("CARGO_PKG_VERSION").to_string(),
+                podman,
+                systemd,
+            }
+        })
+        .await
+        .clone()
+}
+
+/// Ejecuta `program --version` y devuelve la versión de la primera línea
+async fn tool_version(program: &str) -> Option<String> {
+    let output = Command::new(program)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_version_line(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Quita el nombre del programa (y "version", si lo hay) de la primera línea:
+/// "podman version 5.2.1" da "5.2.1" y "systemd 255 (255.4-1)" da "255 (255.4-1)"
+fn parse_version_line(output: &str) -> Option<String> {
+    let mut words = output.lines().next()?.split_whitespace().skip(1).peekable();
+    words.next_if_eq(&"version");
+    let version = words.collect::<Vec<_>>().join(" ");
+    (!version.is_empty()).then_some(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_line() {
+        assert_eq!(
+            parse_version_line("podman version 5.2.1\n").as_deref(),
+            Some("5.2.1")
+        );
+        assert_eq!(
+            parse_version_line("systemd 255 (255.4-1ubuntu8)\n+PAM +AUDIT +SELINUX\n").as_deref(),
+            Some("255 (255.4-1ubuntu8)")
+        );
+        assert_eq!(parse_version_line(""), None);
+        assert_eq!(parse_version_line("podman\n"), None);
+        assert_eq!(parse_version_line("podman version \n"), None);
+    }
+}
@@ -0,0 +1,170 @@
+use super::bus::SystemdBus;
+use super::systemd::{get_status, split_quadlet_file_name};
+use crate::models::{file_modified, DiscoverCache, QuadletInfo};
+use anyhow::Result;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::sync::{broadcast, mpsc};
+
+/// Ventana para agrupar las ráfagas de eventos de un editor al guardar
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Primer directorio existente empezando por `dir` y subiendo hacia la raíz
+fn existing_ancestor(dir: &Path) -> Option<&Path> {
+    dir.ancestors().find(|path| path.is_dir())
+}
+
+/// Quadlets afectados por un evento de fichero. Ignora los temporales ocultos
+/// que `Quadlet::save` usa para escribir de forma atómica
+fn changed_quadlets(event: &Event, dir: &Path) -> Vec<PathBuf> {
+    if !matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+        return Vec::new();
+    }
+    event
+        .paths
+        .iter()
+        .filter(|path| path.parent() == Some(dir))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| !name.starts_with('.'))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Vigila el directorio de quadlets para detectar cambios hechos fuera de la API.
+/// Con cada ráfaga de cambios invalida la caché de descubrimiento y publica el
+/// estado de los quadlets afectados. Si el directorio aún no existe vigila el
+/// primer ancestro que exista hasta que se cree
+pub async fn watch_quadlet_dir(
+    dir: PathBuf,
+    bus: SystemdBus,
+    cache: DiscoverCache,
+    tx: broadcast::Sender<QuadletInfo>,
+) -> Result<()> {
+    let (events_tx, mut events) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+        if let Ok(event) = event {
+            let _ = events_tx.send(event);
+        }
+    })?;
+
+    let mut watched = existing_ancestor(&dir)
+        .ok_or_else(|| anyhow::anyhow!("No existe ningún ancestro de {}", dir.display()))?
+        .to_path_buf();
+    watcher.watch(&watched, RecursiveMode::NonRecursive)?;
+
+    while let Some(event) = events.recv().await {
+        if watched != dir {
+            // Se ha creado algún directorio intermedio: bajamos la vigilancia un nivel
+            let Some(ancestor) = existing_ancestor(&dir) else {
+                continue;
+            };
+            if ancestor != watched {
+                let _ = watcher.unwatch(&watched);
+                watched = ancestor.to_path_buf();
+                watcher.watch(&watched, RecursiveMode::NonRecursive)?;
+                if watched == dir {
+                    cache.invalidate().await;
+                }
+            }
+            continue;
+        }
+
+        let mut changed: HashSet<PathBuf> = changed_quadlets(&event, &dir).into_iter().collect();
+        if changed.is_empty() {
+            continue;
+        }
+        while let Ok(Some(event)) = tokio::time::timeout(WATCH_DEBOUNCE, events.recv()).await {
+            changed.extend(changed_quadlets(&event, &dir));
+        }
+
+        cache.invalidate().await;
+        for path in changed {
+            let Some((name, kind)) = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(split_quadlet_file_name)
+            else {
+                continue;
+            };
+            let status = get_status(&bus, &name, kind).await;
+            let _ = tx.send(QuadletInfo {
+                name,
+                kind,
+                status: Some(status),
+                modified: file_modified(&path).await,
+                health: None,
+                enablement: None,
+                meta: None,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{QuadletType, SystemdScope};
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    /// Cuenta cuántas veces la caché tiene que volver a descubrir
+    async fn refreshes(cache: &DiscoverCache, counter: &Arc<AtomicUsize>) -> usize {
+        let shared = counter.clone();
+        cache
+            .get_or_refresh(|| async move {
+                shared.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, ()>(Vec::new())
+            })
+            .await
+            .unwrap();
+        counter.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn test_file_create_invalidates_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiscoverCache::new(Duration::from_secs(60));
+        let (tx, mut rx) = broadcast::channel(16);
+        let counter = Arc::new(AtomicUsize::new(0));
+        assert_eq!(refreshes(&cache, &counter).await, 1);
+
+        let watcher = tokio::spawn(watch_quadlet_dir(
+            dir.path().to_path_buf(),
+            SystemdBus::new(SystemdScope::User),
+            cache.clone(),
+            tx,
+        ));
+        // Dar tiempo a que se instale el watcher
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        std::fs::write(dir.path().join("watched.container"), "[Container]\n").unwrap();
+
+        let info = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("el watcher no publicó el cambio")
+            .unwrap();
+        assert_eq!(info.name, "watched");
+        assert_eq!(info.kind, QuadletType::Container);
+        assert_eq!(refreshes(&cache, &counter).await, 2);
+        watcher.abort();
+    }
+
+    #[test]
+    fn test_existing_ancestor() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("a/b/c");
+        assert_eq!(existing_ancestor(&missing), Some(dir.path()));
+    }
+}
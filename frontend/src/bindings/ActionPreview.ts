@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuadletStatus } from "./QuadletStatus";
+import type { UnitAction } from "./UnitAction";
+
+/**
+ * Resultado de una acción en modo dry-run: lo que pasaría sin ejecutarla
+ */
+export type ActionPreview = { action: UnitAction, 
+/**
+ * Estado actual de la unidad
+ */
+current: QuadletStatus, 
+/**
+ * Estado esperado tras la acción
+ */
+expected: QuadletStatus, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Envoltorio de las respuestas con datos. En OpenAPI aparece como `CustomResponse_<T>`
+ * y en TypeScript como `ApiResponse<T>`. Las respuestas vacías son texto plano
+ */
+export type ApiResponse<T> = { status: number, message: string, data: T, 
+/**
+ * Total de elementos sin paginar, solo en los listados paginados
+ */
+total?: number, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ApplyStep } from "./ApplyStep";
+import type { Quadlet } from "./Quadlet";
+import type { QuadletStatus } from "./QuadletStatus";
+
+/**
+ * Resultado de guardar un quadlet y arrancar su unidad en una sola llamada.
+ * Lo ya hecho antes de un fallo no se deshace
+ */
+export type ApplyResult = { quadlet: Quadlet, 
+/**
+ * Estado de la unidad tras aplicar
+ */
+status: QuadletStatus, 
+/**
+ * Paso que falló después de guardar, si alguno
+ */
+failed_step: ApplyStep | null, error: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Paso de `apply` (guardar, daemon-reload y arrancar) en el que algo falló
+ */
+export type ApplyStep = "daemon-reload" | "start";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Operación que modificó un quadlet o actuó sobre su unidad. `result` es "ok"
+ * o el motivo del fallo
+ */
+export type AuditEntry = { id: bigint, timestamp: string, username: string, action: string, target: string, result: string, };
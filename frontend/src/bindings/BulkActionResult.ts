@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Resultado de una acción en lote para una unidad
+ */
+export type BulkActionResult = { name: string, ok: boolean, 
+/**
+ * Motivo del fallo, si lo hay
+ */
+error: string | null, };
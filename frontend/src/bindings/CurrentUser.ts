@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Role } from "./Role";
+
+/**
+ * Identidad del usuario autenticado, sacada de su token
+ */
+export type CurrentUser = { username: string, role: Role, };
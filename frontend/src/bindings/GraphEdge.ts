@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Referencia de un quadlet a otro a través de `Pod=`, `Network=` o `Volume=`
+ */
+export type GraphEdge = { from: string, to: string, 
+/**
+ * Clave que crea la referencia
+ */
+key: string, };
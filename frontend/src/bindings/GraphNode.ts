@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuadletType } from "./QuadletType";
+
+/**
+ * Quadlet del grafo de dependencias
+ */
+export type GraphNode = { 
+/**
+ * Nombre del fichero, p. ej. "web.container"
+ */
+id: string, name: string, kind: QuadletType, 
+/**
+ * Referenciado por otro quadlet pero sin fichero en disco
+ */
+missing: boolean, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Resultado del healthcheck de podman de un contenedor
+ */
+export type HealthState = "healthy" | "unhealthy" | "starting" | "none";
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Resultado de descargar la imagen de un `.container`
+ */
+export type ImagePull = { image: string, 
+/**
+ * Id de la imagen descargada, si podman lo devolvió
+ */
+id: string | null, 
+/**
+ * Progreso que mostró podman durante la descarga
+ */
+output: string, };
@@ -0,0 +1,22 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Entrada de log de journald
+ */
+export type LogEntry = { 
+/**
+ * Momento en el que se registró la entrada (RFC 3339)
+ */
+timestamp: string, 
+/**
+ * Prioridad syslog (0 = emerg ... 7 = debug)
+ */
+priority: number, 
+/**
+ * Mensaje
+ */
+message: string, 
+/**
+ * Unidad systemd que generó la entrada
+ */
+unit: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Unidad generada por podman cuyo quadlet ya no está en el directorio de quadlets
+ */
+export type OrphanUnit = { 
+/**
+ * Nombre de la unidad systemd (`web.service`)
+ */
+unit: string, 
+/**
+ * Fichero de quadlet del que se generó la unidad
+ */
+source_path: string, 
+/**
+ * Estado de activación según systemd (active, inactive, failed...)
+ */
+active_state: string, };
@@ -1,6 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { QuadletStatus } from "./QuadletStatus";
 import type { QuadletType } from "./QuadletType";
+import type { ValidationError } from "./ValidationError";
 
 export type Quadlet = { 
 /**
@@ -22,4 +23,16 @@ content: string | null,
 /**
  * Status actual del Quadlet
  */
-status: QuadletStatus | null, };
+status: QuadletStatus | null, 
+/**
+ * Hash del contenido leído de disco, para detectar ediciones concurrentes
+ */
+etag: string | null, 
+/**
+ * Última modificación del fichero, si el sistema de ficheros la conoce
+ */
+modified: string | null, 
+/**
+ * Avisos de validación detectados al guardar que no impiden el guardado
+ */
+warnings: Array<ValidationError>, };
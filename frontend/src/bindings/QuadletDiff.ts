@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Diferencias entre el contenido en disco de un quadlet y una edición propuesta
+ */
+export type QuadletDiff = { 
+/**
+ * Diff en formato unificado, vacío si no hay cambios
+ */
+diff: string, has_changes: boolean, };
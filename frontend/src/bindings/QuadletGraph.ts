@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GraphEdge } from "./GraphEdge";
+import type { GraphNode } from "./GraphNode";
+
+/**
+ * Grafo de dependencias entre quadlets
+ */
+export type QuadletGraph = { nodes: Array<GraphNode>, edges: Array<GraphEdge>, 
+/**
+ * Ciclos encontrados, cada uno como la lista de ids que lo forman
+ */
+cycles: Array<Array<string>>, };
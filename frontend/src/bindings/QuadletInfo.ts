@@ -1,4 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HealthState } from "./HealthState";
+import type { QuadletMeta } from "./QuadletMeta";
 import type { QuadletStatus } from "./QuadletStatus";
 import type { QuadletType } from "./QuadletType";
 
@@ -14,4 +16,22 @@ kind: QuadletType,
 /**
  * Status actual del quadlet
  */
-status: QuadletStatus | null, };
+status: QuadletStatus | null, 
+/**
+ * Última modificación del fichero, si el sistema de ficheros la conoce
+ */
+modified: string | null, 
+/**
+ * Healthcheck de podman. Solo para contenedores en ejecución; una unidad
+ * activa puede tener el contenedor unhealthy
+ */
+health: HealthState | null, 
+/**
+ * Estado de habilitación de la unidad (`enabled`, `disabled`, `static`,
+ * `generated`...). Solo para los tipos con unidad systemd
+ */
+enablement: string | null, 
+/**
+ * Etiquetas y descripción guardadas en la base de datos
+ */
+meta: QuadletMeta | null, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuadletType } from "./QuadletType";
+import type { ValidationError } from "./ValidationError";
+
+/**
+ * Resultado de revisar un fichero del directorio de quadlets
+ */
+export type QuadletLint = { name: string, kind: QuadletType, 
+/**
+ * Errores de sintaxis y de validación; vacío si el quadlet es correcto
+ */
+errors: Array<ValidationError>, };
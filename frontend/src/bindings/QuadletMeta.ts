@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Etiquetas y descripción de un quadlet. Se guardan en la base de datos, por
+ * fichero, para no tocar el quadlet
+ */
+export type QuadletMeta = { tags: Array<string>, description: string | null, };
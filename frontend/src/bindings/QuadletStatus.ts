@@ -1,3 +1,3 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type QuadletStatus = "Active" | "Inactive" | "Failed" | "Activating" | "Deactivating" | "Unknown";
+export type QuadletStatus = "Active" | "Inactive" | "Failed" | "Activating" | "Deactivating" | "NotLoaded" | "Unknown";
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuadletStatus } from "./QuadletStatus";
+import type { QuadletType } from "./QuadletType";
+
+/**
+ * Resumen de un quadlet `.container` con sus claves principales ya interpretadas
+ */
+export type QuadletSummary = { name: string, kind: QuadletType, status: QuadletStatus | null, image: string | null, ports: Array<string>, 
+/**
+ * El archivo no se pudo interpretar; `image` y `ports` vienen vacíos
+ */
+parse_error: boolean, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuadletType } from "./QuadletType";
+
+/**
+ * Plantilla de quadlet incluida en Quadly
+ */
+export type QuadletTemplate = { 
+/**
+ * Identificador de la plantilla, p. ej. "basic-container"
+ */
+id: string, 
+/**
+ * Tipo de quadlet que genera
+ */
+kind: QuadletType, description: string, 
+/**
+ * Variables `{{...}}` que hay que rellenar
+ */
+placeholders: Array<string>, 
+/**
+ * Contenido con las marcas `{{...}}` sin sustituir
+ */
+content: string, };
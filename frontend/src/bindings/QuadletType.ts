@@ -3,4 +3,4 @@
 /**
  * Tipo de archivo Quadlet soportado
  */
-export type QuadletType = "container" | "network" | "volume" | "kube" | "pod" | "image" | "any";
+export type QuadletType = "container" | "network" | "volume" | "kube" | "pod" | "image" | "build" | "any";
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Rol de un usuario. Se guarda en la base de datos y en el token en minúsculas
+ */
+export type Role = "admin" | "viewer";
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ServerFeatures } from "./ServerFeatures";
+import type { SystemdScope } from "./SystemdScope";
+
+/**
+ * Capacidades del servidor expuestas al frontend
+ */
+export type ServerConfig = { scope: SystemdScope, quadlet_dir: string, features: ServerFeatures, version: string, };
@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Funcionalidades opcionales que el frontend puede mostrar u ocultar
+ */
+export type ServerFeatures = { 
+/**
+ * Eventos de estado en vivo por Server-Sent Events
+ */
+sse: boolean, 
+/**
+ * Seguimiento de logs en vivo por WebSocket (requiere journalctl)
+ */
+log_stream: boolean, 
+/**
+ * El servidor trabaja con el bus del sistema en lugar del de sesión
+ */
+system_bus: boolean, };
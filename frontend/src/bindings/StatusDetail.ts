@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuadletStatus } from "./QuadletStatus";
+
+/**
+ * Estado de la unidad de un quadlet junto con cómo terminó su proceso principal
+ */
+export type StatusDetail = { status: QuadletStatus, 
+/**
+ * Código de salida del proceso principal (`ExecMainStatus`)
+ */
+exit_code: number | null, 
+/**
+ * Motivo del último fin de la unidad según systemd: `success`, `exit-code`,
+ * `oom-kill`, `timeout`...
+ */
+result: string | null, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Ámbito de systemd sobre el que trabaja Quadly
+ */
+export type SystemdScope = "user" | "system";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Role } from "./Role";
+
+/**
+ * Contenido del JWT de sesión. `iat` y `exp` son segundos desde epoch
+ */
+export type TokenClaims = { sub: string, role: Role, iat: number, exp: number, };
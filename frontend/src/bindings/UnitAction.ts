@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Acción de control sobre la unidad systemd de un quadlet
+ */
+export type UnitAction = "start" | "stop" | "restart" | "restart-if-active" | "daemon-reload" | "reset-failed" | "enable" | "disable" | "toggle";
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { QuadletType } from "./QuadletType";
+
+/**
+ * Errores recientes del journal de la unidad de un quadlet
+ */
+export type UnitErrors = { 
+/**
+ * Unidad systemd (`web.service`)
+ */
+unit: string, 
+/**
+ * Quadlet que genera la unidad
+ */
+name: string, kind: QuadletType, 
+/**
+ * Entradas con prioridad de error o más grave entre las leídas
+ */
+count: number, last_message: string, last_timestamp: string, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Consumo de recursos de la unidad systemd de un quadlet.
+ * Los valores son `None` si la unidad no está activa o systemd no lleva la cuenta
+ */
+export type UnitResources = { 
+/**
+ * La unidad está activa
+ */
+active: boolean, 
+/**
+ * Memoria en uso, en bytes
+ */
+memory_bytes: number | null, 
+/**
+ * Tiempo de CPU consumido, en nanosegundos
+ */
+cpu_usage_nsec: number | null, 
+/**
+ * Número de tareas (procesos e hilos)
+ */
+tasks: number | null, };
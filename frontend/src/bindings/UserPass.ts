@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Credenciales enviadas en el login. `password` va en claro: el hash solo se guarda en `User`
+ */
+export type UserPass = { username: string, password: string, };
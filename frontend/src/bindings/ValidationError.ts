@@ -1,3 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
+/**
+ * Problema encontrado al validar un quadlet. Según el caso se devuelve como
+ * error o como aviso junto al quadlet guardado
+ */
 export type ValidationError = { field: string, message: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Versiones de Quadly y de las herramientas de las que depende. `None` si la
+ * herramienta no está instalada o no se pudo consultar
+ */
+export type Versions = { quadly: string, podman: string | null, systemd: string | null, };